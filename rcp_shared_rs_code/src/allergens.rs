@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use crate::catalogue;
+use crate::models::allergen::Allergen;
+use crate::models::catalogue_entry::CatalogueEntry;
+use crate::models::ingredient::IngredientData;
+use crate::models::recipe::Recipe;
+use crate::terms;
+
+// Rules for common ingredient names in English, maintained by hand
+const BUNDLED_RULES: &str = include_str!("../data/allergens.yml");
+
+/* Why an allergen rules file could not be read. */
+#[derive(Clone, Debug, PartialEq)]
+pub struct AllergenError(pub String);
+
+impl std::fmt::Display for AllergenError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.write_str(&self.0)
+    }
+}
+
+/* Which ingredient names contain an allergen, read from a YAML file like data/allergens.yml:
+ * for every allergen a list of terms the name `contains` and a list of terms that are an `except`ion,
+ * e.g. “butter” contains milk except in “peanut butter”. Terms match whole words, singular or plural. */
+#[derive(Clone, Debug, Default)]
+pub struct AllergenRules {
+    pub rules: Vec<AllergenRule>,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct AllergenRule {
+    #[serde(skip)]
+    pub allergen: Option<Allergen>,
+    pub contains: Vec<String>,
+    pub except: Vec<String>,
+}
+
+impl AllergenRules {
+    /* The rules that come with rcp. */
+    pub fn bundled() -> Self {
+        Self::from_yaml(BUNDLED_RULES).expect("bundled allergen rules are invalid")
+    }
+
+    pub fn from_yaml(yaml: &str) -> Result<Self, AllergenError> {
+        let rules: BTreeMap<String, AllergenRule> = serde_yaml::from_str(yaml)
+            .map_err(|e| AllergenError(format!("Invalid allergen rules: {}", e)))?;
+
+        let mut rules = rules
+            .into_iter()
+            .map(|(name, mut rule)| {
+                let allergen = Allergen::from_str(&name)
+                    .map_err(|_| AllergenError(format!("Unknown allergen {}", name)))?;
+                rule.allergen = Some(allergen);
+                rule.contains = rule
+                    .contains
+                    .iter()
+                    .map(|term| terms::words(term))
+                    .collect();
+                rule.except = rule.except.iter().map(|term| terms::words(term)).collect();
+                Ok(rule)
+            })
+            .collect::<Result<Vec<AllergenRule>, AllergenError>>()?;
+        rules.sort_by_key(|rule| rule.allergen);
+        Ok(Self { rules })
+    }
+
+    /* The allergens the rules find in an ingredient name. */
+    pub fn detect(&self, name: &str) -> Vec<Allergen> {
+        let name = terms::words(name);
+        self.rules
+            .iter()
+            .filter(|rule| terms::matches(&name, &rule.contains, &rule.except))
+            .filter_map(|rule| rule.allergen)
+            .collect()
+    }
+
+    /* The allergens of an ingredient: those declared on it or on the catalogue entry it is linked to,
+     * or else those detected from its name, the canonical name of its entry if it is linked. */
+    pub fn ingredient_allergens(
+        &self,
+        ingredient: &IngredientData,
+        catalogue: &[CatalogueEntry],
+    ) -> Vec<Allergen> {
+        match declared_allergens(ingredient, catalogue) {
+            Some(declared) => {
+                let mut declared = declared.clone();
+                declared.sort();
+                declared.dedup();
+                declared
+            }
+            None => match catalogue::linked_entry(catalogue, ingredient) {
+                Some(entry) => self.detect(&entry.canonical_name),
+                None => self.detect(&ingredient.ingredient_name),
+            },
+        }
+    }
+}
+
+/* The allergens of one ingredient or substitution of a recipe. */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct IngredientAllergens {
+    pub ingredient_name: String,
+    /* The ingredient this one can replace, if it is a substitution. */
+    pub substitution_for: Option<String>,
+    pub allergens: Vec<Allergen>,
+    /* Whether the allergens were declared on the ingredient or its catalogue entry rather than detected from its name. */
+    pub declared: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct RecipeAllergens {
+    /* The allergens of the ingredients. */
+    pub contains: Vec<Allergen>,
+    /* Allergens that only come with a substitution, so the dish contains them if the substitution is used. */
+    pub may_contain: Vec<Allergen>,
+    pub ingredients: Vec<IngredientAllergens>,
+}
+
+impl RecipeAllergens {
+    /* Whether the recipe contains none of the allergens, with any of its substitutions. */
+    pub fn is_free_from(&self, allergens: &[Allergen]) -> bool {
+        !allergens
+            .iter()
+            .any(|a| self.contains.contains(a) || self.may_contain.contains(a))
+    }
+}
+
+/* The allergen declaration of a recipe. Ingredients linked to the catalogue use the allergens and the name of their entry. */
+pub fn recipe_allergens(
+    recipe: &Recipe,
+    rules: &AllergenRules,
+    catalogue: &[CatalogueEntry],
+) -> RecipeAllergens {
+    let mut result = RecipeAllergens::default();
+
+    for ing in recipe.ingredients.iter().flatten() {
+        let allergens = rules.ingredient_allergens(&ing.ingredient, catalogue);
+        result.contains.extend(allergens.iter().copied());
+        result.ingredients.push(IngredientAllergens {
+            ingredient_name: ing.ingredient.ingredient_name.clone(),
+            substitution_for: None,
+            allergens,
+            declared: declared_allergens(&ing.ingredient, catalogue).is_some(),
+        });
+
+        for sub in ing.substitutions.iter() {
+            let allergens = rules.ingredient_allergens(sub, catalogue);
+            result.may_contain.extend(allergens.iter().copied());
+            result.ingredients.push(IngredientAllergens {
+                ingredient_name: sub.ingredient_name.clone(),
+                substitution_for: Some(ing.ingredient.ingredient_name.clone()),
+                allergens,
+                declared: declared_allergens(sub, catalogue).is_some(),
+            });
+        }
+    }
+
+    result.contains.sort();
+    result.contains.dedup();
+    let contains = result.contains.clone();
+    result.may_contain.retain(|a| !contains.contains(a));
+    result.may_contain.sort();
+    result.may_contain.dedup();
+    result
+}
+
+// The allergens declared on the ingredient, or else on the catalogue entry it is linked to
+fn declared_allergens<'a>(
+    ingredient: &'a IngredientData,
+    catalogue: &'a [CatalogueEntry],
+) -> Option<&'a Vec<Allergen>> {
+    ingredient.allergens.as_ref().or_else(|| {
+        catalogue::linked_entry(catalogue, ingredient)?
+            .allergens
+            .as_ref()
+    })
+}
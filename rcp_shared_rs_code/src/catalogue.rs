@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::catalogue_entry::CatalogueEntry;
+use crate::models::ingredient::IngredientData;
+use crate::models::recipe::Recipe;
+use crate::terms;
+
+/* A catalogue entry for a name being typed, see autocomplete. */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Suggestion {
+    pub catalogue_uuid: String,
+    pub canonical_name: String,
+    /* The canonical name or synonym that matched the query. */
+    pub matched: String,
+    pub category: Option<String>,
+}
+
+/* A catalogue entry for an ingredient of a recipe that isn't linked yet, see propose_links. */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LinkProposal {
+    pub recipe_uuid: String,
+    /* Index into the ingredients of the recipe. */
+    pub ingredient_idx: usize,
+    /* Index into the substitutions of the ingredient, None for the ingredient itself. */
+    pub substitution_idx: Option<usize>,
+    pub ingredient_name: String,
+    pub catalogue_uuid: String,
+    pub canonical_name: String,
+}
+
+/* Matches an ingredient name to an entry: one with the same canonical name or synonym, singular or plural,
+ * or else the one with the longest name or synonym contained in it as whole words, so “chopped scallions” is a scallion. */
+pub fn find_entry<'a>(catalogue: &'a [CatalogueEntry], name: &str) -> Option<&'a CatalogueEntry> {
+    let name = terms::words(name);
+    if name.is_empty() {
+        return None;
+    }
+    let names: Vec<(&CatalogueEntry, String)> = catalogue
+        .iter()
+        .flat_map(|entry| {
+            std::iter::once(&entry.canonical_name)
+                .chain(entry.synonyms.iter())
+                .map(move |entry_name| (entry, terms::words(entry_name)))
+        })
+        .filter(|(_, entry_name)| !entry_name.is_empty())
+        .collect();
+
+    names
+        .iter()
+        .find(|(_, entry_name)| *entry_name == name)
+        .or_else(|| {
+            names
+                .iter()
+                .filter(|(_, entry_name)| {
+                    terms::matches(&name, std::slice::from_ref(entry_name), &[])
+                })
+                .max_by_key(|(_, entry_name)| entry_name.len())
+        })
+        .map(|(entry, _)| *entry)
+}
+
+/* The entry an ingredient is linked to. None if it isn't linked or the entry was removed from the catalogue. */
+pub fn linked_entry<'a>(
+    catalogue: &'a [CatalogueEntry],
+    ingredient: &IngredientData,
+) -> Option<&'a CatalogueEntry> {
+    let catalogue_uuid = ingredient.catalogue_uuid.as_deref()?;
+    catalogue
+        .iter()
+        .find(|entry| entry.catalogue_uuid == catalogue_uuid)
+}
+
+/* A copy of the recipe in which linked ingredients and substitutions are named by their canonical name,
+ * for matching them against price lists, aisles, nutrient tables or the pantry. Not meant to be shown. */
+pub fn with_canonical_names(recipe: &Recipe, catalogue: &[CatalogueEntry]) -> Recipe {
+    let mut recipe = recipe.clone();
+    for ing in recipe.ingredients.iter_mut().flatten() {
+        for data in std::iter::once(&mut ing.ingredient).chain(ing.substitutions.iter_mut()) {
+            if let Some(entry) = linked_entry(catalogue, data) {
+                data.ingredient_name = entry.canonical_name.clone();
+            }
+        }
+    }
+    recipe
+}
+
+/* The entries with a canonical name or synonym containing a word that starts with `query`, for completing ingredient names as they are typed.
+ * Names that start with the query come first, then canonical names, then shorter names. Every entry is suggested once. */
+pub fn autocomplete(catalogue: &[CatalogueEntry], query: &str, limit: usize) -> Vec<Suggestion> {
+    let query = normalize(query);
+    if query.is_empty() {
+        return vec![];
+    }
+
+    // (starts with the query, is a synonym, suggestion)
+    let mut suggestions: Vec<(bool, bool, Suggestion)> = vec![];
+    for entry in catalogue.iter() {
+        let names = std::iter::once((false, &entry.canonical_name))
+            .chain(entry.synonyms.iter().map(|synonym| (true, synonym)));
+        let best = names
+            .filter_map(|(synonym, name)| {
+                let normalized = normalize(name);
+                if normalized.starts_with(&query) {
+                    Some((true, synonym, name))
+                } else if normalized.contains(&format!(" {}", query)) {
+                    Some((false, synonym, name))
+                } else {
+                    None
+                }
+            })
+            .min_by_key(|(starts, synonym, name)| (!starts, *synonym, name.len()));
+        if let Some((starts, synonym, name)) = best {
+            suggestions.push((
+                starts,
+                synonym,
+                Suggestion {
+                    catalogue_uuid: entry.catalogue_uuid.clone(),
+                    canonical_name: entry.canonical_name.clone(),
+                    matched: name.trim().to_string(),
+                    category: entry.category.clone(),
+                },
+            ));
+        }
+    }
+
+    suggestions.sort_by_key(|(starts, synonym, suggestion)| {
+        (
+            !starts,
+            *synonym,
+            suggestion.matched.len(),
+            suggestion.matched.to_lowercase(),
+        )
+    });
+    suggestions
+        .into_iter()
+        .take(limit)
+        .map(|(_, _, suggestion)| suggestion)
+        .collect()
+}
+
+/* Finds catalogue entries for the ingredients and substitutions of a recipe that aren't linked to an existing entry, with find_entry.
+ * For linking recipes that were stored before the catalogue, the proposals are meant to be checked before they are applied. */
+pub fn propose_links(recipe: &Recipe, catalogue: &[CatalogueEntry]) -> Vec<LinkProposal> {
+    let mut proposals = vec![];
+    for (ingredient_idx, ing) in recipe.ingredients.iter().flatten().enumerate() {
+        let datas = std::iter::once((None, &ing.ingredient)).chain(
+            ing.substitutions
+                .iter()
+                .enumerate()
+                .map(|(i, s)| (Some(i), s)),
+        );
+        for (substitution_idx, data) in datas {
+            if linked_entry(catalogue, data).is_some() {
+                continue;
+            }
+            if let Some(entry) = find_entry(catalogue, &data.ingredient_name) {
+                proposals.push(LinkProposal {
+                    recipe_uuid: recipe.recipe_uuid.clone(),
+                    ingredient_idx,
+                    substitution_idx,
+                    ingredient_name: data.ingredient_name.clone(),
+                    catalogue_uuid: entry.catalogue_uuid.clone(),
+                    canonical_name: entry.canonical_name.clone(),
+                });
+            }
+        }
+    }
+    proposals
+}
+
+/* The ingredient or substitution a link is meant for. None if the recipe has no ingredient at its index
+ * or the one there has another name, e.g. because the recipe was edited after the link was proposed. */
+pub fn linked_ingredient<'a>(
+    recipe: &'a Recipe,
+    link: &LinkProposal,
+) -> Option<&'a IngredientData> {
+    let ing = recipe.ingredients.as_ref()?.get(link.ingredient_idx)?;
+    let data = match link.substitution_idx {
+        Some(idx) => ing.substitutions.get(idx)?,
+        None => &ing.ingredient,
+    };
+    Some(data).filter(|data| data.ingredient_name == link.ingredient_name)
+}
+
+// Lowercase words separated by single spaces, but not made singular: "spring o" has to stay a prefix of "spring onions"
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
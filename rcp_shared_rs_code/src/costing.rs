@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::ingredient::Amount;
+use crate::models::price_item::PriceItem;
+use crate::models::recipe::Recipe;
+use crate::models::unit::Unit;
+use crate::terms;
+
+/* The cost of one ingredient of a recipe. */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct IngredientCost {
+    pub ingredient_name: String,
+    /* The ingredient name of the matched price, to check the match. */
+    pub price_item: String,
+    /* The amount in the purchase unit of the price, e.g. 0.25 for 250 g of butter bought by the kg. */
+    pub quantity: f64,
+    pub cost: f64,
+}
+
+/* Ingredients that are left out of the total. */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MissingCost {
+    pub ingredient_name: String,
+    pub reason: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RecipeCost {
+    pub total: f64,
+    /* The total divided by the yield, if the recipe has one. */
+    pub per_yield_unit: Option<f64>,
+    /* The unit of the yield, e.g. “servings”. */
+    pub yield_unit: Option<String>,
+    /* The selling price of one yield unit the food cost is calculated for. */
+    pub selling_price: Option<f64>,
+    /* The share of the selling price spent on ingredients, in percent. */
+    pub food_cost_percent: Option<f64>,
+    pub ingredients: Vec<IngredientCost>,
+    /* If not empty, the total is too low. */
+    pub missing: Vec<MissingCost>,
+}
+
+/* Matches an ingredient name to a price: one with the same name, singular or plural,
+ * or else the one with the longest name contained in it as whole words, so “butter” prices “unsalted butter”. */
+pub fn find_price<'a>(prices: &'a [PriceItem], name: &str) -> Option<&'a PriceItem> {
+    let name = terms::words(name);
+    if name.is_empty() {
+        return None;
+    }
+    prices
+        .iter()
+        .find(|item| terms::words(&item.ingredient_name) == name)
+        .or_else(|| {
+            prices
+                .iter()
+                .map(|item| (item, vec![terms::words(&item.ingredient_name)]))
+                .filter(|(_, price_name)| terms::matches(&name, price_name, &[]))
+                .max_by_key(|(_, price_name)| price_name[0].len())
+                .map(|(item, _)| item)
+        })
+}
+
+/* The amount in the purchase unit of the price. Units we don't understand (“bunch”) only match the same unit. */
+fn purchase_quantity(amount: &Amount, item: &PriceItem) -> Option<f64> {
+    match (&amount.unit, &item.purchase_unit) {
+        (Unit::Other(from), Unit::Other(to)) if terms::words(from) == terms::words(to) => {
+            Some(amount.amount)
+        }
+        (from, to) => from.convert(amount.amount, to),
+    }
+}
+
+/* Calculates the ingredient cost of a recipe for the yield at `yield_idx`, with the ingredient amounts for that yield.
+ * Every ingredient costs its share of a pack, grossed up by the trim yield: 1 kg of onions with a trim yield of 90 %
+ * costs as much as 1.11 kg bought. Substitutions are not counted, ingredients without an amount cost nothing.
+ * `selling_price` is the price of one yield unit, e.g. of one serving. */
+pub fn recipe_cost(
+    recipe: &Recipe,
+    yield_idx: usize,
+    prices: &[PriceItem],
+    selling_price: Option<f64>,
+) -> RecipeCost {
+    let mut total = 0.0;
+    let mut ingredients = vec![];
+    let mut missing = vec![];
+
+    for ing in recipe.ingredients.iter().flatten() {
+        let data = &ing.ingredient;
+        let missing_because = |reason: String| MissingCost {
+            ingredient_name: data.ingredient_name.clone(),
+            reason,
+        };
+
+        let item = match find_price(prices, &data.ingredient_name) {
+            Some(item) => item,
+            None => {
+                missing.push(missing_because("not in the price list".to_string()));
+                continue;
+            }
+        };
+        let amount = match data.amounts.get(yield_idx) {
+            Some(amount) if amount.amount > 0.0 => amount,
+            _ => continue,
+        };
+        let quantity = match purchase_quantity(amount, item) {
+            Some(quantity) => quantity,
+            None => {
+                missing.push(missing_because(format!(
+                    "can't convert \"{}\" to the purchase unit \"{}\"",
+                    amount, item.purchase_unit
+                )));
+                continue;
+            }
+        };
+
+        let cost = quantity * item.usable_unit_price();
+        total += cost;
+        ingredients.push(IngredientCost {
+            ingredient_name: data.ingredient_name.clone(),
+            price_item: item.ingredient_name.clone(),
+            quantity,
+            cost,
+        });
+    }
+
+    let recipe_yield = recipe
+        .yields
+        .as_ref()
+        .and_then(|yields| yields.get(yield_idx))
+        .filter(|y| y.amount > 0.0);
+    let per_yield_unit = recipe_yield.map(|y| total / y.amount);
+    let selling_price = selling_price.filter(|price| *price > 0.0);
+    RecipeCost {
+        total,
+        per_yield_unit,
+        yield_unit: recipe_yield.map(|y| y.unit.clone()),
+        selling_price,
+        food_cost_percent: match (per_yield_unit, selling_price) {
+            (Some(cost), Some(price)) => Some(cost / price * 100.0),
+            _ => None,
+        },
+        ingredients,
+        missing,
+    }
+}
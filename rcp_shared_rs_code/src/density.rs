@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+
+use crate::models::catalogue_entry::CatalogueEntry;
+use crate::models::ingredient::{Amount, IngredientData};
+use crate::models::recipe::Recipe;
+use crate::models::unit::{MassUnit, Unit};
+use crate::terms;
+
+// Densities of common baking and cooking ingredients, maintained by hand
+const BUNDLED_DENSITIES: &str = include_str!("../data/densities.yml");
+
+/* Why a densities file could not be read. */
+#[derive(Clone, Debug, PartialEq)]
+pub struct DensityError(pub String);
+
+impl std::fmt::Display for DensityError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.write_str(&self.0)
+    }
+}
+
+/* How many grams a milliliter of an ingredient weighs, read from a YAML file like data/densities.yml
+ * that maps ingredient names to grams per milliliter. Names match whole words, singular or plural, see allergens::AllergenRules. */
+#[derive(Clone, Debug, Default)]
+pub struct Densities {
+    /* (ingredient name as in terms::words, grams per milliliter) */
+    pub densities: Vec<(String, f64)>,
+}
+
+impl Densities {
+    /* The densities that come with rcp. */
+    pub fn bundled() -> Self {
+        Self::from_yaml(BUNDLED_DENSITIES).expect("bundled densities are invalid")
+    }
+
+    pub fn from_yaml(yaml: &str) -> Result<Self, DensityError> {
+        let file: BTreeMap<String, f64> = serde_yaml::from_str(yaml)
+            .map_err(|e| DensityError(format!("Invalid densities: {}", e)))?;
+
+        let mut densities = Self::default();
+        for (name, density) in file.iter() {
+            let key = terms::words(name);
+            if key.is_empty() {
+                return Err(DensityError(
+                    "Density without an ingredient name".to_string(),
+                ));
+            }
+            if !density.is_finite() || *density <= 0.0 {
+                return Err(DensityError(format!(
+                    "Density of {} must be a positive number of grams per milliliter",
+                    name.trim()
+                )));
+            }
+            if densities.densities.iter().any(|(k, _)| *k == key) {
+                return Err(DensityError(format!("{} is listed twice", name.trim())));
+            }
+            densities.densities.push((key, *density));
+        }
+        Ok(densities)
+    }
+
+    /* Adds the densities of `other`, e.g. a file of the user's own, replacing those of the same ingredients. */
+    pub fn extend(&mut self, other: &Densities) {
+        for (name, density) in other.densities.iter() {
+            self.insert(name, *density);
+        }
+    }
+
+    /* Adds the default density of every catalogue entry that has one, under its canonical name and its synonyms,
+     * replacing those of the same ingredients. */
+    pub fn extend_from_catalogue(&mut self, catalogue: &[CatalogueEntry]) {
+        for entry in catalogue.iter() {
+            let density = match entry.default_density {
+                Some(density) if density.is_finite() && density > 0.0 => density,
+                _ => continue,
+            };
+            for name in std::iter::once(&entry.canonical_name).chain(entry.synonyms.iter()) {
+                self.insert(&terms::words(name), density);
+            }
+        }
+    }
+
+    /* The grams per milliliter of an ingredient: those of its name, or else of the longest name contained in it as whole words,
+     * so “sifted cake flour” weighs what cake flour does. */
+    pub fn density_of(&self, ingredient: &IngredientData) -> Option<f64> {
+        let name = terms::words(&ingredient.ingredient_name);
+        if name.is_empty() {
+            return None;
+        }
+        self.densities
+            .iter()
+            .find(|(key, _)| *key == name)
+            .or_else(|| {
+                self.densities
+                    .iter()
+                    .filter(|(key, _)| terms::matches(&name, std::slice::from_ref(key), &[]))
+                    .max_by_key(|(key, _)| key.len())
+            })
+            .map(|(_, density)| *density)
+    }
+
+    /* Converts an amount of an ingredient into `unit`. Volumes and weights are converted into each other
+     * with the ingredient's density, so 2 cups of flour are 251 g. Returns None if the ingredient has no density
+     * or the units measure different things, like a clove and a gram. */
+    pub fn convert(
+        &self,
+        ingredient: &IngredientData,
+        amount: &Amount,
+        unit: &Unit,
+    ) -> Option<Amount> {
+        if let Some(converted) = amount.convert_to(unit) {
+            return Some(converted);
+        }
+        let factor = match (&amount.unit, unit) {
+            (Unit::Volume(from), Unit::Mass(to)) => {
+                from.milliliters() * self.density_of(ingredient)? / to.grams()
+            }
+            (Unit::Mass(from), Unit::Volume(to)) => {
+                from.grams() / self.density_of(ingredient)? / to.milliliters()
+            }
+            _ => return None,
+        };
+        Some(Amount {
+            unit: unit.clone(),
+            ..amount.scaled(factor)
+        })
+    }
+
+    /* The ingredient with every weight and every volume it has a density for in grams.
+     * Counts, units we don't understand and volumes of ingredients without a density are left as they are. */
+    pub fn in_grams(&self, ingredient: &IngredientData) -> IngredientData {
+        let grams = Unit::Mass(MassUnit::Gram);
+        let mut ingredient = ingredient.clone();
+        ingredient.amounts = ingredient
+            .amounts
+            .iter()
+            .map(|amount| {
+                self.convert(&ingredient, amount, &grams)
+                    .unwrap_or_else(|| amount.clone())
+            })
+            .collect();
+        ingredient
+    }
+
+    /* A copy of the recipe with the ingredients and their substitutions in grams, see in_grams. */
+    pub fn recipe_in_grams(&self, recipe: &Recipe) -> Recipe {
+        let mut recipe = recipe.clone();
+        for ing in recipe.ingredients.iter_mut().flatten() {
+            ing.ingredient = self.in_grams(&ing.ingredient);
+            for sub in ing.substitutions.iter_mut() {
+                *sub = self.in_grams(sub);
+            }
+        }
+        recipe
+    }
+
+    // Sets the density of an ingredient name (see terms::words)
+    fn insert(&mut self, name: &str, density: f64) {
+        if name.is_empty() {
+            return;
+        }
+        match self.densities.iter_mut().find(|(key, _)| key == name) {
+            Some(entry) => entry.1 = density,
+            None => self.densities.push((name.to_string(), density)),
+        }
+    }
+}
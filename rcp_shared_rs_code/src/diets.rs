@@ -0,0 +1,186 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use crate::models::allergen::Allergen;
+use crate::models::diet::Diet;
+use crate::models::ingredient::IngredientData;
+use crate::models::recipe::Recipe;
+use crate::terms;
+
+// Categories for common ingredient names in English, maintained by hand
+const BUNDLED_TAXONOMY: &str = include_str!("../data/diets.yml");
+
+/* Why a diet taxonomy could not be read. */
+#[derive(Clone, Debug, PartialEq)]
+pub struct DietError(pub String);
+
+impl std::fmt::Display for DietError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.write_str(&self.0)
+    }
+}
+
+/* Ingredient categories like “meat” or “dairy” and the categories each diet rules out, read from a YAML file like data/diets.yml.
+ * Category terms match whole words of the ingredient name, singular or plural, see allergens::AllergenRules. */
+#[derive(Clone, Debug, Default)]
+pub struct DietTaxonomy {
+    pub categories: Vec<IngredientCategory>,
+    pub diets: BTreeMap<Diet, Vec<String>>,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct IngredientCategory {
+    #[serde(skip)]
+    pub name: String,
+    /* If an ingredient declares its allergens, it is in this category if they contain the allergen, whatever its name. */
+    pub allergen: Option<Allergen>,
+    pub contains: Vec<String>,
+    pub except: Vec<String>,
+    /* Other categories whose terms this one contains as well. */
+    pub include: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct TaxonomyFile {
+    categories: BTreeMap<String, IngredientCategory>,
+    diets: BTreeMap<String, Vec<String>>,
+}
+
+impl DietTaxonomy {
+    /* The taxonomy that comes with rcp. */
+    pub fn bundled() -> Self {
+        Self::from_yaml(BUNDLED_TAXONOMY).expect("bundled diet taxonomy is invalid")
+    }
+
+    pub fn from_yaml(yaml: &str) -> Result<Self, DietError> {
+        let file: TaxonomyFile = serde_yaml::from_str(yaml)
+            .map_err(|e| DietError(format!("Invalid diet taxonomy: {}", e)))?;
+
+        let mut categories = vec![];
+        for (name, category) in file.categories.iter() {
+            let mut category = category.clone();
+            category.name = name.clone();
+            for included in category.include.clone().iter() {
+                let other = file.categories.get(included).ok_or_else(|| {
+                    DietError(format!("Unknown category {} in {}", included, name))
+                })?;
+                category.contains.extend(other.contains.iter().cloned());
+                category.except.extend(other.except.iter().cloned());
+            }
+            category.contains = category.contains.iter().map(|t| terms::words(t)).collect();
+            category.except = category.except.iter().map(|t| terms::words(t)).collect();
+            categories.push(category);
+        }
+
+        let mut diets = BTreeMap::new();
+        for (name, ruled_out) in file.diets.iter() {
+            let diet =
+                Diet::from_str(name).map_err(|_| DietError(format!("Unknown diet {}", name)))?;
+            if let Some(unknown) = ruled_out.iter().find(|c| !file.categories.contains_key(*c)) {
+                return Err(DietError(format!(
+                    "Unknown category {} in {}",
+                    unknown, name
+                )));
+            }
+            diets.insert(diet, ruled_out.clone());
+        }
+
+        Ok(Self { categories, diets })
+    }
+
+    /* The categories of an ingredient, each with the term of the name that put it there. */
+    pub fn categorize(&self, ingredient: &IngredientData) -> Vec<(&IngredientCategory, String)> {
+        let name = terms::words(&ingredient.ingredient_name);
+        self.categories
+            .iter()
+            .filter_map(
+                |category| match (category.allergen, ingredient.allergens.as_ref()) {
+                    (Some(allergen), Some(declared)) => {
+                        if declared.contains(&allergen) {
+                            Some((category, format!("declared {}", allergen).to_lowercase()))
+                        } else {
+                            None
+                        }
+                    }
+                    _ => terms::find(&name, &category.contains, &category.except)
+                        .map(|term| (category, term.to_string())),
+                },
+            )
+            .collect()
+    }
+}
+
+/* An ingredient that rules out a diet. */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DietViolation {
+    pub ingredient_name: String,
+    /* The category of the taxonomy, e.g. “dairy”. */
+    pub category: String,
+    /* What put the ingredient in the category, e.g. “butter”. */
+    pub term: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DietFlag {
+    pub diet: Diet,
+    pub suitable: bool,
+    /* Whether `suitable` was set by hand, see Recipe::diet_overrides. */
+    pub overridden: bool,
+    /* The ingredients that rule the diet out, even if the recipe was marked as suitable by hand. */
+    pub broken_by: Vec<DietViolation>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct RecipeDiets {
+    /* The diets the recipe is suitable for. */
+    pub diets: Vec<Diet>,
+    /* Every diet of the taxonomy with the reason it was or wasn't flagged. */
+    pub flags: Vec<DietFlag>,
+}
+
+/* Derives the diets of a recipe from its ingredients. Substitutions are not considered,
+ * a recipe is only vegan if it is vegan as written. The recipe's diet_overrides win over the ingredients. */
+pub fn recipe_diets(recipe: &Recipe, taxonomy: &DietTaxonomy) -> RecipeDiets {
+    let categorized: Vec<(&IngredientData, Vec<(&IngredientCategory, String)>)> = recipe
+        .ingredients
+        .iter()
+        .flatten()
+        .map(|ing| (&ing.ingredient, taxonomy.categorize(&ing.ingredient)))
+        .collect();
+
+    let mut result = RecipeDiets::default();
+    for (diet, ruled_out) in taxonomy.diets.iter() {
+        let broken_by: Vec<DietViolation> = categorized
+            .iter()
+            .flat_map(|(ingredient, categories)| {
+                categories
+                    .iter()
+                    .filter(|(category, _)| ruled_out.contains(&category.name))
+                    .map(move |(category, term)| DietViolation {
+                        ingredient_name: ingredient.ingredient_name.clone(),
+                        category: category.name.clone(),
+                        term: term.clone(),
+                    })
+            })
+            .collect();
+
+        let overridden = recipe
+            .diet_overrides
+            .as_ref()
+            .and_then(|overrides| overrides.get(diet))
+            .copied();
+        let suitable = overridden.unwrap_or(broken_by.is_empty());
+        if suitable {
+            result.diets.push(*diet);
+        }
+        result.flags.push(DietFlag {
+            diet: *diet,
+            suitable,
+            overridden: overridden.is_some(),
+            broken_by,
+        });
+    }
+    result
+}
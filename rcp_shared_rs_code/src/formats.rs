@@ -0,0 +1,197 @@
+use std::str::FromStr;
+
+use crate::models::recipe_request::RecipeRequest;
+
+#[cfg(feature = "importers")]
+mod archive;
+pub mod cooklang;
+#[cfg(feature = "importers")]
+pub mod html;
+pub mod markdown;
+#[cfg(feature = "importers")]
+pub mod mealie;
+pub mod mealmaster;
+pub mod orf;
+#[cfg(feature = "importers")]
+pub mod paprika;
+pub mod pdf;
+#[cfg(feature = "importers")]
+pub mod recipeml;
+pub mod schema_org;
+#[cfg(feature = "importers")]
+pub mod tandoor;
+
+/* The recipe document formats that can be imported. */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    // Open Recipe Format YAML
+    Orf,
+    // schema.org Recipe JSON-LD
+    SchemaOrg,
+    // A web page with a recipe, see html::from_html
+    Html,
+    // Cooklang, the markup language for recipes (.cook files)
+    Cooklang,
+    // MealMaster text export, can contain many recipes
+    MealMaster,
+    // RecipeML XML, can contain many recipes
+    RecipeMl,
+    // Paprika export, a .paprikarecipes archive or the JSON of one recipe
+    Paprika,
+    // Mealie export, a zip archive or recipe JSON
+    Mealie,
+    // Tandoor export, a zip archive or recipe JSON
+    Tandoor,
+}
+
+impl Format {
+    // Guesses the format from the document itself, for uploads that don't name it
+    pub fn detect(document: &str) -> Format {
+        let start = document.trim_start();
+        if start.starts_with('{') || start.starts_with('[') {
+            detect_json(document)
+        } else if start.starts_with('<') && document.contains("<recipeml") {
+            Format::RecipeMl
+        } else if start.starts_with('<') {
+            Format::Html
+        } else if document
+            .lines()
+            .any(|line| mealmaster::is_recipe_start(line.trim()))
+        {
+            Format::MealMaster
+        } else if looks_like_cooklang(document) {
+            Format::Cooklang
+        } else {
+            Format::Orf
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "orf" | "yaml" | "yml" => Ok(Format::Orf),
+            "schema.org" | "schemaorg" | "jsonld" | "json-ld" | "json" => Ok(Format::SchemaOrg),
+            "html" | "htm" => Ok(Format::Html),
+            "cooklang" | "cook" => Ok(Format::Cooklang),
+            "mealmaster" | "mmf" | "mm" => Ok(Format::MealMaster),
+            "recipeml" | "xml" => Ok(Format::RecipeMl),
+            "paprika" | "paprikarecipes" | "paprikarecipe" => Ok(Format::Paprika),
+            "mealie" => Ok(Format::Mealie),
+            "tandoor" => Ok(Format::Tandoor),
+            _ => Err(()),
+        }
+    }
+}
+
+/* The recipes of a document that can contain several. Every recipe is read on its own,
+ * so one broken recipe doesn't keep the others from being imported. */
+pub type RecipeResults = Vec<Result<RecipeRequest, FormatError>>;
+
+/* Reads a recipe document in the given format, or the detected one if None.
+ * Of documents with several recipes only the first one is read, see import_all. */
+#[cfg(feature = "importers")]
+pub fn import(document: &str, format: Option<Format>) -> Result<RecipeRequest, FormatError> {
+    match format.unwrap_or_else(|| Format::detect(document)) {
+        Format::Orf => orf::from_orf_yaml(document),
+        Format::SchemaOrg => schema_org::from_schema_org_json(document),
+        Format::Html => html::from_html(document, None),
+        Format::Cooklang => cooklang::from_cooklang(document),
+        Format::Paprika => paprika::from_paprika_json(document),
+        Format::Tandoor => tandoor::from_tandoor_json(document),
+        format => import_all(document, Some(format))?.remove(0),
+    }
+}
+
+/* Reads every recipe of a document in the given format, or the detected one if None.
+ * Fails if the document can't be read at all; formats with a single recipe give one result. */
+#[cfg(feature = "importers")]
+pub fn import_all(document: &str, format: Option<Format>) -> Result<RecipeResults, FormatError> {
+    match format.unwrap_or_else(|| Format::detect(document)) {
+        Format::MealMaster => mealmaster::from_mealmaster(document),
+        Format::RecipeMl => recipeml::from_recipeml(document),
+        Format::Mealie => mealie::from_mealie_json(document),
+        format => Ok(vec![Ok(import(document, Some(format))?)]),
+    }
+}
+
+/* Reads every recipe of an uploaded file, which can also be the zip or gzip export of another recipe manager.
+ * Without a format, archives are told apart by the files they contain and everything else is read as text. */
+#[cfg(feature = "importers")]
+pub fn import_bytes(data: &[u8], format: Option<Format>) -> Result<RecipeResults, FormatError> {
+    let format = match format {
+        Some(format) => format,
+        None if archive::is_zip(data) => detect_archive(&archive::zip_names(data))?,
+        None if archive::is_gzip(data) => Format::Paprika,
+        None => return import_all(&String::from_utf8_lossy(data), None),
+    };
+    match format {
+        Format::Paprika => paprika::from_paprika_archive(data),
+        Format::Mealie => mealie::from_mealie_archive(data),
+        Format::Tandoor => tandoor::from_tandoor_archive(data),
+        format => import_all(&archive::utf8(data.to_vec())?, Some(format)),
+    }
+}
+
+// Paprika archives hold .paprikarecipe files, Tandoor a zip or recipe.json per recipe, Mealie a JSON per recipe
+#[cfg(feature = "importers")]
+fn detect_archive(names: &[String]) -> Result<Format, FormatError> {
+    if names.iter().any(|name| name.ends_with(".paprikarecipe")) {
+        Ok(Format::Paprika)
+    } else if names
+        .iter()
+        .any(|name| name.ends_with(".zip") || archive::file_name(name) == "recipe.json")
+    {
+        Ok(Format::Tandoor)
+    } else if names.iter().any(|name| name.ends_with(".json")) {
+        Ok(Format::Mealie)
+    } else {
+        Err(FormatError(
+            "Unknown archive, expected a Paprika, Mealie or Tandoor export".to_string(),
+        ))
+    }
+}
+
+// JSON-LD has an @type, the exports of other recipe managers have their own keys
+fn detect_json(document: &str) -> Format {
+    let has_key = |key: &str| document.contains(&format!("\"{}\"", key));
+    if has_key("@type") || has_key("@context") {
+        Format::SchemaOrg
+    } else if has_key("recipe_ingredient") || has_key("recipeIngredient") {
+        Format::Mealie
+    } else if has_key("working_time") && has_key("steps") {
+        Format::Tandoor
+    } else if has_key("ingredients") && has_key("directions") {
+        Format::Paprika
+    } else {
+        Format::SchemaOrg
+    }
+}
+
+// ORF documents always have a recipe_name key, Cooklang has metadata lines or ingredient markup
+fn looks_like_cooklang(document: &str) -> bool {
+    if document
+        .lines()
+        .any(|line| line.trim_start().starts_with("recipe_name:"))
+    {
+        return false;
+    }
+    document
+        .lines()
+        .any(|line| line.trim_start().starts_with(">>"))
+        || document
+            .split_whitespace()
+            .any(|word| word.len() > 1 && (word.starts_with('@') || word.starts_with('~')))
+}
+
+/* Why a recipe document could not be read. The message is meant to be shown to whoever uploaded the document. */
+#[derive(Clone, Debug, PartialEq)]
+pub struct FormatError(pub String);
+
+impl std::fmt::Display for FormatError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.write_str(&self.0)
+    }
+}
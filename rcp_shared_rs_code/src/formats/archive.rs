@@ -0,0 +1,76 @@
+use flate2::read::GzDecoder;
+use std::io::{Cursor, Read};
+
+use super::FormatError;
+
+// Helpers for the exports of other recipe managers, which come as zip and gzip archives
+
+pub(crate) fn is_zip(data: &[u8]) -> bool {
+    data.starts_with(b"PK\x03\x04")
+}
+
+pub(crate) fn is_gzip(data: &[u8]) -> bool {
+    data.starts_with(&[0x1f, 0x8b])
+}
+
+/* The files of a zip archive as (path, content), without directories. */
+pub(crate) fn read_zip(data: &[u8]) -> Result<Vec<(String, Vec<u8>)>, FormatError> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data))
+        .map_err(|e| FormatError(format!("Invalid zip archive: {}", e)))?;
+
+    let mut files = vec![];
+    for idx in 0..archive.len() {
+        let mut file = archive
+            .by_index(idx)
+            .map_err(|e| FormatError(format!("Invalid zip archive: {}", e)))?;
+        if file.is_dir() {
+            continue;
+        }
+        let mut content = vec![];
+        file.read_to_end(&mut content)
+            .map_err(|e| FormatError(format!("Can't read {}: {}", file.name(), e)))?;
+        files.push((file.name().to_string(), content));
+    }
+    Ok(files)
+}
+
+// Only the names, to tell the exports apart without unpacking them
+pub(crate) fn zip_names(data: &[u8]) -> Vec<String> {
+    match zip::ZipArchive::new(Cursor::new(data)) {
+        Ok(archive) => archive.file_names().map(str::to_string).collect(),
+        Err(_) => vec![],
+    }
+}
+
+pub(crate) fn gunzip(data: &[u8]) -> Result<Vec<u8>, FormatError> {
+    let mut content = vec![];
+    GzDecoder::new(data)
+        .read_to_end(&mut content)
+        .map_err(|e| FormatError(format!("Invalid gzip data: {}", e)))?;
+    Ok(content)
+}
+
+pub(crate) fn utf8(data: Vec<u8>) -> Result<String, FormatError> {
+    String::from_utf8(data).map_err(|_| FormatError("Text is not valid UTF-8".to_string()))
+}
+
+// "recipes/pancakes/images/original.webp" -> "original.webp"
+pub(crate) fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+pub(crate) fn image_mime_type(path: &str) -> Option<&'static str> {
+    let extension = path.rsplit('.').next()?.to_lowercase();
+    match extension.as_str() {
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "png" => Some("image/png"),
+        "webp" => Some("image/webp"),
+        "gif" => Some("image/gif"),
+        _ => None,
+    }
+}
+
+/* Embeds an image file as a data: URL, which is how imported photos are stored in Recipe::image. */
+pub(crate) fn data_url(mime_type: &str, content: &[u8]) -> String {
+    format!("data:{};base64,{}", mime_type, base64::encode(content))
+}
@@ -0,0 +1,631 @@
+use std::str::FromStr;
+
+use super::FormatError;
+use crate::models::duration::Duration;
+use crate::models::ingredient::{Amount, Ingredient, IngredientData};
+use crate::models::r#yield::Yield;
+use crate::models::recipe::Recipe;
+use crate::models::recipe_request::RecipeRequest;
+use crate::models::step::Step;
+use crate::models::timer::Timer;
+use crate::models::unit::Unit;
+use crate::quantity::{self, Quantity};
+
+// Characters that end a single word ingredient, cookware or timer name like "@salt"
+const NAME_TERMINATORS: &str = "@#~{}()[].,;:!?\"'";
+
+/* Reads a recipe written in Cooklang (https://cooklang.org).
+ * Every paragraph becomes a step. `@ingredient{amount%unit}`, `#cookware{}` and `~timer{amount%unit}`
+ * are collected into the ingredients, equipment and step timers, and replaced by their plain text in the step.
+ * Metadata can be given as `>> key: value` lines or as a YAML front matter. */
+pub fn from_cooklang(text: &str) -> Result<RecipeRequest, FormatError> {
+    let text = strip_block_comments(text);
+    let (front_matter, body) = split_front_matter(&text);
+
+    let mut metadata: Vec<(String, String)> =
+        front_matter.map(parse_front_matter).unwrap_or_default();
+    let mut parser = StepParser::default();
+    let mut paragraph: Vec<String> = vec![];
+    let mut notes: Vec<String> = vec![];
+
+    for line in body.lines() {
+        // Metadata values like URLs can contain "--", so only the other lines can have comments
+        let line = match line.trim() {
+            line if line.starts_with(">>") => line,
+            line => strip_line_comment(line).trim(),
+        };
+
+        if let Some(meta) = line.strip_prefix(">>") {
+            if let Some(pos) = meta.find(':') {
+                metadata.push((
+                    meta[..pos].trim().to_string(),
+                    meta[pos + 1..].trim().to_string(),
+                ));
+            }
+        } else if let Some(note) = line.strip_prefix('>') {
+            notes.push(note.trim().to_string());
+        } else if line.is_empty() || line.starts_with('=') {
+            // Blank lines and section headings end a step
+            parser.push_paragraph(&paragraph);
+            paragraph.clear();
+            parser.push_notes(&mut notes);
+        } else {
+            if paragraph.is_empty() {
+                parser.push_notes(&mut notes);
+            }
+            paragraph.push(line.to_string());
+        }
+    }
+    parser.push_paragraph(&paragraph);
+    parser.push_notes(&mut notes);
+
+    if parser.steps.is_empty() && parser.ingredients.is_empty() {
+        return Err(FormatError("No Cooklang recipe found".to_string()));
+    }
+
+    let mut recipe = RecipeRequest {
+        ingredients: some_if_not_empty(parser.ingredients),
+        equipment: some_if_not_empty(parser.equipment),
+        steps: some_if_not_empty(parser.steps),
+        notes: parser.recipe_notes,
+        ..Default::default()
+    };
+    for (key, value) in metadata.into_iter().filter(|(_, value)| !value.is_empty()) {
+        apply_metadata(&mut recipe, &key.to_lowercase(), value);
+    }
+
+    // Every ingredient needs one amount per yield, so a recipe without servings gets a single batch
+    if recipe.yields.is_none() {
+        recipe.yields = Some(vec![Yield {
+            amount: 1.0,
+            unit: "batch".to_string(),
+        }]);
+    }
+    Ok(recipe)
+}
+
+/* Writes a recipe as Cooklang, using the amounts of the first yield.
+ * Ingredients, equipment and timers are marked up where the step text first mentions them;
+ * those no step mentions are listed in a paragraph of their own before the steps. */
+pub fn to_cooklang(recipe: &Recipe) -> String {
+    let mut out = String::new();
+
+    let mut metadata: Vec<(&str, String)> = vec![];
+    if let Some(name) = &recipe.recipe_name {
+        metadata.push(("title", name.clone()));
+    }
+    if let Some(notes) = &recipe.notes {
+        metadata.push((
+            "description",
+            notes.lines().collect::<Vec<&str>>().join(" "),
+        ));
+    }
+    if let Some(authors) = recipe.source_authors.as_ref().filter(|a| !a.is_empty()) {
+        metadata.push(("author", authors.join(", ")));
+    }
+    if let Some(url) = &recipe.source_url {
+        metadata.push(("source", url.clone()));
+    }
+    if let Some(y) = recipe.yields.as_ref().and_then(|yields| yields.first()) {
+        metadata.push((
+            "servings",
+            format!("{} {}", quantity::format_decimal(y.amount), y.unit),
+        ));
+    }
+    let durations = [
+        ("prep time", recipe.prep_time),
+        ("cook time", recipe.cook_time),
+        ("time", recipe.total_time),
+    ];
+    for (key, duration) in durations.iter() {
+        if let Some(duration) = duration {
+            metadata.push((*key, duration.to_string()));
+        }
+    }
+    for (key, value) in metadata
+        .iter()
+        .filter(|(_, value)| !value.trim().is_empty())
+    {
+        out += &format!(">> {}: {}\n", key, value.trim());
+    }
+
+    let ingredients: Vec<&IngredientData> = recipe
+        .ingredients
+        .iter()
+        .flatten()
+        .map(|ing| &ing.ingredient)
+        .collect();
+    let equipment: Vec<&String> = recipe.equipment.iter().flatten().collect();
+    let steps: Vec<&Step> = recipe.steps.iter().flatten().collect();
+
+    let mut ingredient_used = vec![false; ingredients.len()];
+    let mut equipment_used = vec![false; equipment.len()];
+    let mut paragraphs: Vec<String> = vec![];
+
+    for step in steps.iter() {
+        let mut marks: Vec<(usize, usize, String)> = vec![];
+
+        for (idx, ing) in ingredients.iter().enumerate() {
+            if ingredient_used[idx] {
+                continue;
+            }
+            if let Some(range) = find_free(&step.step, &ing.ingredient_name, &marks) {
+                marks.push((range.0, range.1, ingredient_markup(ing)));
+                ingredient_used[idx] = true;
+            }
+        }
+        for (idx, name) in equipment.iter().enumerate() {
+            if let Some(range) = find_free(&step.step, name, &marks) {
+                marks.push((range.0, range.1, format!("#{}{{}}", name)));
+                equipment_used[idx] = true;
+            }
+        }
+
+        let mut unmatched_timers = vec![];
+        for timer in step.timers.iter() {
+            match find_free(&step.step, &timer.to_string(), &marks) {
+                Some(range) => marks.push((range.0, range.1, timer_markup(timer))),
+                None => unmatched_timers.push(timer_markup(timer)),
+            }
+        }
+
+        marks.sort_by_key(|mark| mark.0);
+        let mut text = String::new();
+        let mut pos = 0;
+        for (start, end, markup) in marks {
+            text += &step.step[pos..start];
+            text += &markup;
+            pos = end;
+        }
+        text += &step.step[pos..];
+        for markup in unmatched_timers {
+            text += &format!(" {}", markup);
+        }
+
+        let mut paragraph = text.lines().map(str::trim).collect::<Vec<&str>>().join(" ");
+        paragraph = without_line_comments(&paragraph);
+        // Notes follow their step as a paragraph of "> " lines
+        if let Some(notes) = &step.notes {
+            let notes: Vec<String> = notes
+                .lines()
+                .filter(|note| !note.trim().is_empty())
+                .map(|note| format!("> {}", without_line_comments(note.trim())))
+                .collect();
+            if !notes.is_empty() {
+                paragraph += &format!("\n\n{}", notes.join("\n"));
+            }
+        }
+        paragraphs.push(paragraph);
+    }
+
+    let unmentioned: Vec<String> = ingredients
+        .iter()
+        .zip(ingredient_used.iter())
+        .filter(|(_, used)| !**used)
+        .map(|(ing, _)| ingredient_markup(ing))
+        .chain(
+            equipment
+                .iter()
+                .zip(equipment_used.iter())
+                .filter(|(_, used)| !**used)
+                .map(|(name, _)| format!("#{}{{}}", name)),
+        )
+        .collect();
+    if !unmentioned.is_empty() {
+        paragraphs.insert(0, without_line_comments(&unmentioned.join(", ")));
+    }
+
+    if !out.is_empty() && !paragraphs.is_empty() {
+        out += "\n";
+    }
+    out += &paragraphs.join("\n\n");
+    out += "\n";
+    out
+}
+
+// Collects ingredients, equipment and steps while going through the paragraphs of a recipe
+#[derive(Default)]
+struct StepParser {
+    ingredients: Vec<Ingredient>,
+    equipment: Vec<String>,
+    steps: Vec<Step>,
+    recipe_notes: Option<String>,
+}
+
+impl StepParser {
+    fn push_paragraph(&mut self, lines: &[String]) {
+        if lines.is_empty() {
+            return;
+        }
+        let text = lines.join(" ");
+        if let Some(step) = self.parse_step(&text) {
+            self.steps.push(step);
+        }
+    }
+
+    // Notes belong to the step before them, or to the recipe if there is none yet
+    fn push_notes(&mut self, notes: &mut Vec<String>) {
+        if notes.is_empty() {
+            return;
+        }
+        let text = notes.join("\n");
+        notes.clear();
+        let target = match self.steps.last_mut() {
+            Some(step) => &mut step.notes,
+            None => &mut self.recipe_notes,
+        };
+        *target = Some(match target.take() {
+            Some(existing) => format!("{}\n{}", existing, text),
+            None => text,
+        });
+    }
+
+    // Paragraphs that only list ingredients and cookware, like "@flour{200%g}, #bowl{}", aren't steps
+    fn parse_step(&mut self, text: &str) -> Option<Step> {
+        let mut step = Step::default();
+        let mut plain = String::new();
+        let mut prose = String::new();
+        let mut rest = text;
+
+        while let Some(pos) = rest.find(&['@', '#', '~'][..]) {
+            plain += &rest[..pos];
+            prose += &rest[..pos];
+            let marker = rest[pos..].chars().next().unwrap();
+            let after = &rest[pos + 1..];
+
+            let parsed = match parse_marker(after, marker == '~') {
+                Some(parsed) => parsed,
+                None => {
+                    plain.push(marker);
+                    prose.push(marker);
+                    rest = after;
+                    continue;
+                }
+            };
+            rest = &after[parsed.len..];
+
+            match marker {
+                '@' => {
+                    plain += &parsed.name;
+                    self.add_ingredient(&parsed);
+                }
+                '#' => {
+                    plain += &parsed.name;
+                    if !self
+                        .equipment
+                        .iter()
+                        .any(|name| name.eq_ignore_ascii_case(&parsed.name))
+                    {
+                        self.equipment.push(parsed.name);
+                    }
+                }
+                _ => {
+                    let timer = Timer {
+                        name: if parsed.name.is_empty() {
+                            None
+                        } else {
+                            Some(parsed.name)
+                        },
+                        amount: parsed.quantity.map_or(0.0, |q| q.value),
+                        unit: parsed.unit,
+                    };
+                    plain += &timer.to_string();
+                    step.timers.push(timer);
+                }
+            }
+        }
+        plain += rest;
+        prose += rest;
+
+        if step.timers.is_empty() && !prose.chars().any(char::is_alphanumeric) {
+            return None;
+        }
+        step.step = plain.split_whitespace().collect::<Vec<&str>>().join(" ");
+        Some(step)
+    }
+
+    // Mentioning an ingredient again without an amount refers to the earlier one
+    fn add_ingredient(&mut self, parsed: &Marker) {
+        let known = self.ingredients.iter().any(|ing| {
+            ing.ingredient
+                .ingredient_name
+                .eq_ignore_ascii_case(&parsed.name)
+        });
+        if known && parsed.quantity.is_none() && parsed.unit.is_empty() {
+            return;
+        }
+
+        let mut amount = Amount {
+            unit: Unit::from_str(&parsed.unit).unwrap_or_default(),
+            ..Amount::new()
+        };
+        if let Some(quantity) = parsed.quantity {
+            amount.set_quantity(quantity);
+        }
+        self.ingredients.push(Ingredient {
+            ingredient: IngredientData {
+                ingredient_name: parsed.name.clone(),
+                amounts: vec![amount],
+                processing: parsed.processing.clone().into_iter().collect(),
+                notes: parsed.amount_text.clone(),
+                usda_num: None,
+                allergens: None,
+                catalogue_uuid: None,
+            },
+            substitutions: vec![],
+        });
+    }
+}
+
+// An ingredient, cookware or timer after its marker character
+struct Marker {
+    name: String,
+    quantity: Option<Quantity>,
+    unit: String,
+    // Amounts like "a pinch" that aren't a number
+    amount_text: Option<String>,
+    processing: Option<String>,
+    // Bytes of the text after the marker character that belong to the marker
+    len: usize,
+}
+
+// "@ground black pepper{1%tsp}(freshly ground)", "@salt", "~{25%minutes}"
+fn parse_marker(text: &str, is_timer: bool) -> Option<Marker> {
+    let (name, mut len, braces) = match text.find('{') {
+        Some(open)
+            if !text[..open].contains(|c: char| NAME_TERMINATORS.contains(c))
+                && text[open..].contains('}') =>
+        {
+            let close = open + text[open..].find('}')?;
+            (text[..open].trim(), close + 1, Some(&text[open + 1..close]))
+        }
+        _ => {
+            let end = text
+                .find(|c: char| c.is_whitespace() || NAME_TERMINATORS.contains(c))
+                .unwrap_or(text.len());
+            (&text[..end], end, None)
+        }
+    };
+    if name.is_empty() && (!is_timer || braces.is_none()) {
+        return None;
+    }
+
+    let (amount, unit) = match braces.map(|b| b.find('%').map(|pos| (&b[..pos], &b[pos + 1..]))) {
+        Some(Some((amount, unit))) => (amount.trim(), unit.trim()),
+        Some(None) => (braces.unwrap_or("").trim(), ""),
+        None => ("", ""),
+    };
+    // A trailing "*" marks amounts that don't scale with the servings
+    let amount = amount.trim_end_matches('*').trim();
+    let quantity = Quantity::from_str(amount).ok();
+
+    let mut processing = None;
+    if !is_timer && braces.is_some() && text[len..].starts_with('(') {
+        if let Some(close) = text[len..].find(')') {
+            processing =
+                Some(text[len + 1..len + close].trim().to_string()).filter(|p| !p.is_empty());
+            len += close + 1;
+        }
+    }
+
+    Some(Marker {
+        name: name.to_string(),
+        quantity,
+        unit: unit.to_string(),
+        amount_text: if quantity.is_none() && !amount.is_empty() {
+            Some(amount.to_string())
+        } else {
+            None
+        },
+        processing,
+        len,
+    })
+}
+
+fn apply_metadata(recipe: &mut RecipeRequest, key: &str, value: String) {
+    match key {
+        "title" | "name" => recipe.recipe_name = Some(value),
+        "description" | "introduction" => {
+            recipe.notes = Some(match recipe.notes.take() {
+                Some(notes) => format!("{}\n{}", value, notes),
+                None => value,
+            })
+        }
+        "author" | "authors" | "source.author" => {
+            recipe.source_authors = Some(value.split(',').map(|a| a.trim().to_string()).collect())
+        }
+        "source" | "source.url" | "url" if value.starts_with("http") => {
+            recipe.source_url = Some(value)
+        }
+        "source" | "source.name" => recipe
+            .source_authors
+            .get_or_insert_with(Vec::new)
+            .push(value),
+        "servings" | "serves" | "yield" | "yields" => {
+            recipe.yields = parse_yield(&value).map(|y| vec![y])
+        }
+        "time" | "total time" | "time required" | "duration" => {
+            recipe.total_time = Duration::from_str(&value).ok()
+        }
+        "prep time" | "prep_time" | "time.prep" => {
+            recipe.prep_time = Duration::from_str(&value).ok()
+        }
+        "cook time" | "cook_time" | "time.cook" => {
+            recipe.cook_time = Duration::from_str(&value).ok()
+        }
+        _ => {}
+    }
+}
+
+// "4", "4 servings", "2 loaves"; for "2|4" the first is used
+fn parse_yield(value: &str) -> Option<Yield> {
+    let value = value.split('|').next()?.trim();
+    let mut tokens = value.splitn(2, char::is_whitespace);
+    let amount = Quantity::from_str(tokens.next()?).ok()?.value;
+    let unit = tokens.next().map(str::trim).unwrap_or("");
+    Some(Yield {
+        amount,
+        unit: if unit.is_empty() {
+            "servings".to_string()
+        } else {
+            unit.to_string()
+        },
+    })
+}
+
+fn strip_block_comments(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("[-") {
+        out += &rest[..start];
+        rest = match rest[start..].find("-]") {
+            Some(end) => &rest[start + end + 2..],
+            None => "",
+        };
+    }
+    out += rest;
+    out
+}
+
+fn strip_line_comment(line: &str) -> &str {
+    match line.find("--") {
+        Some(pos) => &line[..pos],
+        None => line,
+    }
+}
+
+// Cooklang has no escape for "--", which starts a comment outside of metadata, so it is written as an em dash
+fn without_line_comments(text: &str) -> String {
+    text.replace("--", "—")
+}
+
+// A document starting with a "---" line may have a YAML front matter up to the next "---" line
+fn split_front_matter(text: &str) -> (Option<&str>, &str) {
+    let trimmed = text.trim_start();
+    if !trimmed.starts_with("---") {
+        return (None, text);
+    }
+    let after = match trimmed.find('\n') {
+        Some(pos) => &trimmed[pos + 1..],
+        None => return (None, text),
+    };
+    let mut offset = 0;
+    for line in after.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return (Some(&after[..offset]), &after[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+    (None, text)
+}
+
+// Nested keys like "time: {prep: 10 min}" are flattened to "time.prep"
+fn parse_front_matter(yaml: &str) -> Vec<(String, String)> {
+    let value: serde_yaml::Value = serde_yaml::from_str(yaml).unwrap_or(serde_yaml::Value::Null);
+    let mut metadata = vec![];
+    flatten_yaml("", &value, &mut metadata);
+    metadata
+}
+
+fn flatten_yaml(prefix: &str, value: &serde_yaml::Value, metadata: &mut Vec<(String, String)>) {
+    use serde_yaml::Value;
+
+    let text = match value {
+        Value::Mapping(map) => {
+            for (key, value) in map {
+                if let Some(key) = yaml_text(key) {
+                    let key = if prefix.is_empty() {
+                        key
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    flatten_yaml(&key, value, metadata);
+                }
+            }
+            return;
+        }
+        Value::Sequence(values) => values
+            .iter()
+            .filter_map(yaml_text)
+            .collect::<Vec<String>>()
+            .join(", "),
+        value => match yaml_text(value) {
+            Some(text) => text,
+            None => return,
+        },
+    };
+    metadata.push((prefix.to_string(), text));
+}
+
+fn yaml_text(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(s) => Some(s.trim().to_string()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        serde_yaml::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+fn ingredient_markup(ing: &IngredientData) -> String {
+    let mut markup = format!("@{}{{", ing.ingredient_name);
+    if let Some(amount) = ing.amounts.first().filter(|a| a.amount > 0.0) {
+        markup += &quantity::format_decimal(amount.amount);
+        if let Some(max) = amount.amount_max {
+            markup += &format!("-{}", quantity::format_decimal(max));
+        }
+        let unit = amount.unit.to_string();
+        if !unit.is_empty() {
+            markup += &format!("%{}", unit);
+        }
+    } else if let Some(notes) = &ing.notes {
+        // Amounts like "a pinch" are kept in the notes when reading Cooklang
+        markup += notes;
+    }
+    markup.push('}');
+    if !ing.processing.is_empty() {
+        markup += &format!("({})", ing.processing.join(", "));
+    }
+    markup
+}
+
+fn timer_markup(timer: &Timer) -> String {
+    format!(
+        "~{}{{{}%{}}}",
+        timer.name.as_deref().unwrap_or(""),
+        quantity::format_decimal(timer.amount),
+        timer.unit
+    )
+}
+
+// First case-insensitive occurrence of `word` as a whole word, outside the already marked ranges
+fn find_free(text: &str, word: &str, marks: &[(usize, usize, String)]) -> Option<(usize, usize)> {
+    let word = word.trim();
+    if word.is_empty() {
+        return None;
+    }
+    let lower = word.to_lowercase();
+    let is_word_char = |c: Option<char>| matches!(c, Some(c) if c.is_alphanumeric());
+
+    text.char_indices()
+        .map(|(start, _)| start)
+        .find_map(|start| {
+            let end = start + word.len();
+            let candidate = text.get(start..end)?;
+            if candidate.to_lowercase() != lower
+                || is_word_char(text[..start].chars().next_back())
+                || is_word_char(text[end..].chars().next())
+                || marks.iter().any(|(s, e, _)| start < *e && *s < end)
+            {
+                return None;
+            }
+            Some((start, end))
+        })
+}
+
+fn some_if_not_empty<T>(list: Vec<T>) -> Option<Vec<T>> {
+    if list.is_empty() {
+        None
+    } else {
+        Some(list)
+    }
+}
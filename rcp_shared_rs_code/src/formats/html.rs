@@ -0,0 +1,261 @@
+use scraper::{ElementRef, Html, Selector};
+use serde_json::{json, Map, Value};
+
+use super::schema_org;
+use super::FormatError;
+use crate::models::recipe_request::RecipeRequest;
+
+// Words in class names, ids and headings of ingredient and instruction lists on sites without structured data
+const INGREDIENT_HINTS: [&str; 2] = ["ingredient", "zutat"];
+const INSTRUCTION_HINTS: [&str; 7] = [
+    "instruction",
+    "direction",
+    "method",
+    "step",
+    "preparation",
+    "zubereitung",
+    "anleitung",
+];
+
+/* Reads a recipe from a web page, e.g. one saved from a recipe site. Works without network access.
+ * Embedded schema.org JSON-LD is preferred, then schema.org microdata, then lists that look like ingredients and instructions.
+ * `source_url` is where the page came from; if None, the page's canonical URL is used. */
+pub fn from_html(html: &str, source_url: Option<&str>) -> Result<RecipeRequest, FormatError> {
+    let document = Html::parse_document(html);
+
+    let node = json_ld_recipe(&document)
+        .or_else(|| microdata_recipe(&document))
+        .or_else(|| heuristic_recipe(&document))
+        .ok_or_else(|| FormatError("No recipe found in the HTML document".to_string()))?;
+
+    let mut recipe = schema_org::from_schema_org(&decode_strings(node))?;
+    if let Some(url) = source_url
+        .map(str::to_string)
+        .or_else(|| canonical_url(&document))
+    {
+        recipe.source_url = Some(url);
+    }
+    Ok(recipe)
+}
+
+fn selector(css: &str) -> Selector {
+    // Only called with the constant selectors below
+    Selector::parse(css).unwrap()
+}
+
+fn json_ld_recipe(document: &Html) -> Option<Value> {
+    document
+        .select(&selector(r#"script[type="application/ld+json"]"#))
+        .filter_map(|script| {
+            let text: String = script.text().collect();
+            // Some sites wrap the JSON in comments or CDATA sections
+            let json = text
+                .trim()
+                .trim_start_matches("<!--")
+                .trim_end_matches("-->")
+                .trim()
+                .trim_start_matches("<![CDATA[")
+                .trim_end_matches("]]>");
+            serde_json::from_str::<Value>(json).ok()
+        })
+        .find(|value| schema_org::from_schema_org(value).is_ok())
+}
+
+fn microdata_recipe(document: &Html) -> Option<Value> {
+    let scope = document
+        .select(&selector("[itemscope][itemtype]"))
+        .find(|element| {
+            let item_type = element.value().attr("itemtype").unwrap_or("");
+            item_type
+                .trim_end_matches('/')
+                .ends_with("schema.org/Recipe")
+        })?;
+
+    let mut node = microdata_properties(scope);
+    node.insert("@type".to_string(), json!("Recipe"));
+    Some(Value::Object(node))
+}
+
+// Collects the itemprops of an itemscope, without those of nested scopes
+fn microdata_properties(scope: ElementRef) -> Map<String, Value> {
+    let mut properties = Map::new();
+    collect_microdata(scope, &mut properties);
+    properties
+}
+
+fn collect_microdata(parent: ElementRef, properties: &mut Map<String, Value>) {
+    for child in parent.children().filter_map(ElementRef::wrap) {
+        let element = child.value();
+        let is_scope = element.attr("itemscope").is_some();
+
+        if let Some(names) = element.attr("itemprop") {
+            let value = if is_scope {
+                let mut nested = microdata_properties(child);
+                if let Some(item_type) = element.attr("itemtype") {
+                    let name = item_type.trim_end_matches('/').rsplit('/').next();
+                    nested.insert("@type".to_string(), json!(name));
+                }
+                Value::Object(nested)
+            } else {
+                json!(microdata_value(child))
+            };
+
+            for name in names.split_whitespace() {
+                match properties.get_mut(name) {
+                    Some(Value::Array(values)) => values.push(value.clone()),
+                    Some(existing) => *existing = json!([existing.clone(), value.clone()]),
+                    None => {
+                        properties.insert(name.to_string(), value.clone());
+                    }
+                }
+            }
+        }
+
+        if !is_scope {
+            collect_microdata(child, properties);
+        }
+    }
+}
+
+fn microdata_value(element: ElementRef) -> String {
+    let el = element.value();
+    let attr = match el.name() {
+        "meta" => el.attr("content"),
+        "time" => el.attr("datetime"),
+        "a" | "link" => el.attr("href"),
+        "img" | "source" => el.attr("src"),
+        "data" | "meter" => el.attr("value"),
+        _ => el.attr("content"),
+    };
+    match attr {
+        Some(value) => value.trim().to_string(),
+        None => element_text(element),
+    }
+}
+
+// Last resort: a title and lists whose class, id or heading mentions ingredients or instructions
+fn heuristic_recipe(document: &Html) -> Option<Value> {
+    let ingredients = find_list(document, &INGREDIENT_HINTS)?;
+    let instructions = find_list(document, &INSTRUCTION_HINTS).unwrap_or_default();
+
+    let name = document
+        .select(&selector(r#"meta[property="og:title"]"#))
+        .find_map(|meta| meta.value().attr("content").map(str::to_string))
+        .or_else(|| document.select(&selector("h1")).next().map(element_text))
+        .or_else(|| document.select(&selector("title")).next().map(element_text));
+    let description = document
+        .select(&selector(r#"meta[name="description"]"#))
+        .find_map(|meta| meta.value().attr("content").map(str::to_string));
+
+    Some(json!({
+        "@type": "Recipe",
+        "name": name,
+        "description": description,
+        "recipeIngredient": ingredients,
+        "recipeInstructions": instructions,
+    }))
+}
+
+fn find_list(document: &Html, hints: &[&str]) -> Option<Vec<String>> {
+    let mentions = |text: &str| {
+        let lower = text.to_lowercase();
+        hints.iter().any(|hint| lower.contains(hint))
+    };
+
+    // <ul class="ingredients">, or a list inside <div id="recipe-ingredients">
+    for list in document.select(&selector("ul, ol")) {
+        let hinted = std::iter::once(list)
+            .chain(list.ancestors().filter_map(ElementRef::wrap).take(3))
+            .any(|element| {
+                let el = element.value();
+                mentions(el.attr("class").unwrap_or("")) || mentions(el.attr("id").unwrap_or(""))
+            });
+        if hinted {
+            let items = list_items(list);
+            if !items.is_empty() {
+                return Some(items);
+            }
+        }
+    }
+
+    // <h2>Ingredients</h2> followed by a list
+    for heading in document.select(&selector("h1, h2, h3, h4, h5, h6")) {
+        if !mentions(&element_text(heading)) {
+            continue;
+        }
+        let list = heading
+            .next_siblings()
+            .filter_map(ElementRef::wrap)
+            .take(3)
+            .find_map(|sibling| match sibling.value().name() {
+                "ul" | "ol" => Some(sibling),
+                _ => sibling.select(&selector("ul, ol")).next(),
+            });
+        if let Some(list) = list {
+            let items = list_items(list);
+            if !items.is_empty() {
+                return Some(items);
+            }
+        }
+    }
+    None
+}
+
+fn list_items(list: ElementRef) -> Vec<String> {
+    list.select(&selector("li"))
+        .map(element_text)
+        .filter(|text| !text.is_empty())
+        .collect()
+}
+
+fn canonical_url(document: &Html) -> Option<String> {
+    document
+        .select(&selector(r#"link[rel="canonical"]"#))
+        .find_map(|link| link.value().attr("href"))
+        .or_else(|| {
+            document
+                .select(&selector(r#"meta[property="og:url"]"#))
+                .find_map(|meta| meta.value().attr("content"))
+        })
+        .map(|url| url.trim().to_string())
+        .filter(|url| url.starts_with("http"))
+}
+
+// Text of an element with whitespace collapsed, as rendered by a browser
+fn element_text(element: ElementRef) -> String {
+    let text: String = element.text().collect::<Vec<&str>>().join(" ");
+    collapse_whitespace(&text)
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+// JSON-LD strings often contain entities like "&amp;" or markup like "<p>"
+fn decode_strings(value: Value) -> Value {
+    match value {
+        Value::String(s) if s.contains('&') || s.contains('<') => {
+            // Keep paragraphs and line breaks apart, instructions are split into steps by line
+            let marked = s
+                .replace("<br", "\n<br")
+                .replace("</p>", "</p>\n")
+                .replace("</li>", "</li>\n")
+                .replace("</div>", "</div>\n");
+            let fragment = Html::parse_fragment(&marked);
+            let text: String = fragment.root_element().text().collect();
+            let lines: Vec<String> = text
+                .lines()
+                .map(collapse_whitespace)
+                .filter(|line| !line.is_empty())
+                .collect();
+            Value::String(lines.join("\n"))
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(decode_strings).collect()),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| (key, decode_strings(value)))
+                .collect(),
+        ),
+        other => other,
+    }
+}
@@ -0,0 +1,275 @@
+use crate::models::recipe::Recipe;
+use crate::models::step::Step;
+use crate::quantity;
+
+/* Renders a recipe as Markdown, e.g. to paste it into a chat or wiki.
+ * Ingredient amounts are those of the first yield, which is the one shown. */
+pub fn to_markdown(recipe: &Recipe) -> String {
+    render(recipe, Style::Markdown)
+}
+
+/* Renders a recipe as plain text with the same layout as to_markdown, for places that don't render Markdown. */
+pub fn to_plain_text(recipe: &Recipe) -> String {
+    render(recipe, Style::Plain)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Style {
+    Markdown,
+    Plain,
+}
+
+impl Style {
+    fn title(self, text: &str) -> String {
+        match self {
+            Style::Markdown => format!("# {}", escape(text)),
+            Style::Plain => format!("{}\n{}", text, "=".repeat(text.chars().count())),
+        }
+    }
+
+    fn heading(self, text: &str) -> String {
+        match self {
+            Style::Markdown => format!("## {}", text),
+            Style::Plain => format!("{}\n{}", text, "-".repeat(text.chars().count())),
+        }
+    }
+
+    // "**Prep time:** 10 min"
+    fn field(self, label: &str, value: &str) -> String {
+        match self {
+            Style::Markdown => format!("**{}:** {}", label, escape(value)),
+            Style::Plain => format!("{}: {}", label, value),
+        }
+    }
+
+    fn text(self, text: &str) -> String {
+        match self {
+            Style::Markdown => escape(text),
+            Style::Plain => text.to_string(),
+        }
+    }
+
+    fn link(self, url: &str) -> String {
+        match self {
+            Style::Markdown => format!("<{}>", url),
+            Style::Plain => url.to_string(),
+        }
+    }
+
+    // Markdown needs two trailing spaces to keep a line break within a paragraph
+    fn line_break(self) -> &'static str {
+        match self {
+            Style::Markdown => "  \n",
+            Style::Plain => "\n",
+        }
+    }
+}
+
+fn render(recipe: &Recipe, style: Style) -> String {
+    let mut sections: Vec<String> = vec![];
+
+    let name = recipe
+        .recipe_name
+        .as_deref()
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or("Untitled recipe");
+    sections.push(style.title(name.trim()));
+
+    if let Some(notes) = recipe.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+        sections.push(paragraphs(notes, style));
+    }
+
+    let overview = overview(recipe, style);
+    if !overview.is_empty() {
+        sections.push(overview.join(style.line_break()));
+    }
+
+    let ingredients: Vec<String> = recipe
+        .ingredients
+        .iter()
+        .flatten()
+        .map(|ing| format!("- {}", style.text(&ing.get_line(0))))
+        .collect();
+    if !ingredients.is_empty() {
+        sections.push(style.heading("Ingredients"));
+        sections.push(ingredients.join("\n"));
+    }
+
+    let equipment: Vec<String> = recipe
+        .equipment
+        .iter()
+        .flatten()
+        .map(|name| format!("- {}", style.text(name)))
+        .collect();
+    if !equipment.is_empty() {
+        sections.push(style.heading("Equipment"));
+        sections.push(equipment.join("\n"));
+    }
+
+    let steps: Vec<String> = recipe
+        .steps
+        .iter()
+        .flatten()
+        .enumerate()
+        .map(|(idx, step)| render_step(idx + 1, step, style))
+        .collect();
+    if !steps.is_empty() {
+        sections.push(style.heading("Steps"));
+        sections.push(steps.join("\n\n"));
+    }
+
+    let source = source(recipe, style);
+    if !source.is_empty() {
+        sections.push(style.heading("Source"));
+        sections.push(source.join(style.line_break()));
+    }
+
+    let mut out = sections.join("\n\n");
+    out.push('\n');
+    out
+}
+
+// Yield, times and oven settings
+fn overview(recipe: &Recipe, style: Style) -> Vec<String> {
+    let mut lines = vec![];
+
+    if let Some(y) = recipe.yields.as_ref().and_then(|yields| yields.first()) {
+        let value = format!("{} {}", quantity::format_decimal(y.amount), y.unit);
+        lines.push(style.field("Yield", value.trim()));
+    }
+    let times = [
+        ("Prep time", recipe.prep_time),
+        ("Cook time", recipe.cook_time),
+        ("Rest time", recipe.rest_time),
+        ("Total time", recipe.effective_total_time()),
+    ];
+    for (label, time) in times.iter() {
+        if let Some(time) = time {
+            lines.push(style.field(label, &time.to_string()));
+        }
+    }
+
+    // ORF asks for the fan setting to be shown whenever it is specified
+    let mut oven = vec![];
+    if let Some(temp) = &recipe.oven_temp {
+        oven.push(temp.to_string());
+    }
+    if let Some(fan) = &recipe.oven_fan {
+        oven.push(format!("fan {}", fan.to_string().to_lowercase()));
+    }
+    if let Some(time) = &recipe.oven_time {
+        oven.push(time.to_string());
+    }
+    if !oven.is_empty() {
+        lines.push(style.field("Oven", &oven.join(", ")));
+    }
+
+    if let Some(categories) = recipe.categories.as_ref().filter(|c| !c.is_empty()) {
+        lines.push(style.field("Categories", &categories.join(", ")));
+    }
+    lines
+}
+
+// "1. Step text" with the bench notes and HACCP control points indented below it
+fn render_step(number: usize, step: &Step, style: Style) -> String {
+    let prefix = format!("{}. ", number);
+    let indent = " ".repeat(prefix.len());
+    let mut lines = vec![format!("{}{}", prefix, style.text(&one_line(&step.step)))];
+
+    if let Some(haccp) = &step.haccp {
+        let callouts = [
+            ("Critical control point", &haccp.critical_control_point),
+            ("Control point", &haccp.control_point),
+        ];
+        for (label, text) in callouts.iter() {
+            if text.trim().is_empty() {
+                continue;
+            }
+            lines.push(match style {
+                Style::Markdown => {
+                    format!("{}> **{}:** {}", indent, label, escape(&one_line(text)))
+                }
+                Style::Plain => format!("{}{}: {}", indent, label.to_uppercase(), one_line(text)),
+            });
+        }
+    }
+
+    if let Some(notes) = step.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+        for note in notes.lines().map(str::trim).filter(|n| !n.is_empty()) {
+            lines.push(match style {
+                Style::Markdown => format!("{}> *Note:* {}", indent, escape(note)),
+                Style::Plain => format!("{}Note: {}", indent, note),
+            });
+        }
+    }
+
+    // Markdown needs a blank line between the step and its quote
+    match style {
+        Style::Markdown if lines.len() > 1 => {
+            let mut out = lines.remove(0);
+            out += "\n\n";
+            out += &lines.join(&format!("\n{}>\n", indent));
+            out
+        }
+        _ => lines.join("\n"),
+    }
+}
+
+// Authors, book and URL
+fn source(recipe: &Recipe, style: Style) -> Vec<String> {
+    let mut lines = vec![];
+
+    if let Some(authors) = recipe.source_authors.as_ref().filter(|a| !a.is_empty()) {
+        lines.push(style.field("By", &authors.join(", ")));
+    }
+    if let Some(book) = recipe
+        .source_book
+        .as_ref()
+        .filter(|book| !book.title.trim().is_empty())
+    {
+        let mut value = book.title.trim().to_string();
+        if !book.authors.is_empty() {
+            value += &format!(" by {}", book.authors.join(", "));
+        }
+        if let Some(isbn) = book.isbn.as_deref().filter(|i| !i.trim().is_empty()) {
+            value += &format!(" (ISBN {})", isbn.trim());
+        }
+        lines.push(style.field("From", &value));
+    }
+    if let Some(url) = recipe
+        .source_url
+        .as_deref()
+        .filter(|u| !u.trim().is_empty())
+    {
+        lines.push(match style {
+            Style::Markdown => format!("**URL:** {}", style.link(url.trim())),
+            Style::Plain => format!("URL: {}", style.link(url.trim())),
+        });
+    }
+    lines
+}
+
+// Keeps the paragraphs of free text, joining the lines within each
+fn paragraphs(text: &str, style: Style) -> String {
+    text.split("\n\n")
+        .map(|paragraph| style.text(&one_line(paragraph)))
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
+
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+// Characters that would otherwise start emphasis, code, links or HTML
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '#') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
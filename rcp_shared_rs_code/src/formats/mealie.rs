@@ -0,0 +1,273 @@
+use serde_json::Value;
+use std::str::FromStr;
+
+use super::archive;
+use super::schema_org::{default_yield, parse_ingredient, parse_yield};
+use super::{FormatError, RecipeResults};
+use crate::models::duration::Duration;
+use crate::models::ingredient::{Amount, Ingredient, IngredientData};
+use crate::models::recipe_request::RecipeRequest;
+use crate::models::step::Step;
+use crate::models::unit::Unit;
+use crate::quantity::Quantity;
+
+/* Reads a Mealie export: the zip archive with a folder per recipe (JSON and images),
+ * or the JSON of one recipe or a list of recipes as returned by the Mealie API. */
+pub fn from_mealie_archive(data: &[u8]) -> Result<RecipeResults, FormatError> {
+    if !archive::is_zip(data) {
+        return from_mealie_json(&archive::utf8(data.to_vec())?);
+    }
+
+    let files = archive::read_zip(data)?;
+    let mut results = vec![];
+    for (path, content) in files.iter().filter(|(path, _)| path.ends_with(".json")) {
+        let value: Value = match serde_json::from_slice(content) {
+            Ok(value) => value,
+            Err(e) => {
+                results.push(Err(FormatError(format!("{}: invalid JSON: {}", path, e))));
+                continue;
+            }
+        };
+        // Other JSON files of the export, like the database dump, aren't recipes
+        if !is_recipe(&value) {
+            continue;
+        }
+
+        let mut recipe = from_mealie_value(&value);
+        if let Some(image) = find_image(&files, path) {
+            recipe.image = Some(image);
+        }
+        results.push(Ok(recipe));
+    }
+
+    if results.is_empty() {
+        return Err(FormatError("No Mealie recipe found".to_string()));
+    }
+    Ok(results)
+}
+
+/* Reads the JSON of a Mealie recipe, a list of recipes or an API page with "items". */
+pub fn from_mealie_json(json: &str) -> Result<RecipeResults, FormatError> {
+    let value: Value = serde_json::from_str(json)
+        .map_err(|e| FormatError(format!("Invalid Mealie JSON: {}", e)))?;
+
+    let recipes: Vec<&Value> = match &value {
+        Value::Array(values) => values.iter().collect(),
+        Value::Object(_) if is_recipe(&value) => vec![&value],
+        Value::Object(_) => value
+            .get("items")
+            .or_else(|| value.get("recipes"))
+            .and_then(Value::as_array)
+            .map(|values| values.iter().collect())
+            .unwrap_or_default(),
+        _ => vec![],
+    };
+    if recipes.is_empty() {
+        return Err(FormatError("No Mealie recipe found".to_string()));
+    }
+
+    Ok(recipes
+        .into_iter()
+        .enumerate()
+        .map(|(idx, value)| {
+            if is_recipe(value) {
+                Ok(from_mealie_value(value))
+            } else {
+                Err(FormatError(format!(
+                    "Recipe {}: not a Mealie recipe",
+                    idx + 1
+                )))
+            }
+        })
+        .collect())
+}
+
+fn is_recipe(value: &Value) -> bool {
+    value.get("name").is_some()
+        && (value.get("recipe_ingredient").is_some() || value.get("recipeIngredient").is_some())
+}
+
+// Mealie has used both snake_case and camelCase keys over its versions
+fn field<'a>(value: &'a Value, snake_case: &str, camel_case: &str) -> Option<&'a Value> {
+    value
+        .get(snake_case)
+        .or_else(|| value.get(camel_case))
+        .filter(|v| !v.is_null())
+}
+
+fn from_mealie_value(value: &Value) -> RecipeRequest {
+    let mut notes: Vec<String> = text(value.get("description")).into_iter().collect();
+    for note in list(value.get("notes")) {
+        match (text(note.get("title")), text(note.get("text"))) {
+            (Some(title), Some(text)) => notes.push(format!("{}: {}", title, text)),
+            (None, Some(text)) => notes.push(text),
+            _ => {}
+        }
+    }
+
+    let yields = field(value, "recipe_yield", "recipeYield")
+        .and_then(parse_yield)
+        .or_else(|| field(value, "recipe_servings", "recipeServings").and_then(parse_yield))
+        .unwrap_or_else(default_yield);
+
+    let ingredients: Vec<Ingredient> = list(field(value, "recipe_ingredient", "recipeIngredient"))
+        .into_iter()
+        .filter_map(ingredient)
+        .collect();
+
+    let steps: Vec<Step> = list(field(value, "recipe_instructions", "recipeInstructions"))
+        .into_iter()
+        .filter_map(|instruction| text(instruction.get("text")).or_else(|| text(Some(instruction))))
+        .map(|step| Step {
+            step,
+            ..Default::default()
+        })
+        .collect();
+
+    let categories: Vec<String> = list(field(value, "recipe_category", "recipeCategory"))
+        .into_iter()
+        .chain(list(value.get("tags")))
+        .filter_map(name)
+        .collect();
+    let equipment: Vec<String> = list(value.get("tools"))
+        .into_iter()
+        .filter_map(name)
+        .collect();
+
+    RecipeRequest {
+        recipe_name: text(value.get("name")),
+        notes: if notes.is_empty() {
+            None
+        } else {
+            Some(notes.join("\n\n"))
+        },
+        yields: Some(vec![yields]),
+        prep_time: duration(field(value, "prep_time", "prepTime")),
+        cook_time: duration(field(value, "perform_time", "performTime"))
+            .or_else(|| duration(field(value, "cook_time", "cookTime"))),
+        total_time: duration(field(value, "total_time", "totalTime")),
+        source_url: text(field(value, "org_url", "orgURL")),
+        categories: some_if_not_empty(categories),
+        // Images are files in the export, a URL is only kept if the recipe links one
+        image: text(value.get("image")).filter(|image| image.starts_with("http")),
+        equipment: some_if_not_empty(equipment),
+        ingredients: some_if_not_empty(ingredients),
+        steps: some_if_not_empty(steps),
+        ..Default::default()
+    }
+}
+
+// Structured with quantity, unit and food, or just a line of text in "note"
+fn ingredient(value: &Value) -> Option<Ingredient> {
+    let food = value.get("food").and_then(name);
+    let disable_amount = field(value, "disable_amount", "disableAmount")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+
+    match food {
+        Some(food) if !disable_amount => {
+            let mut amount = Amount {
+                unit: value
+                    .get("unit")
+                    .and_then(name)
+                    .map(|unit| Unit::from_str(&unit).unwrap_or_default())
+                    .unwrap_or_default(),
+                ..Amount::new()
+            };
+            if let Some(quantity) = value.get("quantity").and_then(quantity) {
+                amount.set_quantity(quantity);
+            }
+            Some(Ingredient {
+                ingredient: IngredientData {
+                    ingredient_name: food,
+                    amounts: vec![amount],
+                    processing: vec![],
+                    notes: text(value.get("note")),
+                    usda_num: None,
+                    allergens: None,
+                    catalogue_uuid: None,
+                },
+                substitutions: vec![],
+            })
+        }
+        _ => text(value.get("note"))
+            .or_else(|| text(field(value, "original_text", "originalText")))
+            .or_else(|| text(value.get("display")))
+            .or_else(|| text(Some(value)))
+            .map(|line| parse_ingredient(&line)),
+    }
+}
+
+// The photo in the recipe's folder, e.g. "recipes/pancakes/images/original.webp"
+fn find_image(files: &[(String, Vec<u8>)], json_path: &str) -> Option<String> {
+    let folder = match json_path.rfind('/') {
+        Some(pos) => &json_path[..pos + 1],
+        None => "",
+    };
+    let images: Vec<&(String, Vec<u8>)> = files
+        .iter()
+        .filter(|(path, _)| {
+            let rest = match path.strip_prefix(folder) {
+                Some(rest) => rest,
+                None => return false,
+            };
+            let in_folder = !rest.contains('/') || rest.starts_with("images/");
+            in_folder && archive::image_mime_type(path).is_some()
+        })
+        .collect();
+    let (path, content) = images
+        .iter()
+        .find(|(path, _)| archive::file_name(path).starts_with("original"))
+        .or_else(|| images.first())?;
+    Some(archive::data_url(archive::image_mime_type(path)?, content))
+}
+
+fn text(value: Option<&Value>) -> Option<String> {
+    let text = match value? {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+// Categories, tags, units and foods are objects with a name, or just strings in older exports
+fn name(value: &Value) -> Option<String> {
+    text(value.get("name")).or_else(|| text(Some(value)))
+}
+
+fn list(value: Option<&Value>) -> Vec<&Value> {
+    match value {
+        Some(Value::Array(values)) => values.iter().collect(),
+        _ => vec![],
+    }
+}
+
+fn quantity(value: &Value) -> Option<Quantity> {
+    match value {
+        Value::Number(n) => n.as_f64().filter(|q| *q > 0.0).map(Quantity::new),
+        Value::String(s) => Quantity::from_str(s).ok(),
+        _ => None,
+    }
+}
+
+// "PT1H30M", "1 hour 30 minutes" or minutes as a number
+fn duration(value: Option<&Value>) -> Option<Duration> {
+    match value? {
+        Value::Number(n) => n.as_f64().map(Duration::from_minutes),
+        Value::String(s) => Duration::from_str(s).ok(),
+        _ => None,
+    }
+}
+
+fn some_if_not_empty<T>(list: Vec<T>) -> Option<Vec<T>> {
+    if list.is_empty() {
+        None
+    } else {
+        Some(list)
+    }
+}
@@ -0,0 +1,370 @@
+use std::str::FromStr;
+
+use super::{FormatError, RecipeResults};
+use crate::models::ingredient::{Amount, Ingredient, IngredientData};
+use crate::models::r#yield::Yield;
+use crate::models::recipe_request::RecipeRequest;
+use crate::models::step::Step;
+use crate::models::unit::Unit;
+use crate::quantity::Quantity;
+
+/* The two-letter unit codes of the MealMaster ingredient column, as in the Meal-Master manual.
+ * Codes that aren't a measure, like sizes and containers, are kept as a unit name. */
+const UNIT_CODES: [(&str, &str); 34] = [
+    ("x", ""),
+    ("ea", ""),
+    ("sm", "small"),
+    ("md", "medium"),
+    ("lg", "large"),
+    ("cn", "can"),
+    ("pk", "package"),
+    ("pn", "pinch"),
+    ("dr", "drop"),
+    ("ds", "dash"),
+    ("ct", "carton"),
+    ("bn", "bunch"),
+    ("sl", "slice"),
+    ("t", "tsp"),
+    ("ts", "tsp"),
+    ("T", "tbsp"),
+    ("tb", "tbsp"),
+    ("fl", "fl oz"),
+    ("c", "cup"),
+    ("pt", "pint"),
+    ("qt", "quart"),
+    ("ga", "gallon"),
+    ("oz", "oz"),
+    ("lb", "lb"),
+    ("ml", "ml"),
+    ("cb", "ml"),
+    ("cl", "cl"),
+    ("dl", "dl"),
+    ("l", "l"),
+    ("mg", "mg"),
+    ("cg", "centigram"),
+    ("dg", "decigram"),
+    ("g", "g"),
+    ("kg", "kg"),
+];
+
+// Ingredients are written in two columns when they fit, the second one starts here
+const SECOND_COLUMN: usize = 41;
+
+/* Reads all recipes of a MealMaster file (https://en.wikipedia.org/wiki/Meal-Master),
+ * in both the "MMMMM" and the older "-----" layout.
+ * Fails only if the file contains no recipe at all. */
+pub fn from_mealmaster(text: &str) -> Result<RecipeResults, FormatError> {
+    let mut results = vec![];
+    // The lines of the current recipe and the line number of its first line
+    let mut block: Option<(usize, Vec<&str>)> = None;
+
+    for (line_idx, line) in text.lines().enumerate() {
+        let trimmed = line.trim();
+        if is_recipe_start(trimmed) {
+            // A recipe without end line ends at the next one
+            if let Some((first_line, lines)) = block.take() {
+                results.push(parse_recipe(results.len() + 1, first_line, &lines));
+            }
+            block = Some((line_idx + 2, vec![]));
+        } else if trimmed == "MMMMM" || trimmed == "-----" {
+            if let Some((first_line, lines)) = block.take() {
+                results.push(parse_recipe(results.len() + 1, first_line, &lines));
+            }
+        } else if let Some((_, lines)) = block.as_mut() {
+            lines.push(line.trim_end());
+        }
+    }
+    if let Some((first_line, lines)) = block.take() {
+        results.push(parse_recipe(results.len() + 1, first_line, &lines));
+    }
+
+    if results.is_empty() {
+        return Err(FormatError("No MealMaster recipe found".to_string()));
+    }
+    Ok(results)
+}
+
+// "MMMMM----- Recipe via Meal-Master (tm) v8.05" or "---------- Recipe via Meal-Master (tm) v8.02"
+pub(crate) fn is_recipe_start(line: &str) -> bool {
+    let lower = line.to_lowercase();
+    (line.starts_with("MMMMM") || line.starts_with("-----"))
+        && (lower.contains("meal-master") || lower.contains("recipe via"))
+}
+
+fn parse_recipe(
+    number: usize,
+    first_line: usize,
+    lines: &[&str],
+) -> Result<RecipeRequest, FormatError> {
+    let mut recipe = RecipeRequest::default();
+    let mut ingredients: Vec<Ingredient> = vec![];
+    let mut directions: Vec<&str> = vec![];
+    let mut in_header = true;
+    let mut in_directions = false;
+
+    for (line_idx, line) in lines.iter().enumerate() {
+        if in_header {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some((key, value)) = header_field(line) {
+                match key.as_str() {
+                    "title" => recipe.recipe_name = Some(value),
+                    "categories" => {
+                        let categories = value
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|c| !c.is_empty())
+                            .map(str::to_string)
+                            .collect();
+                        recipe.categories = some_if_not_empty(categories);
+                    }
+                    "yield" | "servings" => {
+                        recipe.yields = parse_yield(&value, &key).map(|y| vec![y])
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            in_header = false;
+        }
+
+        if !in_directions {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || is_subheading(trimmed) {
+                continue;
+            }
+            if let Some(columns) = ingredient_columns(line) {
+                for column in columns {
+                    match parse_ingredient(column) {
+                        Ok(Some(ingredient)) => ingredients.push(ingredient),
+                        // "-finely chopped" continues the ingredient above
+                        Ok(None) => {
+                            if let Some(last) = ingredients.last_mut() {
+                                continue_ingredient(&mut last.ingredient, column);
+                            }
+                        }
+                        Err(message) => {
+                            return Err(recipe_error(
+                                number,
+                                &recipe,
+                                &format!("{} in line {}", message, first_line + line_idx),
+                            ))
+                        }
+                    }
+                }
+                continue;
+            }
+            in_directions = true;
+        }
+        directions.push(line);
+    }
+
+    let (steps, notes) = parse_directions(&directions);
+    if recipe.recipe_name.is_none() {
+        return Err(recipe_error(number, &recipe, "missing title"));
+    }
+    if ingredients.is_empty() && steps.is_empty() {
+        return Err(recipe_error(
+            number,
+            &recipe,
+            "no ingredients or directions",
+        ));
+    }
+
+    // Ingredients need one amount per yield, so a recipe without yield gets a single batch
+    recipe.yields = recipe.yields.or_else(|| {
+        Some(vec![Yield {
+            amount: 1.0,
+            unit: "batch".to_string(),
+        }])
+    });
+    recipe.ingredients = some_if_not_empty(ingredients);
+    recipe.steps = some_if_not_empty(steps);
+    recipe.notes = notes;
+    Ok(recipe)
+}
+
+fn recipe_error(number: usize, recipe: &RecipeRequest, message: &str) -> FormatError {
+    match &recipe.recipe_name {
+        Some(name) => FormatError(format!("Recipe {} ({}): {}", number, name, message)),
+        None => FormatError(format!("Recipe {}: {}", number, message)),
+    }
+}
+
+// "      Title: Banana Bread", " Categories: Breads, Cakes"
+fn header_field(line: &str) -> Option<(String, String)> {
+    let pos = line.find(':')?;
+    let key = line[..pos].trim().to_lowercase();
+    match key.as_str() {
+        "title" | "categories" | "yield" | "servings" => {
+            Some((key, line[pos + 1..].trim().to_string()))
+        }
+        _ => None,
+    }
+}
+
+// "MMMMM--------------------------BATTER--------------------------"
+fn is_subheading(line: &str) -> bool {
+    line.starts_with("MMMMM-") || (line.starts_with("-----") && line.ends_with("-----"))
+}
+
+// Splits a line of the ingredient block into its columns, None if it isn't one
+fn ingredient_columns(line: &str) -> Option<Vec<&str>> {
+    if !is_ingredient_column(line) {
+        return None;
+    }
+    match line.get(SECOND_COLUMN..) {
+        Some(second) if is_ingredient_column(second) => {
+            Some(vec![line[..SECOND_COLUMN].trim_end(), second])
+        }
+        _ => Some(vec![line]),
+    }
+}
+
+// Amount in columns 1-7, unit code in 9-10 and the ingredient from column 12
+fn is_ingredient_column(column: &str) -> bool {
+    let (amount, unit, rest) = match split_column(column) {
+        Some(parts) => parts,
+        None => return false,
+    };
+    // Amounts that look like one but can't be read are reported by parse_ingredient
+    let amount_ok = amount
+        .chars()
+        .all(|c| c.is_ascii_digit() || " ./-".contains(c));
+    let unit_ok = unit.is_empty() || UNIT_CODES.iter().any(|(code, _)| *code == unit);
+    amount_ok && unit_ok && !rest.is_empty()
+}
+
+fn split_column(column: &str) -> Option<(&str, &str, &str)> {
+    let amount = column.get(..7)?;
+    let separator = column.get(7..8)?;
+    let unit = column.get(8..10)?;
+    let rest = column.get(10..)?;
+    if separator != " " {
+        return None;
+    }
+    Some((amount.trim(), unit.trim(), rest.trim()))
+}
+
+// Returns None for continuation lines, an error message if the amount can't be read
+fn parse_ingredient(column: &str) -> Result<Option<Ingredient>, String> {
+    let (amount_text, code, rest) =
+        split_column(column).ok_or_else(|| format!("invalid ingredient \"{}\"", column.trim()))?;
+    if amount_text.is_empty() && code.is_empty() && rest.starts_with('-') {
+        return Ok(None);
+    }
+
+    let mut amount = Amount {
+        unit: mealmaster_unit(code),
+        ..Amount::new()
+    };
+    if !amount_text.is_empty() {
+        let quantity = Quantity::from_str(amount_text)
+            .map_err(|_| format!("invalid amount \"{}\"", amount_text))?;
+        amount.set_quantity(quantity);
+    }
+
+    // "Butter; softened" and "Onions, chopped" carry the processing after the name
+    let (name, processing) = match rest.find(&[';', ','][..]) {
+        Some(pos) => (rest[..pos].trim(), vec![rest[pos + 1..].trim().to_string()]),
+        None => (rest, vec![]),
+    };
+
+    Ok(Some(Ingredient {
+        ingredient: IngredientData {
+            ingredient_name: name.to_string(),
+            amounts: vec![amount],
+            processing: processing.into_iter().filter(|p| !p.is_empty()).collect(),
+            notes: None,
+            usda_num: None,
+            allergens: None,
+            catalogue_uuid: None,
+        },
+        substitutions: vec![],
+    }))
+}
+
+/* Maps a MealMaster unit code like "ts" or "c" to a unit. Unknown codes are kept as they are. */
+pub fn mealmaster_unit(code: &str) -> Unit {
+    let name = UNIT_CODES
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map_or(code, |(_, name)| *name);
+    Unit::from_str(name).unwrap_or_default()
+}
+
+// "           -finely chopped" continues the processing, or the name if there is none
+fn continue_ingredient(data: &mut IngredientData, column: &str) {
+    let text = match split_column(column) {
+        Some((_, _, rest)) => rest.trim_start_matches('-').trim(),
+        None => return,
+    };
+    match data.processing.last_mut() {
+        Some(processing) => *processing += &format!(" {}", text),
+        None => data.ingredient_name += &format!(" {}", text),
+    }
+}
+
+// "1 loaf", "4" for servings
+fn parse_yield(value: &str, key: &str) -> Option<Yield> {
+    let mut tokens = value.splitn(2, char::is_whitespace);
+    let amount = Quantity::from_str(tokens.next()?).ok()?.value;
+    let unit = tokens.next().map(str::trim).unwrap_or("");
+    Some(Yield {
+        amount,
+        unit: if unit.is_empty() || key == "servings" {
+            "servings".to_string()
+        } else {
+            unit.to_string()
+        },
+    })
+}
+
+// Paragraphs are steps, lines like "Source: ..." or "From: ..." go to the notes
+fn parse_directions(lines: &[&str]) -> (Vec<Step>, Option<String>) {
+    let mut steps = vec![];
+    let mut notes = vec![];
+    let mut paragraph: Vec<&str> = vec![];
+
+    let mut push = |paragraph: &mut Vec<&str>| {
+        if !paragraph.is_empty() {
+            steps.push(Step {
+                step: paragraph.join(" "),
+                ..Default::default()
+            });
+            paragraph.clear();
+        }
+    };
+
+    for line in lines.iter().map(|line| line.trim()) {
+        let lower = line.to_lowercase();
+        if line.is_empty() {
+            push(&mut paragraph);
+        } else if ["source:", "from:", "posted by", "notes:", "note:"]
+            .iter()
+            .any(|prefix| lower.starts_with(prefix))
+        {
+            push(&mut paragraph);
+            notes.push(line.to_string());
+        } else {
+            paragraph.push(line);
+        }
+    }
+    push(&mut paragraph);
+
+    let notes = if notes.is_empty() {
+        None
+    } else {
+        Some(notes.join("\n"))
+    };
+    (steps, notes)
+}
+
+fn some_if_not_empty<T>(list: Vec<T>) -> Option<Vec<T>> {
+    if list.is_empty() {
+        None
+    } else {
+        Some(list)
+    }
+}
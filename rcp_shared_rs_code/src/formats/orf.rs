@@ -0,0 +1,430 @@
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use super::FormatError;
+use crate::models::allergen::Allergen;
+use crate::models::book_source::BookSource;
+use crate::models::diet::Diet;
+use crate::models::duration::Duration;
+use crate::models::haccp_value::HACCPValue;
+use crate::models::ingredient::{Amount, Ingredient, IngredientData};
+use crate::models::oven_fan_value::OvenFanValue;
+use crate::models::r#yield::Yield;
+use crate::models::recipe::Recipe;
+use crate::models::recipe_request::RecipeRequest;
+use crate::models::step::Step;
+use crate::models::temperature::Temperature;
+use crate::models::temperature_unit::TemperatureUnit;
+use crate::models::unit::Unit;
+use crate::quantity::Quantity;
+
+/* Reads a recipe written in the Open Recipe Format (https://github.com/cnstoll/Open-Recipe-Format).
+ * Accepts what hand-written files commonly contain: notes as a single string or a list, amounts like “1/2”,
+ * times like “45 minutes” and temperature units like “F”. */
+pub fn from_orf_yaml(yaml: &str) -> Result<RecipeRequest, FormatError> {
+    let orf: OrfRecipe =
+        serde_yaml::from_str(yaml).map_err(|e| FormatError(format!("Invalid ORF YAML: {}", e)))?;
+    orf.into_recipe_request()
+}
+
+/* Writes a recipe as Open Recipe Format YAML. Reading the result with from_orf_yaml gives back the same recipe. */
+pub fn to_orf_yaml(recipe: &Recipe) -> String {
+    // Serializing plain structs with string keys can't fail
+    serde_yaml::to_string(&OrfRecipe::from_recipe(recipe)).unwrap_or_default()
+}
+
+// The ORF document layout. Names of yields and ingredients are used as keys, notes are lists.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct OrfRecipe {
+    recipe_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oven_fan: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oven_temp: Option<OrfTemperature>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_duration"
+    )]
+    oven_time: Option<Duration>,
+
+    // Not part of ORF, other readers ignore them
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_duration"
+    )]
+    prep_time: Option<Duration>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_duration"
+    )]
+    cook_time: Option<Duration>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_duration"
+    )]
+    rest_time: Option<Duration>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_duration"
+    )]
+    total_time: Option<Duration>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    yields: Vec<BTreeMap<String, f64>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    ingredients: Vec<BTreeMap<String, OrfIngredient>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    steps: Vec<OrfStep>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    notes: Option<OrfNotes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_book: Option<OrfBookSource>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    source_authors: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_url: Option<String>,
+
+    // Not part of ORF either
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    categories: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diet_overrides: Option<BTreeMap<Diet, bool>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct OrfTemperature {
+    amount: f64,
+    unit: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct OrfIngredient {
+    amounts: Vec<OrfAmount>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    processing: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    notes: Option<OrfNotes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usda_num: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    allergens: Option<Vec<Allergen>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    substitutions: Vec<BTreeMap<String, OrfIngredient>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct OrfAmount {
+    amount: OrfQuantity,
+    #[serde(default)]
+    unit: String,
+}
+
+// Written as a number where possible, as text for fractions and ranges like "2-3"
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum OrfQuantity {
+    Number(f64),
+    Text(String),
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct OrfStep {
+    step: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    haccp: Option<OrfHaccp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    notes: Option<OrfNotes>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct OrfHaccp {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    control_point: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    critical_control_point: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct OrfBookSource {
+    authors: Vec<String>,
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    isbn: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    notes: Option<OrfNotes>,
+}
+
+// The spec uses lists of notes, our models keep them as one text with a note per line
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+enum OrfNotes {
+    Text(String),
+    List(Vec<String>),
+}
+
+impl OrfNotes {
+    fn from_text(notes: &Option<String>) -> Option<OrfNotes> {
+        let lines: Vec<String> = notes
+            .iter()
+            .flat_map(|notes| notes.lines())
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect();
+        if lines.is_empty() {
+            None
+        } else {
+            Some(OrfNotes::List(lines))
+        }
+    }
+
+    fn into_text(notes: Option<OrfNotes>) -> Option<String> {
+        match notes? {
+            OrfNotes::Text(text) => Some(text),
+            OrfNotes::List(list) if list.is_empty() => None,
+            OrfNotes::List(list) => Some(list.join("\n")),
+        }
+    }
+}
+
+fn serialize_duration<S: Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match duration {
+        // "1 h 30 min" rather than ISO 8601, ORF files are meant to be read by people
+        Some(duration) => serializer.collect_str(duration),
+        None => serializer.serialize_none(),
+    }
+}
+
+impl OrfRecipe {
+    fn from_recipe(recipe: &Recipe) -> Self {
+        Self {
+            recipe_name: recipe.recipe_name.clone(),
+            oven_fan: recipe.oven_fan.as_ref().map(|fan| fan.to_string()),
+            oven_temp: recipe.oven_temp.as_ref().map(|temp| OrfTemperature {
+                amount: temp.amount,
+                unit: match temp.unit {
+                    TemperatureUnit::Celsius => "C".to_string(),
+                    TemperatureUnit::Fahrenheit => "F".to_string(),
+                    TemperatureUnit::GasMark => "Gas Mark".to_string(),
+                },
+            }),
+            oven_time: recipe.oven_time,
+            prep_time: recipe.prep_time,
+            cook_time: recipe.cook_time,
+            rest_time: recipe.rest_time,
+            total_time: recipe.total_time,
+            yields: recipe
+                .yields
+                .iter()
+                .flatten()
+                .map(|y| std::iter::once((y.unit.clone(), y.amount)).collect())
+                .collect(),
+            ingredients: recipe
+                .ingredients
+                .iter()
+                .flatten()
+                .map(|ing| {
+                    let mut orf = OrfIngredient::from_data(&ing.ingredient);
+                    orf.substitutions = ing
+                        .substitutions
+                        .iter()
+                        .map(OrfIngredient::from_data_named)
+                        .collect();
+                    std::iter::once((ing.ingredient.ingredient_name.clone(), orf)).collect()
+                })
+                .collect(),
+            steps: recipe
+                .steps
+                .iter()
+                .flatten()
+                .map(|step| OrfStep {
+                    step: step.step.clone(),
+                    haccp: step.haccp.as_ref().map(|haccp| OrfHaccp {
+                        control_point: non_empty(&haccp.control_point),
+                        critical_control_point: non_empty(&haccp.critical_control_point),
+                    }),
+                    notes: OrfNotes::from_text(&step.notes),
+                })
+                .collect(),
+            notes: OrfNotes::from_text(&recipe.notes),
+            source_book: recipe.source_book.as_ref().map(|book| OrfBookSource {
+                authors: book.authors.clone(),
+                title: book.title.clone(),
+                isbn: book.isbn.clone(),
+                notes: OrfNotes::from_text(&book.notes),
+            }),
+            source_authors: recipe.source_authors.clone().unwrap_or_default(),
+            source_url: recipe.source_url.clone(),
+            categories: recipe.categories.clone().unwrap_or_default(),
+            diet_overrides: recipe.diet_overrides.clone(),
+            image: recipe.image.clone(),
+        }
+    }
+
+    fn into_recipe_request(self) -> Result<RecipeRequest, FormatError> {
+        let oven_fan = match self.oven_fan {
+            Some(fan) => Some(
+                OvenFanValue::from_str(&fan)
+                    .map_err(|_| FormatError(format!("Unknown oven_fan value: {}", fan)))?,
+            ),
+            None => None,
+        };
+
+        let oven_temp = match self.oven_temp {
+            Some(temp) => Some(Temperature {
+                amount: temp.amount,
+                unit: TemperatureUnit::from_str(temp.unit.trim())
+                    .map_err(|_| FormatError(format!("Unknown temperature unit: {}", temp.unit)))?,
+            }),
+            None => None,
+        };
+
+        let mut yields = vec![];
+        for y in self.yields {
+            for (unit, amount) in y {
+                yields.push(Yield { amount, unit });
+            }
+        }
+
+        let mut ingredients = vec![];
+        for entry in self.ingredients {
+            for (name, orf) in entry {
+                let substitutions = orf
+                    .substitutions
+                    .iter()
+                    .flat_map(|sub| sub.iter())
+                    .map(|(name, sub)| sub.to_data(name))
+                    .collect::<Result<Vec<IngredientData>, FormatError>>()?;
+                ingredients.push(Ingredient {
+                    ingredient: orf.to_data(&name)?,
+                    substitutions,
+                });
+            }
+        }
+
+        let steps = self
+            .steps
+            .into_iter()
+            .map(|step| Step {
+                step: step.step,
+                haccp: step.haccp.map(|haccp| HACCPValue {
+                    control_point: haccp.control_point.unwrap_or_default(),
+                    critical_control_point: haccp.critical_control_point.unwrap_or_default(),
+                }),
+                notes: OrfNotes::into_text(step.notes),
+                timers: vec![],
+            })
+            .collect::<Vec<Step>>();
+
+        Ok(RecipeRequest {
+            recipe_name: self.recipe_name,
+            oven_fan,
+            oven_temp,
+            oven_time: self.oven_time,
+            prep_time: self.prep_time,
+            cook_time: self.cook_time,
+            rest_time: self.rest_time,
+            total_time: self.total_time,
+            notes: OrfNotes::into_text(self.notes),
+            source_book: self.source_book.map(|book| BookSource {
+                authors: book.authors,
+                title: book.title,
+                isbn: book.isbn,
+                notes: OrfNotes::into_text(book.notes),
+            }),
+            source_authors: some_if_not_empty(self.source_authors),
+            source_url: self.source_url,
+            categories: some_if_not_empty(self.categories),
+            diet_overrides: self.diet_overrides,
+            image: self.image,
+            equipment: None,
+            ingredients: some_if_not_empty(ingredients),
+            steps: some_if_not_empty(steps),
+            yields: some_if_not_empty(yields),
+        })
+    }
+}
+
+impl OrfIngredient {
+    fn from_data(data: &IngredientData) -> Self {
+        Self {
+            amounts: data
+                .amounts
+                .iter()
+                .map(|a| OrfAmount {
+                    amount: match a.amount_max {
+                        Some(_) => OrfQuantity::Text(a.quantity().to_string()),
+                        None => OrfQuantity::Number(a.amount),
+                    },
+                    unit: match a.unit {
+                        Unit::Count => "each".to_string(),
+                        ref unit => unit.to_string(),
+                    },
+                })
+                .collect(),
+            processing: data.processing.clone(),
+            notes: OrfNotes::from_text(&data.notes),
+            usda_num: data.usda_num.clone(),
+            allergens: data.allergens.clone(),
+            substitutions: vec![],
+        }
+    }
+
+    fn from_data_named(data: &IngredientData) -> BTreeMap<String, Self> {
+        std::iter::once((data.ingredient_name.clone(), Self::from_data(data))).collect()
+    }
+
+    fn to_data(&self, name: &str) -> Result<IngredientData, FormatError> {
+        let mut amounts = vec![];
+        for orf in &self.amounts {
+            let quantity = match &orf.amount {
+                OrfQuantity::Number(value) => Quantity::new(*value),
+                OrfQuantity::Text(text) => Quantity::from_str(text)
+                    .map_err(|_| FormatError(format!("Invalid amount for {}: {}", name, text)))?,
+            };
+            let mut amount = Amount {
+                unit: Unit::from_str(&orf.unit).unwrap_or_default(),
+                ..Amount::new()
+            };
+            amount.set_quantity(quantity);
+            amounts.push(amount);
+        }
+
+        Ok(IngredientData {
+            amounts,
+            processing: self.processing.clone(),
+            notes: OrfNotes::into_text(self.notes.clone()),
+            ingredient_name: name.to_string(),
+            usda_num: self.usda_num.clone(),
+            allergens: self.allergens.clone(),
+            catalogue_uuid: None,
+        })
+    }
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    if s.trim().is_empty() {
+        None
+    } else {
+        Some(s.to_string())
+    }
+}
+
+fn some_if_not_empty<T>(list: Vec<T>) -> Option<Vec<T>> {
+    if list.is_empty() {
+        None
+    } else {
+        Some(list)
+    }
+}
@@ -0,0 +1,137 @@
+use serde::Deserialize;
+use serde_json::json;
+use std::str::FromStr;
+
+use super::archive;
+use super::schema_org::{default_yield, parse_ingredient, parse_yield};
+use super::{FormatError, RecipeResults};
+use crate::models::duration::Duration;
+use crate::models::recipe_request::RecipeRequest;
+use crate::models::step::Step;
+
+/* Reads a Paprika export: a .paprikarecipes archive (a zip of gzipped JSON recipes),
+ * a single gzipped .paprikarecipe or its plain JSON. */
+pub fn from_paprika_archive(data: &[u8]) -> Result<RecipeResults, FormatError> {
+    if !archive::is_zip(data) {
+        return Ok(vec![from_paprika_recipe(data)]);
+    }
+
+    let results: RecipeResults = archive::read_zip(data)?
+        .into_iter()
+        .filter(|(path, _)| path.ends_with(".paprikarecipe"))
+        .enumerate()
+        .map(|(idx, (path, content))| {
+            from_paprika_recipe(&content).map_err(|e| {
+                let name = archive::file_name(&path).trim_end_matches(".paprikarecipe");
+                FormatError(format!("Recipe {} ({}): {}", idx + 1, name, e))
+            })
+        })
+        .collect();
+
+    if results.is_empty() {
+        return Err(FormatError("No Paprika recipe found".to_string()));
+    }
+    Ok(results)
+}
+
+// A .paprikarecipe file, gzipped or not
+fn from_paprika_recipe(data: &[u8]) -> Result<RecipeRequest, FormatError> {
+    let json = if archive::is_gzip(data) {
+        archive::utf8(archive::gunzip(data)?)?
+    } else {
+        archive::utf8(data.to_vec())?
+    };
+    from_paprika_json(&json)
+}
+
+/* Reads the JSON of a single Paprika recipe. */
+pub fn from_paprika_json(json: &str) -> Result<RecipeRequest, FormatError> {
+    let paprika: PaprikaRecipe = serde_json::from_str(json)
+        .map_err(|e| FormatError(format!("Invalid Paprika recipe: {}", e)))?;
+    Ok(paprika.into_recipe_request())
+}
+
+// Paprika stores ingredients and directions as text, one per line
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct PaprikaRecipe {
+    name: String,
+    description: String,
+    ingredients: String,
+    directions: String,
+    notes: String,
+    servings: String,
+    prep_time: String,
+    cook_time: String,
+    total_time: String,
+    // The site or book the recipe is from
+    source: String,
+    source_url: String,
+    categories: Vec<String>,
+    // Base64 encoded JPEG
+    photo_data: Option<String>,
+    image_url: Option<String>,
+}
+
+impl PaprikaRecipe {
+    fn into_recipe_request(self) -> RecipeRequest {
+        let ingredients: Vec<_> = lines(&self.ingredients).map(parse_ingredient).collect();
+        let steps: Vec<Step> = lines(&self.directions)
+            .map(|line| Step {
+                step: line.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        let notes: Vec<&str> = [self.description.trim(), self.notes.trim()]
+            .iter()
+            .copied()
+            .filter(|text| !text.is_empty())
+            .collect();
+        let image = match self.photo_data.filter(|data| !data.is_empty()) {
+            Some(data) => Some(format!("data:image/jpeg;base64,{}", data)),
+            None => self.image_url.filter(|url| !url.is_empty()),
+        };
+
+        RecipeRequest {
+            recipe_name: non_empty(&self.name),
+            notes: if notes.is_empty() {
+                None
+            } else {
+                Some(notes.join("\n\n"))
+            },
+            yields: Some(vec![
+                parse_yield(&json!(self.servings)).unwrap_or_else(default_yield)
+            ]),
+            prep_time: Duration::from_str(&self.prep_time).ok(),
+            cook_time: Duration::from_str(&self.cook_time).ok(),
+            total_time: Duration::from_str(&self.total_time).ok(),
+            source_authors: non_empty(&self.source).map(|source| vec![source]),
+            source_url: non_empty(&self.source_url),
+            categories: some_if_not_empty(self.categories),
+            image,
+            ingredients: some_if_not_empty(ingredients),
+            steps: some_if_not_empty(steps),
+            ..Default::default()
+        }
+    }
+}
+
+fn lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines().map(str::trim).filter(|line| !line.is_empty())
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    if s.trim().is_empty() {
+        None
+    } else {
+        Some(s.trim().to_string())
+    }
+}
+
+fn some_if_not_empty<T>(list: Vec<T>) -> Option<Vec<T>> {
+    if list.is_empty() {
+        None
+    } else {
+        Some(list)
+    }
+}
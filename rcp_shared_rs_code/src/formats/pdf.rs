@@ -0,0 +1,614 @@
+use std::collections::BTreeMap;
+
+use crate::models::recipe::Recipe;
+use crate::models::step::Step;
+use crate::quantity;
+
+// A4 in points, the unit of PDF coordinates. The origin is the bottom left corner.
+const PAGE_WIDTH: f64 = 595.28;
+const PAGE_HEIGHT: f64 = 841.89;
+const MARGIN: f64 = 56.0;
+const FOOTER_Y: f64 = 30.0;
+
+const TOC_SIZE: f64 = 11.0;
+const TOC_LEADING: f64 = 18.0;
+
+/* Lays out a printable cookbook: a title page, the table of contents, every recipe on a new page
+ * with its ingredients, steps and HACCP control points, and an index of the ingredients.
+ * The PDF only uses the standard Helvetica fonts, which every PDF reader has, so nothing needs to be embedded.
+ * Text outside of Windows-1252 (Latin-1 plus typographic quotes and dashes) is printed as "?". */
+pub fn to_cookbook_pdf(title: &str, recipes: &[Recipe]) -> Vec<u8> {
+    // Recipes come first, so the table of contents knows their page numbers
+    let toc_pages = toc_page_count(recipes.len());
+    let first_page = 2 + toc_pages;
+
+    let mut body = Layout::new(first_page);
+    let mut starts = vec![];
+    let mut index: BTreeMap<String, (String, Vec<usize>)> = BTreeMap::new();
+    for recipe in recipes {
+        body.new_page();
+        starts.push(body.page_number());
+        layout_recipe(&mut body, recipe, &mut index);
+    }
+    if !index.is_empty() {
+        body.new_page();
+        layout_index(&mut body, &index);
+    }
+
+    let mut toc = Layout::new(2);
+    layout_toc(&mut toc, recipes, &starts);
+
+    let mut pages = vec![title_page(title, recipes.len())];
+    pages.extend(toc.finish());
+    pages.extend(body.finish());
+    write_pdf(title, &pages)
+}
+
+fn title_page(title: &str, recipe_count: usize) -> Vec<u8> {
+    let mut layout = Layout::new(1);
+    layout.new_page();
+    layout.y = PAGE_HEIGHT * 0.62;
+    for line in wrap(title, Font::Bold, 32.0, layout.width()) {
+        let x = (PAGE_WIDTH - text_width(&line, Font::Bold, 32.0)) / 2.0;
+        layout.text(x, Font::Bold, 32.0, &line);
+        layout.y -= 40.0;
+    }
+    let subtitle = match recipe_count {
+        1 => "1 recipe".to_string(),
+        n => format!("{} recipes", n),
+    };
+    let x = (PAGE_WIDTH - text_width(&subtitle, Font::Italic, 14.0)) / 2.0;
+    layout.y -= 10.0;
+    layout.text(x, Font::Italic, 14.0, &subtitle);
+    // The title page has no page number
+    layout.pages.remove(0)
+}
+
+// The "Contents" heading takes three lines of the first page
+fn toc_entries_per_page(first: bool) -> usize {
+    let lines = ((PAGE_HEIGHT - 2.0 * MARGIN) / TOC_LEADING).floor() as usize;
+    if first {
+        lines - 3
+    } else {
+        lines
+    }
+}
+
+fn toc_page_count(recipe_count: usize) -> usize {
+    let first = toc_entries_per_page(true);
+    if recipe_count <= first {
+        1
+    } else {
+        let rest = toc_entries_per_page(false);
+        1 + (recipe_count - first).div_ceil(rest)
+    }
+}
+
+// One line per recipe: the name, dot leaders and the page number
+fn layout_toc(layout: &mut Layout, recipes: &[Recipe], starts: &[usize]) {
+    layout.new_page();
+    layout.heading("Contents", 20.0);
+    layout.y -= TOC_LEADING;
+
+    let mut on_page = 0;
+    for (recipe, page) in recipes.iter().zip(starts) {
+        if on_page == toc_entries_per_page(layout.pages.len() == 1) {
+            layout.new_page();
+            on_page = 0;
+        }
+        let number = page.to_string();
+        let number_width = text_width(&number, Font::Regular, TOC_SIZE);
+        let name = truncate(
+            recipe_name(recipe),
+            Font::Regular,
+            TOC_SIZE,
+            layout.width() - number_width - 30.0,
+        );
+        let name_width = text_width(&name, Font::Regular, TOC_SIZE);
+        let dot_width = text_width(".", Font::Regular, TOC_SIZE);
+        let dots = ((layout.width() - name_width - number_width - 12.0) / dot_width).max(0.0);
+
+        layout.text(MARGIN, Font::Regular, TOC_SIZE, &name);
+        layout.text(
+            MARGIN + name_width + 6.0,
+            Font::Regular,
+            TOC_SIZE,
+            &".".repeat(dots as usize),
+        );
+        layout.text(
+            PAGE_WIDTH - MARGIN - number_width,
+            Font::Regular,
+            TOC_SIZE,
+            &number,
+        );
+        layout.y -= TOC_LEADING;
+        on_page += 1;
+    }
+}
+
+fn layout_recipe(
+    layout: &mut Layout,
+    recipe: &Recipe,
+    index: &mut BTreeMap<String, (String, Vec<usize>)>,
+) {
+    layout.heading(recipe_name(recipe), 20.0);
+
+    let overview = overview(recipe);
+    if !overview.is_empty() {
+        layout.paragraph(MARGIN, Font::Regular, 10.0, &overview.join(" \u{b7} "));
+    }
+    if let Some(notes) = recipe.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+        layout.y -= 4.0;
+        for paragraph in notes.split("\n\n") {
+            layout.paragraph(MARGIN, Font::Italic, 10.0, paragraph);
+        }
+    }
+
+    let ingredients: Vec<_> = recipe.ingredients.iter().flatten().collect();
+    if !ingredients.is_empty() {
+        layout.y -= 10.0;
+        layout.heading("Ingredients", 13.0);
+        for ing in ingredients {
+            layout.item("\u{2022}", &ing.get_line(0), Font::Regular);
+            // The page the ingredient ended up on, after a possible page break
+            let name = ing.ingredient.ingredient_name.trim();
+            if !name.is_empty() {
+                let entry = index
+                    .entry(name.to_lowercase())
+                    .or_insert_with(|| (name.to_string(), vec![]));
+                if !entry.1.contains(&layout.page_number()) {
+                    entry.1.push(layout.page_number());
+                }
+            }
+        }
+    }
+
+    let steps: Vec<&Step> = recipe.steps.iter().flatten().collect();
+    if !steps.is_empty() {
+        layout.y -= 10.0;
+        layout.heading("Steps", 13.0);
+        for (idx, step) in steps.iter().enumerate() {
+            layout.item(&format!("{}.", idx + 1), &step.step, Font::Regular);
+            layout_step_notes(layout, step);
+            layout.y -= 4.0;
+        }
+    }
+
+    let source = source(recipe);
+    if !source.is_empty() {
+        layout.y -= 10.0;
+        layout.paragraph(
+            MARGIN,
+            Font::Italic,
+            9.0,
+            &format!("Source: {}", source.join(", ")),
+        );
+    }
+}
+
+// HACCP control points in bold and bench notes in italics, under the step text
+fn layout_step_notes(layout: &mut Layout, step: &Step) {
+    let x = MARGIN + ITEM_INDENT;
+    if let Some(haccp) = &step.haccp {
+        if !haccp.critical_control_point.trim().is_empty() {
+            layout.paragraph(
+                x,
+                Font::Bold,
+                10.0,
+                &format!(
+                    "Critical control point: {}",
+                    haccp.critical_control_point.trim()
+                ),
+            );
+        }
+        if !haccp.control_point.trim().is_empty() {
+            layout.paragraph(
+                x,
+                Font::Bold,
+                10.0,
+                &format!("Control point: {}", haccp.control_point.trim()),
+            );
+        }
+    }
+    if let Some(notes) = step.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+        layout.paragraph(x, Font::Italic, 10.0, &format!("Note: {}", notes.trim()));
+    }
+}
+
+// Every ingredient with the pages it is listed on, sorted by name
+fn layout_index(layout: &mut Layout, index: &BTreeMap<String, (String, Vec<usize>)>) {
+    layout.heading("Index of ingredients", 20.0);
+    layout.y -= 6.0;
+    for (name, pages) in index.values() {
+        let pages: Vec<String> = pages.iter().map(usize::to_string).collect();
+        layout.paragraph(
+            MARGIN,
+            Font::Regular,
+            10.0,
+            &format!("{}, {}", name, pages.join(", ")),
+        );
+    }
+}
+
+fn recipe_name(recipe: &Recipe) -> &str {
+    recipe
+        .recipe_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .unwrap_or("Untitled recipe")
+}
+
+fn overview(recipe: &Recipe) -> Vec<String> {
+    let mut parts = vec![];
+    if let Some(y) = recipe.yields.as_ref().and_then(|yields| yields.first()) {
+        parts.push(format!(
+            "Yield: {} {}",
+            quantity::format_decimal(y.amount),
+            y.unit
+        ));
+    }
+    let times = [
+        ("Prep time", recipe.prep_time),
+        ("Cook time", recipe.cook_time),
+        ("Total time", recipe.effective_total_time()),
+    ];
+    for (label, time) in times.iter() {
+        if let Some(time) = time {
+            parts.push(format!("{}: {}", label, time));
+        }
+    }
+    let mut oven = vec![];
+    if let Some(temp) = &recipe.oven_temp {
+        oven.push(temp.to_string());
+    }
+    if let Some(fan) = &recipe.oven_fan {
+        oven.push(format!("fan {}", fan.to_string().to_lowercase()));
+    }
+    if !oven.is_empty() {
+        parts.push(format!("Oven: {}", oven.join(", ")));
+    }
+    parts
+}
+
+fn source(recipe: &Recipe) -> Vec<String> {
+    let mut parts = vec![];
+    if let Some(authors) = recipe.source_authors.as_ref().filter(|a| !a.is_empty()) {
+        parts.push(authors.join(", "));
+    }
+    if let Some(book) = recipe
+        .source_book
+        .as_ref()
+        .filter(|book| !book.title.trim().is_empty())
+    {
+        parts.push(book.title.trim().to_string());
+    }
+    if let Some(url) = recipe
+        .source_url
+        .as_deref()
+        .filter(|u| !u.trim().is_empty())
+    {
+        parts.push(url.trim().to_string());
+    }
+    parts
+}
+
+// Bullets and step numbers hang in front of the text
+const ITEM_INDENT: f64 = 18.0;
+
+/* Places text from top to bottom, starting a new page when one is full. */
+struct Layout {
+    // Content streams of the finished pages and the current one
+    pages: Vec<Vec<u8>>,
+    // Page number of the first page of this layout
+    first_page: usize,
+    // Baseline of the next line
+    y: f64,
+}
+
+impl Layout {
+    fn new(first_page: usize) -> Self {
+        Layout {
+            pages: vec![],
+            first_page,
+            y: 0.0,
+        }
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(vec![]);
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    fn page_number(&self) -> usize {
+        self.first_page + self.pages.len() - 1
+    }
+
+    fn width(&self) -> f64 {
+        PAGE_WIDTH - 2.0 * MARGIN
+    }
+
+    // Continues on a new page if the next `height` points don't fit
+    fn reserve(&mut self, height: f64) {
+        if self.y - height < MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn text(&mut self, x: f64, font: Font, size: f64, text: &str) {
+        let page = self.pages.last_mut().expect("no page started");
+        page.extend_from_slice(
+            format!(
+                "BT /{} {} Tf {:.2} {:.2} Td ",
+                font.resource(),
+                size,
+                x,
+                self.y - size
+            )
+            .as_bytes(),
+        );
+        page.extend(pdf_string(text));
+        page.extend_from_slice(b" Tj ET\n");
+    }
+
+    fn heading(&mut self, text: &str, size: f64) {
+        for line in wrap(text, Font::Bold, size, self.width()) {
+            self.reserve(size * 1.3 + 30.0);
+            self.text(MARGIN, Font::Bold, size, &line);
+            self.y -= size * 1.3;
+        }
+        self.y -= size * 0.4;
+    }
+
+    fn paragraph(&mut self, x: f64, font: Font, size: f64, text: &str) {
+        let width = PAGE_WIDTH - MARGIN - x;
+        for line in wrap(text, font, size, width) {
+            self.reserve(size * 1.4);
+            self.text(x, font, size, &line);
+            self.y -= size * 1.4;
+        }
+    }
+
+    // A list item like "• 2 cups flour" or "3. Bake for 25 minutes", wrapped under its text
+    fn item(&mut self, marker: &str, text: &str, font: Font) {
+        let size = 11.0;
+        let x = MARGIN + ITEM_INDENT;
+        let lines = wrap(text, font, size, PAGE_WIDTH - MARGIN - x);
+        for (idx, line) in lines.iter().enumerate() {
+            self.reserve(size * 1.4);
+            if idx == 0 {
+                self.text(MARGIN, font, size, marker);
+            }
+            self.text(x, font, size, line);
+            self.y -= size * 1.4;
+        }
+    }
+
+    // The content streams with a page number at the bottom of each page
+    fn finish(self) -> Vec<Vec<u8>> {
+        let first_page = self.first_page;
+        let mut pages = self.pages;
+        for (idx, page) in pages.iter_mut().enumerate() {
+            let number = (first_page + idx).to_string();
+            let x = (PAGE_WIDTH - text_width(&number, Font::Regular, 9.0)) / 2.0;
+            page.extend_from_slice(format!("BT /F1 9 Tf {:.2} {:.2} Td ", x, FOOTER_Y).as_bytes());
+            page.extend(pdf_string(&number));
+            page.extend_from_slice(b" Tj ET\n");
+        }
+        pages
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Font {
+    Regular,
+    Bold,
+    Italic,
+}
+
+impl Font {
+    fn resource(self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+            Font::Italic => "F3",
+        }
+    }
+
+    fn base_font(self) -> &'static str {
+        match self {
+            Font::Regular => "Helvetica",
+            Font::Bold => "Helvetica-Bold",
+            Font::Italic => "Helvetica-Oblique",
+        }
+    }
+
+    // In thousandths of the font size, from the Adobe font metrics
+    fn char_width(self, c: char) -> f64 {
+        let widths = match self {
+            Font::Bold => &HELVETICA_BOLD_WIDTHS,
+            _ => &HELVETICA_WIDTHS,
+        };
+        match c {
+            ' '..='~' => f64::from(widths[c as usize - 32]),
+            '\u{2022}' => 350.0,
+            '\u{2026}' | '\u{2014}' => 1000.0,
+            '°' => 400.0,
+            _ => 556.0,
+        }
+    }
+}
+
+// Characters from " " to "~"
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, 975, 722, 722, 722, 722, 667,
+    611, 778, 722, 278, 556, 722, 611, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 333, 278, 333, 584, 556, 333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556,
+    278, 889, 611, 611, 611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+fn text_width(text: &str, font: Font, size: f64) -> f64 {
+    text.chars().map(|c| font.char_width(c)).sum::<f64>() * size / 1000.0
+}
+
+// Greedy line breaking at whitespace; words longer than a line are split
+fn wrap(text: &str, font: Font, size: f64, width: f64) -> Vec<String> {
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", line, word)
+        };
+        if text_width(&candidate, font, size) <= width {
+            line = candidate;
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+        }
+        for c in word.chars() {
+            if !line.is_empty() && text_width(&format!("{}{}", line, c), font, size) > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push(c);
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+// Shortens `text` with an ellipsis to fit into `width`
+fn truncate(text: &str, font: Font, size: f64, width: f64) -> String {
+    if text_width(text, font, size) <= width {
+        return text.to_string();
+    }
+    let mut out = String::new();
+    for c in text.chars() {
+        if text_width(&format!("{}{}\u{2026}", out, c), font, size) > width {
+            break;
+        }
+        out.push(c);
+    }
+    format!("{}\u{2026}", out.trim_end())
+}
+
+// A literal string in WinAnsiEncoding, the encoding of the standard fonts
+fn pdf_string(text: &str) -> Vec<u8> {
+    let mut out = vec![b'('];
+    for c in text.chars() {
+        let byte = win_ansi(c);
+        if matches!(byte, b'(' | b')' | b'\\') {
+            out.push(b'\\');
+        }
+        out.push(byte);
+    }
+    out.push(b')');
+    out
+}
+
+fn win_ansi(c: char) -> u8 {
+    match c {
+        ' '..='~' => c as u8,
+        '\u{a0}'..='\u{ff}' => c as u32 as u8,
+        '\u{20ac}' => 0x80,
+        '\u{2026}' => 0x85,
+        '\u{2018}' => 0x91,
+        '\u{2019}' => 0x92,
+        '\u{201c}' => 0x93,
+        '\u{201d}' => 0x94,
+        '\u{2022}' => 0x95,
+        '\u{2013}' => 0x96,
+        '\u{2014}' => 0x97,
+        '\t' | '\n' | '\r' => b' ',
+        _ => b'?',
+    }
+}
+
+// Catalog, page tree, fonts and document info come first, then a page and its content stream per page
+fn write_pdf(title: &str, pages: &[Vec<u8>]) -> Vec<u8> {
+    const FONTS: [Font; 3] = [Font::Regular, Font::Bold, Font::Italic];
+    let first_page_object = 7;
+
+    let kids: Vec<String> = (0..pages.len())
+        .map(|idx| format!("{} 0 R", first_page_object + 2 * idx))
+        .collect();
+    let mut objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        )
+        .into_bytes(),
+    ];
+    for font in FONTS.iter() {
+        objects.push(
+            format!(
+                "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+                font.base_font()
+            )
+            .into_bytes(),
+        );
+    }
+    let mut info = b"<< /Title ".to_vec();
+    info.extend(pdf_string(title));
+    info.extend_from_slice(b" /Producer (rcp) >>");
+    objects.push(info);
+
+    for (idx, content) in pages.iter().enumerate() {
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R /F3 5 0 R >> >> /Contents {} 0 R >>",
+                PAGE_WIDTH,
+                PAGE_HEIGHT,
+                first_page_object + 2 * idx + 1
+            )
+            .into_bytes(),
+        );
+        let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+        stream.extend_from_slice(content);
+        stream.extend_from_slice(b"\nendstream");
+        objects.push(stream);
+    }
+
+    // The binary comment tells file transfers that this isn't text
+    let mut out = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+    let mut offsets = vec![];
+    for (idx, object) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        out.extend_from_slice(format!("{} 0 obj\n", idx + 1).as_bytes());
+        out.extend_from_slice(object);
+        out.extend_from_slice(b"\nendobj\n");
+    }
+
+    let xref = out.len();
+    out.extend_from_slice(
+        format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes(),
+    );
+    for offset in offsets {
+        out.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    out.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R /Info 6 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        )
+        .as_bytes(),
+    );
+    out
+}
@@ -0,0 +1,314 @@
+use roxmltree::{Document, Node};
+use std::str::FromStr;
+
+use super::{FormatError, RecipeResults};
+use crate::models::book_source::BookSource;
+use crate::models::duration::Duration;
+use crate::models::ingredient::{Amount, Ingredient, IngredientData};
+use crate::models::r#yield::Yield;
+use crate::models::recipe_request::RecipeRequest;
+use crate::models::step::Step;
+use crate::models::unit::Unit;
+use crate::quantity::Quantity;
+
+/* Reads all recipes of a RecipeML document (http://www.formatdata.com/recipeml/).
+ * Fails only if the document isn't XML or contains no recipe at all. */
+pub fn from_recipeml(xml: &str) -> Result<RecipeResults, FormatError> {
+    let document =
+        Document::parse(xml).map_err(|e| FormatError(format!("Invalid RecipeML: {}", e)))?;
+
+    let results: RecipeResults = document
+        .descendants()
+        .filter(|node| node.has_tag_name("recipe"))
+        .enumerate()
+        .map(|(idx, node)| {
+            parse_recipe(node).map_err(|message| recipe_error(idx + 1, node, &message))
+        })
+        .collect();
+
+    if results.is_empty() {
+        return Err(FormatError("No RecipeML recipe found".to_string()));
+    }
+    Ok(results)
+}
+
+fn recipe_error(number: usize, recipe: Node, message: &str) -> FormatError {
+    match child(recipe, "head").and_then(|head| child_text(head, "title")) {
+        Some(title) => FormatError(format!("Recipe {} ({}): {}", number, title, message)),
+        None => FormatError(format!("Recipe {}: {}", number, message)),
+    }
+}
+
+fn parse_recipe(node: Node) -> Result<RecipeRequest, String> {
+    let head = child(node, "head").ok_or_else(|| "missing <head>".to_string())?;
+    let mut recipe = RecipeRequest {
+        recipe_name: Some(child_text(head, "title").ok_or_else(|| "missing title".to_string())?),
+        notes: child_text(node, "description"),
+        categories: child(head, "categories")
+            .map(|cats| children(cats, "cat").filter_map(text).collect())
+            .and_then(some_if_not_empty),
+        ..Default::default()
+    };
+
+    recipe.yields = Some(vec![match child(head, "yield") {
+        Some(y) => parse_yield(y)?,
+        // Ingredients need one amount per yield, so a recipe without yield gets a single batch
+        None => Yield {
+            amount: 1.0,
+            unit: "batch".to_string(),
+        },
+    }]);
+
+    // <preptime type="cooking"><time><qty>20</qty><timeunit>minutes</timeunit></time></preptime>
+    for preptime in children(head, "preptime") {
+        let duration = parse_preptime(preptime)?;
+        let kind = preptime.attribute("type").unwrap_or("").to_lowercase();
+        let field = if kind.contains("prep") {
+            &mut recipe.prep_time
+        } else if kind.contains("cook") || kind.contains("bak") {
+            &mut recipe.cook_time
+        } else if kind.contains("rest") || kind.contains("stand") || kind.contains("cool") {
+            &mut recipe.rest_time
+        } else {
+            &mut recipe.total_time
+        };
+        *field = Some(duration);
+    }
+
+    if let Some(source) = child(head, "source") {
+        let items: Vec<String> = children(source, "srcitem").filter_map(text).collect();
+        recipe.source_url = items.iter().find(|item| item.starts_with("http")).cloned();
+        let authors: Vec<String> = items
+            .into_iter()
+            .filter(|item| !item.starts_with("http"))
+            .collect();
+        recipe.source_authors = some_if_not_empty(authors);
+    }
+    if let Some(title) = child(head, "bookinfo").and_then(|book| child_text(book, "title")) {
+        recipe.source_book = Some(BookSource {
+            title,
+            ..Default::default()
+        });
+    }
+
+    if let Some(ingredients) = child(node, "ingredients") {
+        let mut list = vec![];
+        collect_ingredients(ingredients, &mut list)?;
+        recipe.ingredients = some_if_not_empty(list);
+    }
+
+    if let Some(directions) = child(node, "directions") {
+        let mut steps = vec![];
+        collect_steps(directions, &mut steps);
+        recipe.steps = some_if_not_empty(steps);
+    }
+
+    if recipe.ingredients.is_none() && recipe.steps.is_none() {
+        return Err("no ingredients or directions".to_string());
+    }
+    Ok(recipe)
+}
+
+// <ing> elements, also inside <ing-div> sections
+fn collect_ingredients(node: Node, list: &mut Vec<Ingredient>) -> Result<(), String> {
+    for element in node.children().filter(Node::is_element) {
+        match element.tag_name().name() {
+            "ing" => list.push(parse_ingredient(element)?),
+            "ing-div" => collect_ingredients(element, list)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+// <ing><amt><qty>2</qty><unit>cups</unit></amt><item>flour</item><prep>sifted</prep></ing>
+fn parse_ingredient(node: Node) -> Result<Ingredient, String> {
+    let name = child_text(node, "item").ok_or_else(|| "ingredient without <item>".to_string())?;
+
+    let mut amount = Amount::new();
+    if let Some(amt) = child(node, "amt") {
+        if let Some(quantity) = parse_quantity(amt)? {
+            amount.set_quantity(quantity);
+        }
+        amount.unit = child_text(amt, "unit")
+            .map(|unit| Unit::from_str(&unit).unwrap_or_default())
+            .unwrap_or_default();
+    }
+
+    let notes: Vec<String> = children(node, "note").filter_map(text).collect();
+    let substitutions = children(node, "alt-ing")
+        .map(|alt| parse_ingredient(alt).map(|sub| sub.ingredient))
+        .collect::<Result<Vec<IngredientData>, String>>()?;
+
+    Ok(Ingredient {
+        ingredient: IngredientData {
+            ingredient_name: name,
+            amounts: vec![amount],
+            processing: children(node, "prep").filter_map(text).collect(),
+            notes: if notes.is_empty() {
+                None
+            } else {
+                Some(notes.join("\n"))
+            },
+            usda_num: None,
+            allergens: None,
+            catalogue_uuid: None,
+        },
+        substitutions,
+    })
+}
+
+// <qty>1 1/2</qty>, <qty><frac><n>1</n><d>2</d></frac></qty> or <range><q1>2</q1><q2>3</q2></range>
+fn parse_quantity(amt: Node) -> Result<Option<Quantity>, String> {
+    if let Some(range) = child(amt, "range") {
+        let low = quantity_text(child(range, "q1"))?;
+        let high = quantity_text(child(range, "q2"))?;
+        return Ok(match (low, high) {
+            (Some(low), Some(high)) => Some(Quantity {
+                value: low.value,
+                max: Some(high.value),
+            }),
+            (low, high) => low.or(high),
+        });
+    }
+    quantity_text(child(amt, "qty"))
+}
+
+fn quantity_text(node: Option<Node>) -> Result<Option<Quantity>, String> {
+    let node = match node {
+        Some(node) => node,
+        None => return Ok(None),
+    };
+    let text = match child(node, "frac") {
+        Some(frac) => {
+            let whole = node
+                .children()
+                .filter(Node::is_text)
+                .filter_map(|t| t.text())
+                .collect::<String>();
+            format!(
+                "{} {}/{}",
+                whole.trim(),
+                child_text(frac, "n").unwrap_or_default(),
+                child_text(frac, "d").unwrap_or_default()
+            )
+        }
+        None => match text(node) {
+            Some(text) => text,
+            None => return Ok(None),
+        },
+    };
+    Quantity::from_str(text.trim())
+        .map(Some)
+        .map_err(|_| format!("invalid amount \"{}\"", text.trim()))
+}
+
+// <yield>4</yield> or <yield><qty>1</qty><unit>loaf</unit></yield>
+fn parse_yield(node: Node) -> Result<Yield, String> {
+    let (quantity, unit) = if child(node, "qty").is_some() || child(node, "range").is_some() {
+        (parse_quantity(node)?, child_text(node, "unit"))
+    } else {
+        let content = text(node).unwrap_or_default();
+        let mut tokens = content.splitn(2, char::is_whitespace);
+        let quantity = tokens.next().and_then(|t| Quantity::from_str(t).ok());
+        (quantity, tokens.next().map(|unit| unit.trim().to_string()))
+    };
+    let amount = quantity
+        .map(|q| q.value)
+        .ok_or_else(|| "invalid yield".to_string())?;
+    Ok(Yield {
+        amount,
+        unit: unit
+            .filter(|unit| !unit.is_empty())
+            .unwrap_or_else(|| "servings".to_string()),
+    })
+}
+
+// A preptime can have several <time> elements, e.g. hours and minutes
+fn parse_preptime(node: Node) -> Result<Duration, String> {
+    let mut total = Duration::default();
+    for time in children(node, "time") {
+        let qty = child_text(time, "qty").unwrap_or_default();
+        let unit = child_text(time, "timeunit").unwrap_or_else(|| "minutes".to_string());
+        total = total
+            + Duration::from_str(&format!("{} {}", qty, unit))
+                .map_err(|_| format!("invalid time \"{} {}\"", qty, unit))?;
+    }
+    Ok(total)
+}
+
+// <directions> holds <step>s, possibly nested in <dir-div>, or just text
+fn collect_steps(node: Node, steps: &mut Vec<Step>) {
+    let elements: Vec<Node> = node.children().filter(Node::is_element).collect();
+    if elements.is_empty() {
+        let content = text(node).unwrap_or_default();
+        for line in content.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            steps.push(Step {
+                step: line.to_string(),
+                ..Default::default()
+            });
+        }
+        return;
+    }
+    for element in elements {
+        match element.tag_name().name() {
+            "step" => {
+                if let Some(step) = text(element) {
+                    steps.push(Step {
+                        step: step.replace('\n', " "),
+                        ..Default::default()
+                    });
+                }
+            }
+            "dir-div" | "substeps" => collect_steps(element, steps),
+            "note" => {
+                if let (Some(last), Some(note)) = (steps.last_mut(), text(element)) {
+                    last.notes = Some(note);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| n.has_tag_name(name))
+}
+
+fn children<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children().filter(move |n| n.has_tag_name(name))
+}
+
+// All text of an element and its descendants, with whitespace collapsed per line
+fn text(node: Node) -> Option<String> {
+    let content: String = node
+        .descendants()
+        .filter(Node::is_text)
+        .filter_map(|t| t.text())
+        .collect();
+    let lines: Vec<String> = content
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<&str>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect();
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
+}
+
+fn child_text(node: Node, name: &str) -> Option<String> {
+    child(node, name).and_then(text)
+}
+
+fn some_if_not_empty<T>(list: Vec<T>) -> Option<Vec<T>> {
+    if list.is_empty() {
+        None
+    } else {
+        Some(list)
+    }
+}
@@ -0,0 +1,406 @@
+use serde::de::DeserializeOwned;
+use serde_json::{json, Map, Value};
+use std::str::FromStr;
+
+use super::FormatError;
+use crate::models::book_source::BookSource;
+use crate::models::duration::Duration;
+use crate::models::haccp_value::HACCPValue;
+use crate::models::ingredient::{Ingredient, IngredientData};
+use crate::models::oven_fan_value::OvenFanValue;
+use crate::models::r#yield::Yield;
+use crate::models::recipe::Recipe;
+use crate::models::recipe_request::RecipeRequest;
+use crate::models::step::Step;
+use crate::models::temperature::Temperature;
+use crate::models::timer::Timer;
+use crate::quantity::Quantity;
+
+/* Prefix of the properties schema.org has no equivalent for, like HACCP values and substitutions.
+ * Other consumers ignore them, but they let a recipe round-trip without losing data. */
+pub const EXTENSION_PREFIX: &str = "rcp";
+pub const EXTENSION_NAMESPACE: &str = "https://github.com/dominikrosser/rcp/ns#";
+
+/* Maps a recipe to a schema.org Recipe (https://schema.org/Recipe) JSON-LD object.
+ * Ingredients are written as text lines using the amounts of the first yield. */
+pub fn to_schema_org(recipe: &Recipe) -> Value {
+    let mut node = Map::new();
+    node.insert(
+        "@context".to_string(),
+        json!(["https://schema.org", { EXTENSION_PREFIX: EXTENSION_NAMESPACE }]),
+    );
+    node.insert("@type".to_string(), json!("Recipe"));
+
+    insert_some(
+        &mut node,
+        "name",
+        recipe.recipe_name.as_ref().map(|n| json!(n)),
+    );
+    insert_some(
+        &mut node,
+        "description",
+        recipe.notes.as_ref().map(|n| json!(n)),
+    );
+    insert_some(
+        &mut node,
+        "url",
+        recipe.source_url.as_ref().map(|u| json!(u)),
+    );
+    insert_some(&mut node, "image", recipe.image.as_ref().map(|i| json!(i)));
+    insert_some(
+        &mut node,
+        "recipeCategory",
+        recipe.categories.as_ref().map(|c| json!(c)),
+    );
+
+    if let Some(authors) = &recipe.source_authors {
+        node.insert(
+            "author".to_string(),
+            authors.iter().map(|name| person(name)).collect(),
+        );
+    }
+    if let Some(book) = &recipe.source_book {
+        node.insert(
+            "isBasedOn".to_string(),
+            json!({
+                "@type": "Book",
+                "name": book.title,
+                "isbn": book.isbn,
+                "author": book.authors.iter().map(|name| person(name)).collect::<Vec<Value>>(),
+                extension("notes"): book.notes,
+            }),
+        );
+    }
+
+    let durations = [
+        ("prepTime".to_string(), recipe.prep_time),
+        ("cookTime".to_string(), recipe.cook_time),
+        ("totalTime".to_string(), recipe.total_time),
+        (extension("ovenTime"), recipe.oven_time),
+        (extension("restTime"), recipe.rest_time),
+    ];
+    for (key, duration) in durations.iter() {
+        insert_some(&mut node, key, duration.map(|d| json!(d.to_iso8601())));
+    }
+
+    if let Some(yields) = &recipe.yields {
+        let texts: Vec<String> = yields
+            .iter()
+            .map(|y| format!("{} {}", y.amount, y.unit))
+            .collect();
+        let recipe_yield = match texts.as_slice() {
+            [single] => json!(single),
+            _ => json!(texts),
+        };
+        node.insert("recipeYield".to_string(), recipe_yield);
+        node.insert(extension("yields"), json!(yields));
+    }
+
+    if let Some(ingredients) = &recipe.ingredients {
+        node.insert(
+            "recipeIngredient".to_string(),
+            ingredients
+                .iter()
+                .map(|ing| json!(ing.get_line(0)))
+                .collect(),
+        );
+        node.insert(extension("ingredients"), json!(ingredients));
+    }
+
+    if let Some(equipment) = &recipe.equipment {
+        node.insert(
+            "tool".to_string(),
+            equipment
+                .iter()
+                .map(|name| json!({ "@type": "HowToTool", "name": name }))
+                .collect(),
+        );
+    }
+
+    if let Some(steps) = &recipe.steps {
+        let instructions = steps
+            .iter()
+            .map(|step| {
+                let mut how_to = Map::new();
+                how_to.insert("@type".to_string(), json!("HowToStep"));
+                how_to.insert("text".to_string(), json!(step.step));
+                insert_some(
+                    &mut how_to,
+                    &extension("notes"),
+                    step.notes.as_ref().map(|n| json!(n)),
+                );
+                insert_some(
+                    &mut how_to,
+                    &extension("haccp"),
+                    step.haccp.as_ref().map(|h| json!(h)),
+                );
+                if !step.timers.is_empty() {
+                    how_to.insert(extension("timers"), json!(step.timers));
+                }
+                Value::Object(how_to)
+            })
+            .collect();
+        node.insert("recipeInstructions".to_string(), instructions);
+    }
+
+    insert_some(
+        &mut node,
+        &extension("ovenTemperature"),
+        recipe.oven_temp.as_ref().map(|t| json!(t)),
+    );
+    insert_some(
+        &mut node,
+        &extension("ovenFan"),
+        recipe.oven_fan.as_ref().map(|f| json!(f.to_string())),
+    );
+
+    Value::Object(node)
+}
+
+pub fn to_schema_org_json(recipe: &Recipe) -> String {
+    serde_json::to_string_pretty(&to_schema_org(recipe)).unwrap_or_default()
+}
+
+/* Reads the first schema.org Recipe in a JSON-LD document, which may also be an array or an @graph.
+ * The extension properties written by to_schema_org are preferred over their lossy schema.org counterparts. */
+pub fn from_schema_org_json(json: &str) -> Result<RecipeRequest, FormatError> {
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| FormatError(format!("Invalid JSON-LD: {}", e)))?;
+    from_schema_org(&value)
+}
+
+pub fn from_schema_org(value: &Value) -> Result<RecipeRequest, FormatError> {
+    let node =
+        find_recipe(value).ok_or_else(|| FormatError("No schema.org Recipe found".to_string()))?;
+
+    let yields = match extension_value::<Vec<Yield>>(node, "yields") {
+        Some(yields) => yields,
+        // Ingredient lines only have one amount, so only the first yield is used
+        None => vec![node
+            .get("recipeYield")
+            .and_then(parse_yield)
+            .unwrap_or_else(default_yield)],
+    };
+
+    let ingredients = match extension_value::<Vec<Ingredient>>(node, "ingredients") {
+        Some(ingredients) => ingredients,
+        None => texts(node.get("recipeIngredient"))
+            .iter()
+            .map(|line| parse_ingredient(line))
+            .collect(),
+    };
+
+    let mut steps = vec![];
+    if let Some(instructions) = node.get("recipeInstructions") {
+        collect_steps(instructions, &mut steps);
+    }
+
+    let authors: Vec<String> = node.get("author").map(names).unwrap_or_default();
+
+    Ok(RecipeRequest {
+        recipe_name: text(node.get("name")),
+        notes: text(node.get("description")),
+        source_url: text(node.get("url")),
+        source_authors: some_if_not_empty(authors),
+        source_book: node.get("isBasedOn").and_then(parse_book),
+        equipment: some_if_not_empty(node.get("tool").map(names).unwrap_or_default()),
+        categories: some_if_not_empty(texts(node.get("recipeCategory"))),
+        diet_overrides: None,
+        image: node.get("image").and_then(image_url),
+        prep_time: duration(node.get("prepTime")),
+        cook_time: duration(node.get("cookTime")),
+        total_time: duration(node.get("totalTime")),
+        oven_time: duration(node.get(extension("ovenTime"))),
+        rest_time: duration(node.get(extension("restTime"))),
+        oven_temp: extension_value::<Temperature>(node, "ovenTemperature"),
+        oven_fan: text(node.get(extension("ovenFan")))
+            .and_then(|fan| OvenFanValue::from_str(&fan).ok()),
+        yields: Some(yields),
+        ingredients: some_if_not_empty(ingredients),
+        steps: some_if_not_empty(steps),
+    })
+}
+
+fn extension(name: &str) -> String {
+    format!("{}:{}", EXTENSION_PREFIX, name)
+}
+
+fn extension_value<T: DeserializeOwned>(node: &Value, name: &str) -> Option<T> {
+    let value = node.get(extension(name))?;
+    serde_json::from_value(value.clone()).ok()
+}
+
+fn insert_some(node: &mut Map<String, Value>, key: &str, value: Option<Value>) {
+    if let Some(value) = value {
+        node.insert(key.to_string(), value);
+    }
+}
+
+fn person(name: &str) -> Value {
+    json!({ "@type": "Person", "name": name })
+}
+
+fn is_recipe(node: &Value) -> bool {
+    match node.get("@type") {
+        Some(Value::String(t)) => t == "Recipe",
+        Some(Value::Array(types)) => types.iter().any(|t| t == "Recipe"),
+        _ => false,
+    }
+}
+
+fn find_recipe(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Array(nodes) => nodes.iter().find_map(find_recipe),
+        Value::Object(_) if is_recipe(value) => Some(value),
+        Value::Object(_) => value.get("@graph").and_then(find_recipe),
+        _ => None,
+    }
+}
+
+// Strings, numbers and objects with a name or text
+fn text(value: Option<&Value>) -> Option<String> {
+    let text = match value? {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::Array(values) => return values.iter().find_map(|v| text(Some(v))),
+        object @ Value::Object(_) => {
+            return text(object.get("name").or_else(|| object.get("text")))
+        }
+        _ => return None,
+    };
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+fn texts(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Array(values)) => values.iter().filter_map(|v| text(Some(v))).collect(),
+        value => text(value).into_iter().collect(),
+    }
+}
+
+fn names(value: &Value) -> Vec<String> {
+    texts(Some(value))
+}
+
+// "https://…", an ImageObject or a list of either
+fn image_url(value: &Value) -> Option<String> {
+    match value {
+        Value::String(url) => Some(url.trim().to_string()).filter(|url| !url.is_empty()),
+        Value::Array(values) => values.iter().find_map(image_url),
+        Value::Object(_) => text(value.get("url").or_else(|| value.get("contentUrl"))),
+        _ => None,
+    }
+}
+
+fn duration(value: Option<&Value>) -> Option<Duration> {
+    text(value).and_then(|d| Duration::from_str(&d).ok())
+}
+
+// "4", "4 servings", "Makes 12 cookies", ["4", "4 servings"]
+pub(crate) fn parse_yield(value: &Value) -> Option<Yield> {
+    match value {
+        Value::Array(values) => {
+            let yields: Vec<Yield> = values.iter().filter_map(parse_yield).collect();
+            // Prefer the variant that names its unit
+            yields
+                .iter()
+                .find(|y| y.unit != "servings")
+                .or_else(|| yields.first())
+                .cloned()
+        }
+        Value::Number(n) => Some(Yield {
+            amount: n.as_f64()?,
+            unit: "servings".to_string(),
+        }),
+        Value::String(s) => {
+            let tokens: Vec<&str> = s.split_whitespace().collect();
+            let pos = tokens.iter().position(|t| Quantity::from_str(t).is_ok())?;
+            let amount = Quantity::from_str(tokens[pos]).ok()?.value;
+            let unit = tokens[pos + 1..].join(" ");
+            Some(Yield {
+                amount,
+                unit: if unit.is_empty() {
+                    "servings".to_string()
+                } else {
+                    unit
+                },
+            })
+        }
+        _ => None,
+    }
+}
+
+// Every ingredient needs one amount per yield, so a recipe without yield gets a single batch
+pub(crate) fn default_yield() -> Yield {
+    Yield {
+        amount: 1.0,
+        unit: "batch".to_string(),
+    }
+}
+
+// Lines the parser doesn't understand are kept as the ingredient name
+pub(crate) fn parse_ingredient(line: &str) -> Ingredient {
+    Ingredient::from_str(line).unwrap_or_else(|_| Ingredient {
+        ingredient: IngredientData {
+            ingredient_name: line.to_string(),
+            ..IngredientData::new()
+        },
+        substitutions: vec![],
+    })
+}
+
+// recipeInstructions can be text, a list of texts, HowToSteps or HowToSections containing them
+fn collect_steps(value: &Value, steps: &mut Vec<Step>) {
+    match value {
+        Value::String(s) => {
+            for line in s.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                steps.push(Step {
+                    step: line.to_string(),
+                    ..Default::default()
+                });
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                collect_steps(value, steps);
+            }
+        }
+        Value::Object(_) => {
+            if let Some(elements) = value.get("itemListElement") {
+                collect_steps(elements, steps);
+            } else if let Some(step) = text(value.get("text").or_else(|| value.get("name"))) {
+                steps.push(Step {
+                    step,
+                    haccp: extension_value::<HACCPValue>(value, "haccp"),
+                    notes: text(value.get(extension("notes"))),
+                    timers: extension_value::<Vec<Timer>>(value, "timers").unwrap_or_default(),
+                });
+            }
+        }
+        _ => {}
+    }
+}
+
+fn parse_book(value: &Value) -> Option<BookSource> {
+    if value.get("@type")? != "Book" {
+        return None;
+    }
+    Some(BookSource {
+        title: text(value.get("name"))?,
+        authors: value.get("author").map(names).unwrap_or_default(),
+        isbn: text(value.get("isbn")),
+        notes: text(value.get(extension("notes"))),
+    })
+}
+
+fn some_if_not_empty<T>(list: Vec<T>) -> Option<Vec<T>> {
+    if list.is_empty() {
+        None
+    } else {
+        Some(list)
+    }
+}
@@ -0,0 +1,222 @@
+use serde::Deserialize;
+use std::str::FromStr;
+
+use super::archive;
+use super::{FormatError, RecipeResults};
+use crate::models::duration::Duration;
+use crate::models::ingredient::{Amount, Ingredient, IngredientData};
+use crate::models::r#yield::Yield;
+use crate::models::recipe_request::RecipeRequest;
+use crate::models::step::Step;
+use crate::models::timer::Timer;
+use crate::models::unit::Unit;
+
+/* Reads a Tandoor export: a zip archive with one zip per recipe, each holding a recipe.json and its image.
+ * A single recipe zip or recipe.json is read as well. */
+pub fn from_tandoor_archive(data: &[u8]) -> Result<RecipeResults, FormatError> {
+    if !archive::is_zip(data) {
+        return Ok(vec![from_tandoor_json(&archive::utf8(data.to_vec())?)]);
+    }
+
+    let files = archive::read_zip(data)?;
+    let nested: Vec<&(String, Vec<u8>)> = files
+        .iter()
+        .filter(|(path, _)| path.ends_with(".zip"))
+        .collect();
+    if nested.is_empty() {
+        return Ok(vec![from_recipe_files(&files)]);
+    }
+
+    Ok(nested
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (path, content))| {
+            archive::read_zip(content)
+                .and_then(|files| from_recipe_files(&files))
+                .map_err(|e| {
+                    let name = archive::file_name(path).trim_end_matches(".zip");
+                    FormatError(format!("Recipe {} ({}): {}", idx + 1, name, e))
+                })
+        })
+        .collect())
+}
+
+// The files of one recipe's zip
+fn from_recipe_files(files: &[(String, Vec<u8>)]) -> Result<RecipeRequest, FormatError> {
+    let (_, json) = files
+        .iter()
+        .find(|(path, _)| archive::file_name(path) == "recipe.json")
+        .ok_or_else(|| FormatError("No recipe.json found".to_string()))?;
+    let mut recipe = from_tandoor_json(&archive::utf8(json.clone())?)?;
+
+    recipe.image = files.iter().find_map(|(path, content)| {
+        archive::image_mime_type(path).map(|mime_type| archive::data_url(mime_type, content))
+    });
+    Ok(recipe)
+}
+
+/* Reads the recipe.json of a Tandoor export. */
+pub fn from_tandoor_json(json: &str) -> Result<RecipeRequest, FormatError> {
+    let tandoor: TandoorRecipe = serde_json::from_str(json)
+        .map_err(|e| FormatError(format!("Invalid Tandoor recipe: {}", e)))?;
+    Ok(tandoor.into_recipe_request())
+}
+
+// Tandoor lists the ingredients with the step that uses them. Times are in minutes.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct TandoorRecipe {
+    name: String,
+    description: Option<String>,
+    keywords: Vec<TandoorName>,
+    steps: Vec<TandoorStep>,
+    working_time: f64,
+    waiting_time: f64,
+    servings: f64,
+    servings_text: String,
+    source_url: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct TandoorName {
+    name: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct TandoorStep {
+    name: String,
+    instruction: String,
+    ingredients: Vec<TandoorIngredient>,
+    time: f64,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct TandoorIngredient {
+    food: Option<TandoorName>,
+    unit: Option<TandoorName>,
+    amount: f64,
+    note: Option<String>,
+    // Headings like "For the sauce" are stored as ingredients
+    is_header: bool,
+    no_amount: bool,
+}
+
+impl TandoorRecipe {
+    fn into_recipe_request(self) -> RecipeRequest {
+        let ingredients: Vec<Ingredient> = self
+            .steps
+            .iter()
+            .flat_map(|step| step.ingredients.iter())
+            .filter(|ing| !ing.is_header)
+            .filter_map(TandoorIngredient::to_ingredient)
+            .collect();
+
+        let steps: Vec<Step> = self
+            .steps
+            .into_iter()
+            .filter_map(|step| {
+                let text = if step.instruction.trim().is_empty() {
+                    step.name.trim().to_string()
+                } else {
+                    step.instruction.trim().to_string()
+                };
+                if text.is_empty() {
+                    return None;
+                }
+                let timers = if step.time > 0.0 {
+                    vec![Timer {
+                        name: non_empty(&step.name),
+                        amount: step.time,
+                        unit: "minutes".to_string(),
+                    }]
+                } else {
+                    vec![]
+                };
+                Some(Step {
+                    step: text,
+                    timers,
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        let minutes = |m: f64| {
+            if m > 0.0 {
+                Some(Duration::from_minutes(m))
+            } else {
+                None
+            }
+        };
+
+        RecipeRequest {
+            recipe_name: non_empty(&self.name),
+            notes: self.description.as_deref().and_then(non_empty),
+            yields: Some(vec![Yield {
+                amount: if self.servings > 0.0 {
+                    self.servings
+                } else {
+                    1.0
+                },
+                unit: non_empty(&self.servings_text).unwrap_or_else(|| "servings".to_string()),
+            }]),
+            prep_time: minutes(self.working_time),
+            rest_time: minutes(self.waiting_time),
+            source_url: self.source_url.as_deref().and_then(non_empty),
+            categories: some_if_not_empty(
+                self.keywords
+                    .iter()
+                    .filter_map(|keyword| non_empty(&keyword.name))
+                    .collect(),
+            ),
+            ingredients: some_if_not_empty(ingredients),
+            steps: some_if_not_empty(steps),
+            ..Default::default()
+        }
+    }
+}
+
+impl TandoorIngredient {
+    fn to_ingredient(&self) -> Option<Ingredient> {
+        let name = non_empty(&self.food.as_ref()?.name)?;
+        let amount = Amount {
+            amount: if self.no_amount { 0.0 } else { self.amount },
+            unit: self
+                .unit
+                .as_ref()
+                .map(|unit| Unit::from_str(&unit.name).unwrap_or_default())
+                .unwrap_or_default(),
+            ..Amount::new()
+        };
+        Some(Ingredient {
+            ingredient: IngredientData {
+                ingredient_name: name,
+                amounts: vec![amount],
+                processing: vec![],
+                notes: self.note.as_deref().and_then(non_empty),
+                usda_num: None,
+                allergens: None,
+                catalogue_uuid: None,
+            },
+            substitutions: vec![],
+        })
+    }
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    if s.trim().is_empty() {
+        None
+    } else {
+        Some(s.trim().to_string())
+    }
+}
+
+fn some_if_not_empty<T>(list: Vec<T>) -> Option<Vec<T>> {
+    if list.is_empty() {
+        None
+    } else {
+        Some(list)
+    }
+}
@@ -0,0 +1,283 @@
+use std::str::FromStr;
+
+use crate::models::ingredient::{Amount, Ingredient, IngredientData};
+use crate::models::unit::Unit;
+use crate::quantity::Quantity;
+
+// Units that can't be converted, but are still clearly units and not part of the name
+const OTHER_UNITS: [&str; 36] = [
+    "bag", "bags", "bunch", "bunches", "can", "cans", "clove", "cloves", "dash", "dashes", "drop",
+    "drops", "handful", "handfuls", "head", "heads", "jar", "jars", "package", "packages",
+    "packet", "packets", "pinch", "pinches", "slice", "slices", "sprig", "sprigs", "stalk",
+    "stalks", "stick", "sticks", "tin", "tins", "sheet", "sheets",
+];
+
+// Describe the ingredient rather than name it, e.g. "2 large eggs"
+const SIZE_WORDS: [&str; 9] = [
+    "small", "medium", "large", "big", "heaped", "heaping", "level", "rounded", "scant",
+];
+
+// Preparation words that are often put before the name, e.g. "1 cup chopped onions"
+const PROCESSING_WORDS: [&str; 22] = [
+    "beaten",
+    "blanched",
+    "boiled",
+    "chilled",
+    "chopped",
+    "crumbled",
+    "crushed",
+    "cubed",
+    "diced",
+    "grated",
+    "halved",
+    "julienned",
+    "mashed",
+    "melted",
+    "minced",
+    "peeled",
+    "quartered",
+    "shredded",
+    "sifted",
+    "sliced",
+    "softened",
+    "toasted",
+];
+
+// Comma separated phrases that are notes rather than processing, e.g. "salt, to taste"
+const NOTE_PREFIXES: [&str; 6] = ["to taste", "for ", "optional", "divided", "plus ", "about "];
+
+/* Parses a free-text ingredient line like “2 cups all-purpose flour, sifted (or cake flour)”.
+ * The amount and unit come first, the name follows, processing tags are separated by commas,
+ * parentheses hold notes or, when starting with “or”, a substitution.
+ * The returned ingredient has exactly one amount. Fails for lines without an ingredient name. */
+pub(crate) fn parse_ingredient_line(line: &str) -> Result<Ingredient, ()> {
+    let line = line.trim().trim_start_matches(&['-', '*', '•'][..]).trim();
+
+    let (main, parentheticals) = split_parentheticals(line);
+    let mut ingredient = parse_ingredient_data(&main, None)?;
+    let amount = ingredient.ingredient.amounts[0].clone();
+
+    for text in parentheticals {
+        let lower = text.to_lowercase();
+        if lower.starts_with("or ") {
+            let substitution = parse_ingredient_data(&text[3..], Some(&amount))?;
+            ingredient.substitutions.push(substitution.ingredient);
+        } else {
+            push_note(&mut ingredient.ingredient, text.trim());
+        }
+    }
+
+    Ok(ingredient)
+}
+
+// Parses everything but parentheses. Substitutions joined by "or" are part of the result.
+// `inherited` is used as amount if the text has none, as in "1 cup butter or margarine".
+fn parse_ingredient_data(text: &str, inherited: Option<&Amount>) -> Result<Ingredient, ()> {
+    let mut tokens = tokenize(text);
+
+    let (quantity, quantity_len) = leading_quantity(&tokens);
+    tokens.drain(..quantity_len);
+
+    let unit = if quantity.is_some() {
+        let (unit, unit_len) = leading_unit(&tokens);
+        tokens.drain(..unit_len);
+        unit
+    } else {
+        Unit::Count
+    };
+
+    if tokens.first().map(|t| t.eq_ignore_ascii_case("of")) == Some(true) {
+        tokens.remove(0);
+    }
+
+    let amount = match (quantity, inherited) {
+        (Some(quantity), _) => {
+            let mut amount = Amount {
+                unit,
+                ..Amount::new()
+            };
+            amount.set_quantity(quantity);
+            amount
+        }
+        (None, Some(inherited)) => inherited.clone(),
+        (None, None) => Amount::new(),
+    };
+
+    let rest = tokens.join(" ");
+    let mut parts = rest.splitn(2, ',');
+    let name_part = parts.next().unwrap_or("").trim();
+    let processing_part = parts.next().unwrap_or("");
+
+    let mut data = IngredientData {
+        amounts: vec![amount],
+        ..Default::default()
+    };
+
+    for phrase in processing_part
+        .split(',')
+        .flat_map(|p| p.split(" and "))
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+    {
+        let lower = phrase.to_lowercase();
+        if NOTE_PREFIXES.iter().any(|prefix| lower.starts_with(prefix)) {
+            push_note(&mut data, phrase);
+        } else {
+            data.processing.push(phrase.to_string());
+        }
+    }
+
+    let mut alternatives = name_part.split(" or ");
+    let name = alternatives.next().unwrap_or("").trim();
+    let name = take_leading_processing(&mut data, name);
+    let name = match name.len().checked_sub(" to taste".len()) {
+        Some(pos)
+            if name.is_char_boundary(pos) && name[pos..].eq_ignore_ascii_case(" to taste") =>
+        {
+            push_note(&mut data, "to taste");
+            name[..pos].trim()
+        }
+        _ => name.as_str(),
+    };
+
+    if name.is_empty() {
+        return Err(());
+    }
+    data.ingredient_name = name.to_string();
+
+    let mut substitutions = vec![];
+    for alternative in alternatives {
+        let substitution = parse_ingredient_data(alternative, Some(&data.amounts[0]))?;
+        substitutions.push(substitution.ingredient);
+    }
+
+    Ok(Ingredient {
+        ingredient: data,
+        substitutions,
+    })
+}
+
+// Splits "flour (or cake flour)" into "flour" and ["or cake flour"]
+fn split_parentheticals(line: &str) -> (String, Vec<String>) {
+    let mut main = String::new();
+    let mut parentheticals = vec![];
+    let mut depth = 0;
+    let mut current = String::new();
+
+    for c in line.chars() {
+        match c {
+            '(' | '[' => {
+                if depth > 0 {
+                    current.push(c);
+                }
+                depth += 1;
+            }
+            ')' | ']' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    parentheticals.push(current.trim().to_string());
+                    current.clear();
+                } else {
+                    current.push(c);
+                }
+            }
+            _ if depth > 0 => current.push(c),
+            _ => main.push(c),
+        }
+    }
+    if !current.trim().is_empty() {
+        parentheticals.push(current.trim().to_string());
+    }
+
+    (main, parentheticals)
+}
+
+// Splits on whitespace and separates numbers glued to units, as in "200g"
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = text.split_whitespace().map(str::to_string).collect();
+
+    if let Some(first) = tokens.first().cloned() {
+        if let Some(pos) = first.find(char::is_alphabetic) {
+            let (number, unit) = first.split_at(pos);
+            if pos > 0 && Quantity::from_str(number).is_ok() {
+                tokens[0] = unit.to_string();
+                tokens.insert(0, number.to_string());
+            }
+        }
+    }
+    tokens
+}
+
+// Returns the quantity at the start of the tokens and how many tokens it spans
+fn leading_quantity(tokens: &[String]) -> (Option<Quantity>, usize) {
+    for len in (1..=tokens.len().min(4)).rev() {
+        if let Ok(quantity) = Quantity::from_str(&tokens[..len].join(" ")) {
+            return (Some(quantity), len);
+        }
+    }
+
+    // "a pinch of salt", "an onion"
+    match tokens.first().map(|t| t.to_lowercase()) {
+        Some(ref t) if t == "a" || t == "an" => (Some(Quantity::new(1.0)), 1),
+        _ => (None, 0),
+    }
+}
+
+// Returns the unit at the start of the tokens and how many tokens it spans
+fn leading_unit(tokens: &[String]) -> (Unit, usize) {
+    if tokens.len() >= 2 {
+        if let Ok(unit @ Unit::Volume(_)) = Unit::from_str(&tokens[..2].join(" ")) {
+            return (unit, 2);
+        }
+    }
+
+    match tokens.first() {
+        Some(token) => match Unit::from_str(token) {
+            Ok(unit @ Unit::Mass(_)) | Ok(unit @ Unit::Volume(_)) => (unit, 1),
+            _ => {
+                let word = token.trim_end_matches('.').to_lowercase();
+                if OTHER_UNITS.contains(&word.as_str()) {
+                    (Unit::Other(word), 1)
+                } else {
+                    (Unit::Count, 0)
+                }
+            }
+        },
+        None => (Unit::Count, 0),
+    }
+}
+
+// Moves "finely chopped" from "finely chopped onions" into processing, returns the rest
+fn take_leading_processing(data: &mut IngredientData, name: &str) -> String {
+    let words: Vec<&str> = name.split_whitespace().collect();
+    let mut phrases: Vec<String> = vec![];
+    let mut taken = 0;
+
+    // The last word is always kept as the name
+    while taken + 1 < words.len() {
+        let word = words[taken].to_lowercase();
+        let next = words[taken + 1].to_lowercase();
+        if SIZE_WORDS.contains(&word.as_str()) || PROCESSING_WORDS.contains(&word.as_str()) {
+            phrases.push(words[taken].to_string());
+            taken += 1;
+        } else if word.ends_with("ly")
+            && PROCESSING_WORDS.contains(&next.as_str())
+            && taken + 2 < words.len()
+        {
+            phrases.push(format!("{} {}", words[taken], words[taken + 1]));
+            taken += 2;
+        } else {
+            break;
+        }
+    }
+
+    data.processing.splice(0..0, phrases);
+    words[taken..].join(" ")
+}
+
+fn push_note(data: &mut IngredientData, note: &str) {
+    data.notes = Some(match data.notes.take() {
+        Some(notes) => format!("{}; {}", notes, note),
+        None => note.to_string(),
+    });
+}
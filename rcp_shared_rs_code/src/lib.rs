@@ -2,4 +2,18 @@
 #![allow(unused_imports)]
 #![allow(unused_variables)]
 
+pub mod allergens;
+pub mod catalogue;
+pub mod costing;
+pub mod density;
+pub mod diets;
+pub mod formats;
+mod ingredient_parser;
+pub mod meal_planning;
 pub mod models;
+pub mod nutrition;
+pub mod pantry;
+pub mod quantity;
+pub mod shopping;
+mod terms;
+pub mod validation;
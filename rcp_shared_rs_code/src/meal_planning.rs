@@ -0,0 +1,88 @@
+use chrono::{Duration as TimeSpan, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+use crate::models::duration::Duration;
+use crate::models::meal_plan::{MealPlanEntry, MealSlot};
+use crate::models::r#yield::Yield;
+use crate::models::recipe::Recipe;
+use crate::models::shopping_list::ShoppingItem;
+use crate::shopping::{self, Aisles, ShoppingError};
+
+/* When to start cooking a planned meal. */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PrepTask {
+    /* The total time of the recipe before the meal is served, on the day before if it takes that long.
+     * The time the meal is served if the recipe has no times. */
+    pub start: NaiveDateTime,
+    pub meal_date: NaiveDate,
+    pub meal_slot: MealSlot,
+    pub recipe_uuid: String,
+    pub recipe_name: Option<String>,
+    pub servings: f64,
+    /* See Recipe::effective_total_time. */
+    pub total_time: Option<Duration>,
+}
+
+/* The yield an entry is made in: its servings in the unit of the recipe's first yield. */
+pub fn planned_yield(entry: &MealPlanEntry, recipe: &Recipe) -> Yield {
+    let unit = recipe
+        .yields
+        .as_ref()
+        .and_then(|yields| yields.first())
+        .map_or_else(|| Yield::new().unit, |y| y.unit.clone());
+    Yield {
+        amount: entry.servings,
+        unit,
+    }
+}
+
+/* The ingredients to buy for the planned meals, see shopping::shopping_items.
+ * Recipes without a yield can't be scaled and are bought as written. */
+pub fn plan_shopping_items(
+    planned: &[(&MealPlanEntry, &Recipe)],
+    aisles: &Aisles,
+) -> Result<Vec<ShoppingItem>, ShoppingError> {
+    let targets: Vec<(Recipe, Yield)> = planned
+        .iter()
+        .map(|(entry, recipe)| {
+            let target = planned_yield(entry, recipe);
+            let mut recipe = (*recipe).clone();
+            if recipe
+                .yields
+                .as_ref()
+                .filter(|yields| !yields.is_empty())
+                .is_none()
+            {
+                recipe.yields = Some(vec![target.clone()]);
+            }
+            (recipe, target)
+        })
+        .collect();
+
+    let targets: Vec<(&Recipe, &Yield)> = targets.iter().map(|(r, y)| (r, y)).collect();
+    shopping::shopping_items(&targets, aisles)
+}
+
+/* When to start cooking each planned meal, earliest first. */
+pub fn prep_schedule(planned: &[(&MealPlanEntry, &Recipe)]) -> Vec<PrepTask> {
+    let mut tasks: Vec<PrepTask> = planned
+        .iter()
+        .map(|(entry, recipe)| {
+            let served_at = NaiveTime::from_hms_opt(0, 0, 0).unwrap()
+                + TimeSpan::minutes(entry.meal_slot.served_at() as i64);
+            let total_time = recipe.effective_total_time();
+            let lead_time = total_time.map_or(0, |time| time.seconds as i64);
+            PrepTask {
+                start: entry.date.and_time(served_at) - TimeSpan::seconds(lead_time),
+                meal_date: entry.date,
+                meal_slot: entry.meal_slot,
+                recipe_uuid: entry.recipe_uuid.clone(),
+                recipe_name: recipe.recipe_name.clone(),
+                servings: entry.servings,
+                total_time,
+            }
+        })
+        .collect();
+    tasks.sort_by(|a, b| a.start.cmp(&b.start).then(a.meal_slot.cmp(&b.meal_slot)));
+    tasks
+}
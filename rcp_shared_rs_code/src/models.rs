@@ -1,10 +1,21 @@
+pub mod allergen;
 pub mod book_source;
+pub mod catalogue_entry;
+pub mod diet;
+pub mod duration;
 pub mod haccp_value;
 pub mod ingredient;
+pub mod meal_plan;
 pub mod oven_fan_value;
+pub mod pantry_item;
+pub mod price_item;
 pub mod recipe;
 pub mod recipe_request;
+pub mod shopping_list;
 pub mod step;
 pub mod temperature;
 pub mod temperature_unit;
+pub mod timer;
+pub mod unit;
+pub mod unit_system;
 pub mod r#yield;
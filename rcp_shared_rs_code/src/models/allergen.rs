@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/* The 14 major allergens that have to be declared in the EU, in the order of Regulation (EU) No 1169/2011, Annex II. */
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Allergen {
+    /* Cereals containing gluten: wheat, rye, barley, oats, spelt, kamut. */
+    Gluten,
+    Crustaceans,
+    Eggs,
+    Fish,
+    Peanuts,
+    Soybeans,
+    /* Including lactose. */
+    Milk,
+    /* Tree nuts: almonds, hazelnuts, walnuts, cashews, pecans, brazil nuts, pistachios, macadamia nuts. */
+    Nuts,
+    Celery,
+    Mustard,
+    Sesame,
+    /* Sulphur dioxide and sulphites above 10 mg/kg. */
+    Sulphites,
+    Lupin,
+    Molluscs,
+}
+
+impl Allergen {
+    pub const ALL: [Allergen; 14] = [
+        Allergen::Gluten,
+        Allergen::Crustaceans,
+        Allergen::Eggs,
+        Allergen::Fish,
+        Allergen::Peanuts,
+        Allergen::Soybeans,
+        Allergen::Milk,
+        Allergen::Nuts,
+        Allergen::Celery,
+        Allergen::Mustard,
+        Allergen::Sesame,
+        Allergen::Sulphites,
+        Allergen::Lupin,
+        Allergen::Molluscs,
+    ];
+}
+
+impl FromStr for Allergen {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "gluten" | "cereals" => Ok(Allergen::Gluten),
+            "crustaceans" | "crustacean" | "shellfish" => Ok(Allergen::Crustaceans),
+            "eggs" | "egg" => Ok(Allergen::Eggs),
+            "fish" => Ok(Allergen::Fish),
+            "peanuts" | "peanut" => Ok(Allergen::Peanuts),
+            "soybeans" | "soybean" | "soy" | "soya" => Ok(Allergen::Soybeans),
+            "milk" | "dairy" | "lactose" => Ok(Allergen::Milk),
+            "nuts" | "nut" | "tree nuts" => Ok(Allergen::Nuts),
+            "celery" => Ok(Allergen::Celery),
+            "mustard" => Ok(Allergen::Mustard),
+            "sesame" => Ok(Allergen::Sesame),
+            "sulphites" | "sulfites" | "sulphur dioxide" | "sulfur dioxide" => {
+                Ok(Allergen::Sulphites)
+            }
+            "lupin" | "lupine" => Ok(Allergen::Lupin),
+            "molluscs" | "mollusks" | "mollusc" | "mollusk" => Ok(Allergen::Molluscs),
+            _ => Err(()),
+        }
+    }
+}
+
+impl std::fmt::Display for Allergen {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Allergen::Gluten => fmt.write_str("Gluten")?,
+            Allergen::Crustaceans => fmt.write_str("Crustaceans")?,
+            Allergen::Eggs => fmt.write_str("Eggs")?,
+            Allergen::Fish => fmt.write_str("Fish")?,
+            Allergen::Peanuts => fmt.write_str("Peanuts")?,
+            Allergen::Soybeans => fmt.write_str("Soybeans")?,
+            Allergen::Milk => fmt.write_str("Milk")?,
+            Allergen::Nuts => fmt.write_str("Nuts")?,
+            Allergen::Celery => fmt.write_str("Celery")?,
+            Allergen::Mustard => fmt.write_str("Mustard")?,
+            Allergen::Sesame => fmt.write_str("Sesame")?,
+            Allergen::Sulphites => fmt.write_str("Sulphites")?,
+            Allergen::Lupin => fmt.write_str("Lupin")?,
+            Allergen::Molluscs => fmt.write_str("Molluscs")?,
+        };
+        Ok(())
+    }
+}
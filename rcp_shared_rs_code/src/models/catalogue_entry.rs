@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+use super::allergen::Allergen;
+use crate::validation::{self, ValidationError};
+
+/* An ingredient of the catalogue, with the names it goes by. Recipe ingredients are linked to an entry,
+ * so “scallions”, “spring onion” and “green onions” are the same ingredient. See catalogue. */
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct CatalogueEntry {
+    pub catalogue_uuid: String,
+
+    /* The name linked ingredients are matched by, e.g. “spring onion”. */
+    pub canonical_name: String,
+
+    /* Other names of the ingredient, e.g. “scallion” and “green onion”. Plurals don't need to be listed. */
+    pub synonyms: Vec<String>,
+
+    /* A free-text category like “vegetables”. */
+    pub category: Option<String>,
+
+    /* Grams per milliliter, to convert volumes of the ingredient into weights. */
+    pub default_density: Option<f64>,
+
+    /* The allergens the ingredient contains. None if they are not known. */
+    pub allergens: Option<Vec<Allergen>>,
+}
+
+impl CatalogueEntry {
+    /* Returns every problem that keeps this entry from being stored, see validation::validate_catalogue_entry. */
+    pub fn validate(&self) -> Vec<ValidationError> {
+        validation::validate_catalogue_entry(self)
+    }
+}
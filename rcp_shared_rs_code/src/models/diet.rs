@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/* Diets a recipe can be suitable for, see diets::DietTaxonomy for which ingredients each one rules out. */
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Diet {
+    Vegan,
+    Vegetarian,
+    Pescatarian,
+    GlutenFree,
+    DairyFree,
+    Halal,
+}
+
+impl Diet {
+    pub const ALL: [Diet; 6] = [
+        Diet::Vegan,
+        Diet::Vegetarian,
+        Diet::Pescatarian,
+        Diet::GlutenFree,
+        Diet::DairyFree,
+        Diet::Halal,
+    ];
+}
+
+impl FromStr for Diet {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s
+            .trim()
+            .to_lowercase()
+            .replace(['-', '_', ' '], "")
+            .as_str()
+        {
+            "vegan" => Ok(Diet::Vegan),
+            "vegetarian" | "veggie" => Ok(Diet::Vegetarian),
+            "pescatarian" | "pescetarian" => Ok(Diet::Pescatarian),
+            "glutenfree" => Ok(Diet::GlutenFree),
+            "dairyfree" | "lactosefree" => Ok(Diet::DairyFree),
+            "halal" => Ok(Diet::Halal),
+            _ => Err(()),
+        }
+    }
+}
+
+impl std::fmt::Display for Diet {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Diet::Vegan => fmt.write_str("Vegan")?,
+            Diet::Vegetarian => fmt.write_str("Vegetarian")?,
+            Diet::Pescatarian => fmt.write_str("Pescatarian")?,
+            Diet::GlutenFree => fmt.write_str("Gluten-free")?,
+            Diet::DairyFree => fmt.write_str("Dairy-free")?,
+            Diet::Halal => fmt.write_str("Halal")?,
+        };
+        Ok(())
+    }
+}
@@ -0,0 +1,255 @@
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::str::FromStr;
+
+const SECONDS_PER_MINUTE: f64 = 60.0;
+const SECONDS_PER_HOUR: f64 = 60.0 * 60.0;
+const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+const SECONDS_PER_WEEK: f64 = 7.0 * 24.0 * 60.0 * 60.0;
+
+/* A span of time, like the prep or cook time of a recipe.
+ * Stored as an ISO 8601 duration (“PT1H30M”), which is also what schema.org uses.
+ * Plain numbers are read as minutes, which is how oven_time used to be stored. */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Duration {
+    pub seconds: u64,
+}
+
+impl Duration {
+    pub fn from_minutes(minutes: f64) -> Self {
+        Self::from_seconds_f64(minutes * SECONDS_PER_MINUTE)
+    }
+
+    pub fn minutes(&self) -> f64 {
+        self.seconds as f64 / SECONDS_PER_MINUTE
+    }
+
+    fn from_seconds_f64(seconds: f64) -> Self {
+        Self {
+            seconds: seconds.max(0.0).round() as u64,
+        }
+    }
+
+    /* Formats the duration as ISO 8601, e.g. “PT1H30M”. Days are kept as hours, since recipes rarely take longer. */
+    pub fn to_iso8601(&self) -> String {
+        let (hours, minutes, seconds) = self.hms();
+        if self.seconds == 0 {
+            return "PT0S".to_string();
+        }
+
+        let mut iso = "PT".to_string();
+        if hours > 0 {
+            iso += &format!("{}H", hours);
+        }
+        if minutes > 0 {
+            iso += &format!("{}M", minutes);
+        }
+        if seconds > 0 {
+            iso += &format!("{}S", seconds);
+        }
+        iso
+    }
+
+    fn hms(&self) -> (u64, u64, u64) {
+        (
+            self.seconds / 3600,
+            self.seconds % 3600 / 60,
+            self.seconds % 60,
+        )
+    }
+}
+
+impl std::ops::Add for Duration {
+    type Output = Duration;
+
+    fn add(self, other: Duration) -> Duration {
+        Duration {
+            seconds: self.seconds + other.seconds,
+        }
+    }
+}
+
+impl FromStr for Duration {
+    type Err = ();
+
+    // Accepts ISO 8601 ("PT1H30M"), plain minutes ("45") and what cooks write ("1 h 30 min", "1:30")
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(());
+        }
+
+        if s.starts_with('P') || s.starts_with('p') {
+            return parse_iso8601(&s[1..]);
+        }
+
+        if let Ok(minutes) = parse_number(s) {
+            return Ok(Duration::from_minutes(minutes));
+        }
+
+        // Hours and minutes, as on a kitchen timer
+        let parts: Vec<&str> = s.split(':').collect();
+        if let [hours, minutes] = parts.as_slice() {
+            let hours: u64 = hours.trim().parse().map_err(|_| ())?;
+            let minutes: u64 = minutes.trim().parse().map_err(|_| ())?;
+            if minutes >= 60 {
+                return Err(());
+            }
+            return Ok(Duration {
+                seconds: hours * 3600 + minutes * 60,
+            });
+        }
+
+        parse_written(s)
+    }
+}
+
+// Parses the part after the "P" of an ISO 8601 duration. Years and months are rejected, their length varies.
+fn parse_iso8601(s: &str) -> Result<Duration, ()> {
+    let mut seconds = 0.0;
+    let mut number = String::new();
+    let mut in_time = false;
+    let mut has_component = false;
+
+    for c in s.chars() {
+        match c.to_ascii_uppercase() {
+            'T' if !in_time && number.is_empty() => in_time = true,
+            '0'..='9' | '.' | ',' => number.push(c),
+            designator => {
+                let value = parse_number(&number)?;
+                let factor = match (in_time, designator) {
+                    (false, 'W') => SECONDS_PER_WEEK,
+                    (false, 'D') => SECONDS_PER_DAY,
+                    (true, 'H') => SECONDS_PER_HOUR,
+                    (true, 'M') => SECONDS_PER_MINUTE,
+                    (true, 'S') => 1.0,
+                    _ => return Err(()),
+                };
+                seconds += value * factor;
+                number.clear();
+                has_component = true;
+            }
+        }
+    }
+
+    if !number.is_empty() || !has_component {
+        return Err(());
+    }
+    Ok(Duration::from_seconds_f64(seconds))
+}
+
+// Parses "1 hour 30 minutes", "1h30m", "90 min" and the like
+fn parse_written(s: &str) -> Result<Duration, ()> {
+    let mut seconds = 0.0;
+    let mut number = String::new();
+    let mut word = String::new();
+    let mut has_component = false;
+
+    // A trailing space flushes the last number-unit pair
+    for c in s.chars().chain(std::iter::once(' ')) {
+        if c.is_ascii_digit() || c == '.' || c == ',' {
+            if !word.is_empty() {
+                seconds += parse_number(&number)? * unit_seconds(&word)?;
+                number.clear();
+                word.clear();
+                has_component = true;
+            }
+            number.push(c);
+        } else if c.is_alphabetic() {
+            word.push(c);
+        } else if !word.is_empty() && !number.is_empty() {
+            seconds += parse_number(&number)? * unit_seconds(&word)?;
+            number.clear();
+            word.clear();
+            has_component = true;
+        } else if !word.is_empty() && word.eq_ignore_ascii_case("and") {
+            word.clear();
+        }
+    }
+
+    if !number.is_empty() || !word.is_empty() || !has_component {
+        return Err(());
+    }
+    Ok(Duration::from_seconds_f64(seconds))
+}
+
+fn unit_seconds(word: &str) -> Result<f64, ()> {
+    match word.to_lowercase().as_str() {
+        "s" | "sec" | "secs" | "second" | "seconds" => Ok(1.0),
+        "m" | "min" | "mins" | "minute" | "minutes" => Ok(SECONDS_PER_MINUTE),
+        "h" | "hr" | "hrs" | "hour" | "hours" | "std" | "stunde" | "stunden" => {
+            Ok(SECONDS_PER_HOUR)
+        }
+        "d" | "day" | "days" | "tag" | "tage" => Ok(SECONDS_PER_DAY),
+        _ => Err(()),
+    }
+}
+
+fn parse_number(s: &str) -> Result<f64, ()> {
+    let value: f64 = s.trim().replace(',', ".").parse().map_err(|_| ())?;
+    if value.is_finite() && value >= 0.0 {
+        Ok(value)
+    } else {
+        Err(())
+    }
+}
+
+// Human readable, e.g. "1 h 30 min"
+impl std::fmt::Display for Duration {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let (hours, minutes, seconds) = self.hms();
+        let mut parts = vec![];
+        if hours > 0 {
+            parts.push(format!("{} h", hours));
+        }
+        if minutes > 0 {
+            parts.push(format!("{} min", minutes));
+        }
+        if seconds > 0 || parts.is_empty() {
+            parts.push(format!("{} s", seconds));
+        }
+        fmt.write_str(&parts.join(" "))
+    }
+}
+
+impl Serialize for Duration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_iso8601())
+    }
+}
+
+impl<'de> Deserialize<'de> for Duration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(DurationVisitor)
+    }
+}
+
+struct DurationVisitor;
+
+impl<'de> Visitor<'de> for DurationVisitor {
+    type Value = Duration;
+
+    fn expecting(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.write_str("an ISO 8601 duration or a number of minutes")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Duration, E> {
+        Duration::from_str(v).map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Duration, E> {
+        if v.is_finite() && v >= 0.0 {
+            Ok(Duration::from_minutes(v))
+        } else {
+            Err(E::invalid_value(de::Unexpected::Float(v), &self))
+        }
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Duration, E> {
+        self.visit_f64(v as f64)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Duration, E> {
+        self.visit_f64(v as f64)
+    }
+}
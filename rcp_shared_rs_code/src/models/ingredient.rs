@@ -1,7 +1,14 @@
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use super::allergen::Allergen;
+use super::unit::{MassUnit, Unit, VolumeUnit};
+use super::unit_system::UnitSystem;
+use crate::ingredient_parser;
+use crate::quantity::{self, Quantity};
 
 // A dict of items, describing an ingredient, and how much of that ingredient to use.
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
 pub struct Ingredient {
     // Wrapper enclosing the relevant data
     pub ingredient: IngredientData,
@@ -18,21 +25,106 @@ impl Ingredient {
             ..Default::default()
         }
     }
+
+    // Like IngredientData::get_line, substitutions are appended as "(or ...)"
+    pub fn get_line(&self, idx: usize) -> String {
+        let mut line = self.ingredient.get_line(idx);
+        for substitution in &self.substitutions {
+            line += &format!(" (or {})", substitution.get_line(idx));
+        }
+        line
+    }
 }
 
-#[derive(Serialize, Deserialize, Default, Debug)]
+// Parses a free-text line like "2 cups all-purpose flour, sifted (or cake flour)"
+impl FromStr for Ingredient {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ingredient_parser::parse_ingredient_line(s)
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
 pub struct Amount {
     pub amount: f64,
-    pub unit: String,
+
+    /* Upper end if the amount is a range, e.g. 3 for “2-3 cloves”. In that case `amount` is the lower end. */
+    pub amount_max: Option<f64>,
+
+    pub unit: Unit,
 }
 
 impl Amount {
     pub fn new() -> Self {
         Default::default()
     }
+
+    pub fn quantity(&self) -> Quantity {
+        Quantity {
+            value: self.amount,
+            max: self.amount_max,
+        }
+    }
+
+    pub fn set_quantity(&mut self, quantity: Quantity) {
+        self.amount = quantity.value;
+        self.amount_max = quantity.max;
+    }
+
+    // Multiplies both ends of the amount
+    pub fn scaled(&self, factor: f64) -> Amount {
+        Amount {
+            amount: self.amount * factor,
+            amount_max: self.amount_max.map(|max| max * factor),
+            unit: self.unit.clone(),
+        }
+    }
+
+    // Converts this amount into `unit`. Returns None if the units measure different things.
+    pub fn convert_to(&self, unit: &Unit) -> Option<Amount> {
+        let factor = self.unit.convert(1.0, unit)?;
+        Some(Amount {
+            unit: unit.clone(),
+            ..self.scaled(factor)
+        })
+    }
+
+    // Expresses this amount in the best fitting unit of `system`.
+    // Counts and unknown units are returned unchanged.
+    pub fn to_unit_system(&self, system: UnitSystem) -> Amount {
+        let target = match &self.unit {
+            Unit::Mass(m) => Unit::Mass(MassUnit::best_for(self.amount * m.grams(), system)),
+            Unit::Volume(v) => {
+                Unit::Volume(VolumeUnit::best_for(self.amount * v.milliliters(), system))
+            }
+            _ => return self.clone(),
+        };
+        self.convert_to(&target).unwrap_or_else(|| self.clone())
+    }
 }
 
-#[derive(Serialize, Deserialize, Default, Debug)]
+impl std::fmt::Display for Amount {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // Scales and jugs are read in decimals, cups and spoons in fractions
+        let format: fn(f64) -> String = match self.unit.system() {
+            Some(UnitSystem::Metric) => quantity::format_decimal,
+            _ => quantity::format_fraction,
+        };
+        fmt.write_str(&format(self.amount))?;
+        if let Some(max) = self.amount_max {
+            write!(fmt, "-{}", format(max))?;
+        }
+
+        let label = self.unit.label(self.amount_max.unwrap_or(self.amount));
+        if !label.is_empty() {
+            write!(fmt, " {}", label)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
 pub struct IngredientData {
     /* A list of dicts which describe the amounts to use. Normally, the list will only contain one dict.
      * In cases where multiple yields need to be stored (i.e. 50 cookies vs 100 cookes vs 250 cookies),
@@ -45,10 +137,18 @@ pub struct IngredientData {
     /* Any notes specific to this ingredient. */
     pub notes: Option<String>,
 
+    pub ingredient_name: String,
+
     /* This corresponds with the index keys in the USDA Standard Reference. It is generally used for easy lookup of nutritional data.
      * If possible, this should be used, and USDA data, when available, is preferable to any other nutritional data source. */
-    // We removed usda_num in favor of an ingredient name
-    pub ingredient_name: String,
+    // Optional: nutrition looks ingredients up by name, this overrides the match, see nutrition::NutrientTable::lookup
+    pub usda_num: Option<String>,
+
+    // The allergens this ingredient contains, declared by the cook. None detects them from the name, see allergens::AllergenRules
+    pub allergens: Option<Vec<Allergen>>,
+
+    // The catalogue entry this ingredient is, e.g. "spring onion" for "scallions". The name above is still what is shown, see catalogue
+    pub catalogue_uuid: Option<String>,
 }
 
 impl IngredientData {
@@ -61,14 +161,33 @@ impl IngredientData {
 
     pub fn get_amount_str(&self, idx: usize) -> String {
         if idx < self.amounts.len() {
-            let mut amount_str = self.amounts[idx].amount.to_string();
-            if !self.amounts[idx].unit.is_empty() {
-                amount_str.push(' ');
-            }
-            amount_str.push_str(&self.amounts[idx].unit);
-            amount_str
+            self.amounts[idx].to_string()
         } else {
             "".to_string()
         }
     }
+
+    // Same as get_amount_str, but converted into the given unit system
+    pub fn get_amount_str_in(&self, idx: usize, system: UnitSystem) -> String {
+        if idx < self.amounts.len() {
+            self.amounts[idx].to_unit_system(system).to_string()
+        } else {
+            "".to_string()
+        }
+    }
+    /* The ingredient as a line of an ingredient list, e.g. “2 cups all-purpose flour, sifted (sift twice)”,
+     * with the amount for the yield at `idx`. Ingredient::from_str reads such lines back. */
+    pub fn get_line(&self, idx: usize) -> String {
+        let mut line = match self.amounts.get(idx) {
+            Some(amount) if amount.amount > 0.0 => format!("{} {}", amount, self.ingredient_name),
+            _ => self.ingredient_name.clone(),
+        };
+        if !self.processing.is_empty() {
+            line += &format!(", {}", self.processing.join(", "));
+        }
+        if let Some(notes) = self.notes.as_ref().filter(|notes| !notes.trim().is_empty()) {
+            line += &format!(" ({})", notes.trim());
+        }
+        line
+    }
 }
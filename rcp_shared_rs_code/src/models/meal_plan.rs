@@ -0,0 +1,95 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::validation::{self, ValidationError};
+
+/* The meals of a day, in the order they are eaten. */
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MealSlot {
+    Breakfast,
+    Lunch,
+    Snack,
+    Dinner,
+}
+
+impl MealSlot {
+    pub const ALL: [MealSlot; 4] = [
+        MealSlot::Breakfast,
+        MealSlot::Lunch,
+        MealSlot::Snack,
+        MealSlot::Dinner,
+    ];
+
+    /* When the meal is served, in minutes after midnight. The prep schedule counts back from it. */
+    pub fn served_at(&self) -> u32 {
+        match self {
+            MealSlot::Breakfast => 8 * 60,
+            MealSlot::Lunch => 12 * 60 + 30,
+            MealSlot::Snack => 16 * 60,
+            MealSlot::Dinner => 19 * 60,
+        }
+    }
+}
+
+impl FromStr for MealSlot {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "breakfast" => Ok(MealSlot::Breakfast),
+            "lunch" => Ok(MealSlot::Lunch),
+            "snack" => Ok(MealSlot::Snack),
+            "dinner" | "supper" => Ok(MealSlot::Dinner),
+            _ => Err(()),
+        }
+    }
+}
+
+impl std::fmt::Display for MealSlot {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MealSlot::Breakfast => fmt.write_str("Breakfast")?,
+            MealSlot::Lunch => fmt.write_str("Lunch")?,
+            MealSlot::Snack => fmt.write_str("Snack")?,
+            MealSlot::Dinner => fmt.write_str("Dinner")?,
+        };
+        Ok(())
+    }
+}
+
+/* A recipe planned for a meal, see meal_planning. */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct MealPlanEntry {
+    pub meal_plan_uuid: String,
+
+    /* Stored as “2020-11-30”. */
+    pub date: NaiveDate,
+
+    pub meal_slot: MealSlot,
+
+    pub recipe_uuid: String,
+
+    /* How much of the recipe to make, in the unit of its first yield, which is usually “servings”. */
+    pub servings: f64,
+}
+
+impl Default for MealPlanEntry {
+    fn default() -> Self {
+        Self {
+            meal_plan_uuid: "".to_string(),
+            date: NaiveDate::default(),
+            meal_slot: MealSlot::Dinner,
+            recipe_uuid: "".to_string(),
+            servings: 4.0,
+        }
+    }
+}
+
+impl MealPlanEntry {
+    /* Returns every problem that keeps this entry from being stored, see validation::validate_meal_plan_entry. */
+    pub fn validate(&self) -> Vec<ValidationError> {
+        validation::validate_meal_plan_entry(self)
+    }
+}
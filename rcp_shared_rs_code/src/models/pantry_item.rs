@@ -0,0 +1,47 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::unit::Unit;
+use crate::validation::{self, ValidationError};
+
+/* An ingredient on hand, e.g. 500 g of butter that keeps until 2020-12-24. See pantry::cookable_recipes. */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct PantryItem {
+    pub pantry_uuid: String,
+
+    /* Matched against ingredient names, “butter” is used for “unsalted butter” as well. */
+    pub ingredient_name: String,
+
+    /* How much is left, in `unit`. 0 means used up. */
+    pub quantity: f64,
+
+    pub unit: Unit,
+
+    /* Stored as “2020-12-24”. Expired items are not cooked with. */
+    pub expiry_date: Option<NaiveDate>,
+}
+
+impl Default for PantryItem {
+    fn default() -> Self {
+        Self {
+            pantry_uuid: "".to_string(),
+            ingredient_name: "".to_string(),
+            quantity: 1.0,
+            unit: Unit::default(),
+            expiry_date: None,
+        }
+    }
+}
+
+impl PantryItem {
+    /* Whether the item expired before `today`. Items keep on their expiry date. */
+    pub fn is_expired(&self, today: NaiveDate) -> bool {
+        matches!(self.expiry_date, Some(date) if date < today)
+    }
+
+    /* Returns every problem that keeps this item from being stored, see validation::validate_pantry_item. */
+    pub fn validate(&self) -> Vec<ValidationError> {
+        validation::validate_pantry_item(self)
+    }
+}
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+use super::unit::Unit;
+use crate::validation::{self, ValidationError};
+
+/* What an ingredient costs to buy, e.g. a 25 kg sack of flour for 18.50. See costing::recipe_cost. */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct PriceItem {
+    pub price_uuid: String,
+
+    /* Matched against ingredient names, “butter” prices “unsalted butter” as well. */
+    pub ingredient_name: String,
+
+    /* The unit the pack size is given in: a weight, a volume, a count (“30 eggs”) or anything else (“2 bunches”). */
+    pub purchase_unit: Unit,
+
+    pub pack_size: f64,
+
+    /* The price of one pack, in the kitchen's currency. */
+    pub price: f64,
+
+    /* The usable part of what is bought after trimming, peeling or boning, in percent, e.g. 90 for onions. */
+    pub trim_yield: f64,
+}
+
+impl Default for PriceItem {
+    fn default() -> Self {
+        Self {
+            price_uuid: "".to_string(),
+            ingredient_name: "".to_string(),
+            purchase_unit: Unit::default(),
+            pack_size: 1.0,
+            price: 0.0,
+            trim_yield: 100.0,
+        }
+    }
+}
+
+impl PriceItem {
+    /* The price of one purchase unit that ends up in the dish, e.g. of one usable kg. */
+    pub fn usable_unit_price(&self) -> f64 {
+        self.price / (self.pack_size * self.trim_yield / 100.0)
+    }
+
+    /* Returns every problem that keeps this price from being stored, see validation::validate_price_item. */
+    pub fn validate(&self) -> Vec<ValidationError> {
+        validation::validate_price_item(self)
+    }
+}
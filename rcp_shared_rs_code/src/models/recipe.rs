@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 use super::book_source::BookSource;
-use super::ingredient::Ingredient;
+use super::diet::Diet;
+use super::duration::Duration;
+use super::ingredient::{Amount, Ingredient, IngredientData};
 use super::oven_fan_value::OvenFanValue;
 use super::r#yield::Yield;
 use super::step::Step;
@@ -10,7 +13,7 @@ use super::temperature_unit::TemperatureUnit;
 use super::recipe_request::RecipeRequest;
 
 // See Open Recipe Format
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
 #[serde(default)]
 pub struct Recipe {
     // recipe_uuid
@@ -30,7 +33,19 @@ pub struct Recipe {
 
     /* How long the dish should spend in the oven.
      * This is an overall value, which refers to the recipe as a whole. If multiple oven times are used, they should be specified in the recipe. */
-    pub oven_time: Option<f64>,
+    pub oven_time: Option<Duration>,
+
+    /* Time needed before cooking starts, e.g. chopping and marinating. */
+    pub prep_time: Option<Duration>,
+
+    /* Time spent actively cooking or baking. */
+    pub cook_time: Option<Duration>,
+
+    /* Time the dish needs to rest, cool or set before it can be served. */
+    pub rest_time: Option<Duration>,
+
+    /* Time from start to finish. If not specified, it can be derived from the other times, see effective_total_time. */
+    pub total_time: Option<Duration>,
 
     /* A list of dicts, defining which food items are to be added to the recipe. These items should be listed in the order in which they are to be used.
      * Bearing this in mind, a particular item may be listed multiple times, if it is to be used multiple times and/or at different quantities in a recipe.
@@ -51,6 +66,18 @@ pub struct Recipe {
     /* The URL that this recipe was copied from, if applicable. In the case of a recipe-hosting website, this may refer to the official URL at which the recipe is hosted. */
     pub source_url: Option<String>,
 
+    /* Categories and tags like “Dessert” or “Vegetarian”, as the user or the imported recipe manager named them. */
+    pub categories: Option<Vec<String>>,
+
+    /* Diets set by hand: true marks the recipe as suitable, false as not, whatever its ingredients are. See diets::recipe_diets. */
+    pub diet_overrides: Option<BTreeMap<Diet, bool>>,
+
+    /* A photo of the dish: the URL of an image or, for imported photos, a data: URL. */
+    pub image: Option<String>,
+
+    /* Cookware and appliances needed, e.g. “large pot” or “stand mixer”. */
+    pub equipment: Option<Vec<String>>,
+
     /* A list, in order, of steps to be performed on the recipe. Each item in the list is a dict, as specified below. */
     pub steps: Option<Vec<Step>>,
 
@@ -67,11 +94,19 @@ impl From<RecipeRequest> for Recipe {
             oven_fan: rr.oven_fan,
             oven_temp: rr.oven_temp,
             oven_time: rr.oven_time,
+            prep_time: rr.prep_time,
+            cook_time: rr.cook_time,
+            rest_time: rr.rest_time,
+            total_time: rr.total_time,
             ingredients: rr.ingredients,
             notes: rr.notes,
             source_book: rr.source_book,
             source_authors: rr.source_authors,
             source_url: rr.source_url,
+            categories: rr.categories,
+            diet_overrides: rr.diet_overrides,
+            image: rr.image,
+            equipment: rr.equipment,
             steps: rr.steps,
             yields: rr.yields,
         }
@@ -97,4 +132,98 @@ impl Recipe {
     //     }
     // }
 
+    /* The total time if specified, otherwise the sum of prep, cook and rest time.
+     * Recipes stored before these times existed only have oven_time, which is used as a last resort. */
+    pub fn effective_total_time(&self) -> Option<Duration> {
+        if self.total_time.is_some() {
+            return self.total_time;
+        }
+
+        let parts = [self.prep_time, self.cook_time, self.rest_time];
+        let sum = parts.iter().flatten().fold(None, |sum: Option<Duration>, d| {
+            Some(sum.map_or(*d, |sum| sum + *d))
+        });
+        sum.or(self.oven_time)
+    }
+
+    /* Returns a copy of this recipe for the `target` yield, with every ingredient and substitution amount scaled.
+     * Only stored yields with the same unit as the target are considered: a matching yield is used as is,
+     * a target between two stored yields is interpolated and anything else is scaled from the nearest yield.
+     * The returned recipe only has the target yield and one amount per ingredient.
+     * Returns None if the recipe has no yield in the target's unit. */
+    pub fn scaled(&self, target: &Yield) -> Option<Recipe> {
+        let yields: Vec<(usize, f64)> = self
+            .yields
+            .as_ref()?
+            .iter()
+            .enumerate()
+            .filter(|(_, y)| {
+                y.amount > 0.0 && y.unit.trim().eq_ignore_ascii_case(target.unit.trim())
+            })
+            .map(|(idx, y)| (idx, y.amount))
+            .collect();
+
+        if yields.is_empty() {
+            return None;
+        }
+
+        let mut recipe = self.clone();
+        recipe.yields = Some(vec![target.clone()]);
+        if let Some(ingredients) = recipe.ingredients.as_mut() {
+            for ing in ingredients.iter_mut() {
+                scale_ingredient_data(&mut ing.ingredient, &yields, target.amount);
+                for sub in ing.substitutions.iter_mut() {
+                    scale_ingredient_data(sub, &yields, target.amount);
+                }
+            }
+        }
+        Some(recipe)
+    }
+}
+
+// `yields` holds (index into amounts, yield amount) of every usable stored yield
+fn scale_ingredient_data(data: &mut IngredientData, yields: &[(usize, f64)], target: f64) {
+    let known: Vec<(f64, &Amount)> = yields
+        .iter()
+        .filter_map(|(idx, y)| data.amounts.get(*idx).map(|a| (*y, a)))
+        .collect();
+
+    let lower = known
+        .iter()
+        .filter(|(y, _)| *y <= target)
+        .max_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    let upper = known
+        .iter()
+        .filter(|(y, _)| *y >= target)
+        .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    let interpolated = match (lower, upper) {
+        (Some((y_lo, a_lo)), Some((y_hi, _))) if y_lo == y_hi => Some((*a_lo).clone()),
+        (Some((y_lo, a_lo)), Some((y_hi, a_hi))) => a_hi.convert_to(&a_lo.unit).map(|a_hi| {
+            let t = (target - y_lo) / (y_hi - y_lo);
+            Amount {
+                amount: a_lo.amount + (a_hi.amount - a_lo.amount) * t,
+                amount_max: match (a_lo.amount_max, a_hi.amount_max) {
+                    (Some(lo), Some(hi)) => Some(lo + (hi - lo) * t),
+                    _ => None,
+                },
+                unit: a_lo.unit.clone(),
+            }
+        }),
+        _ => None,
+    };
+
+    let scaled = interpolated.or_else(|| {
+        known
+            .iter()
+            .min_by(|a, b| {
+                (a.0 - target)
+                    .abs()
+                    .partial_cmp(&(b.0 - target).abs())
+                    .unwrap()
+            })
+            .map(|(y, a)| a.scaled(target / y))
+    });
+
+    data.amounts = scaled.into_iter().collect();
 }
@@ -1,17 +1,25 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::default::Default;
 
 use super::book_source::BookSource;
+use super::diet::Diet;
+use super::duration::Duration;
 use super::ingredient::Ingredient;
 use super::oven_fan_value::OvenFanValue;
 use super::r#yield::Yield;
 use super::step::Step;
 use super::temperature::Temperature;
+use crate::validation::{self, ValidationError};
 
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
 pub struct RecipeRequest {
     pub recipe_name: Option<String>,
-    pub oven_time: Option<f64>,
+    pub oven_time: Option<Duration>,
+    pub prep_time: Option<Duration>,
+    pub cook_time: Option<Duration>,
+    pub rest_time: Option<Duration>,
+    pub total_time: Option<Duration>,
     pub notes: Option<String>,
     pub oven_fan: Option<OvenFanValue>,
     pub oven_temp: Option<Temperature>,
@@ -19,6 +27,10 @@ pub struct RecipeRequest {
     pub source_authors: Option<Vec<String>>,
     pub source_url: Option<String>,
     pub ingredients: Option<Vec<Ingredient>>,
+    pub categories: Option<Vec<String>>,
+    pub diet_overrides: Option<BTreeMap<Diet, bool>>,
+    pub image: Option<String>,
+    pub equipment: Option<Vec<String>>,
     pub steps: Option<Vec<Step>>,
     pub yields: Option<Vec<Yield>>,
 }
@@ -30,4 +42,9 @@ impl RecipeRequest {
             ..Default::default()
         }
     }
+
+    /* Returns every problem that keeps this recipe from being stored, see validation::validate_recipe_request. */
+    pub fn validate(&self) -> Vec<ValidationError> {
+        validation::validate_recipe_request(self)
+    }
 }
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+use super::ingredient::Amount;
+use super::r#yield::Yield;
+
+/* The ingredients to buy for several recipes, see shopping::shopping_items. */
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct ShoppingList {
+    pub shopping_list_uuid: String,
+
+    pub name: Option<String>,
+
+    /* The recipes the list is for, each with the yield it is made in. */
+    pub recipes: Vec<ShoppingListRecipe>,
+
+    /* Sorted by aisle, in the order of the aisles file. */
+    pub items: Vec<ShoppingItem>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct ShoppingListRecipe {
+    pub recipe_uuid: String,
+
+    pub recipe_name: Option<String>,
+
+    /* The yield to buy for, e.g. 6 servings of a recipe written for 4. */
+    pub target: Yield,
+}
+
+/* One ingredient of a shopping list, added up across all recipes that use it. */
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct ShoppingItem {
+    pub ingredient_name: String,
+
+    /* One amount per kind of unit that can't be added up, e.g. “2 cloves” and “1 head” of garlic.
+     * Empty if no recipe gives an amount, like for “salt to taste”. */
+    pub amounts: Vec<Amount>,
+
+    pub aisle: String,
+
+    /* The names of the recipes that need the ingredient. */
+    pub recipes: Vec<String>,
+
+    pub checked: bool,
+}
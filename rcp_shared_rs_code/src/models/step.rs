@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
 
 use super::haccp_value::HACCPValue;
+use super::timer::Timer;
 
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
 pub struct Step {
     /* The only item in the dict that is absolutely required. */
     pub step: String,
@@ -12,4 +13,8 @@ pub struct Step {
 
     /* A list of notes relevant to this step. Often known as “bench notes” to professionals. */
     pub notes: Option<String>,
+
+    /* Timers mentioned in this step, in the order they appear in the text. */
+    #[serde(default)]
+    pub timers: Vec<Timer>,
 }
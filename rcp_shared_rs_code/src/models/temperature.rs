@@ -1,9 +1,78 @@
 use serde::{Deserialize, Serialize};
 
 use super::temperature_unit::TemperatureUnit;
+use crate::quantity;
 
-#[derive(Serialize, Deserialize, Default, Debug)]
+// Gas marks and the Celsius values printed next to them in UK cookbooks
+const GAS_MARKS: [(f64, f64); 12] = [
+    (0.25, 110.0),
+    (0.5, 120.0),
+    (1.0, 140.0),
+    (2.0, 150.0),
+    (3.0, 170.0),
+    (4.0, 180.0),
+    (5.0, 190.0),
+    (6.0, 200.0),
+    (7.0, 220.0),
+    (8.0, 230.0),
+    (9.0, 240.0),
+    (10.0, 260.0),
+];
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
 pub struct Temperature {
     pub amount: f64,
     pub unit: TemperatureUnit,
 }
+
+impl Temperature {
+    pub fn to_celsius(&self) -> f64 {
+        match self.unit {
+            TemperatureUnit::Celsius => self.amount,
+            TemperatureUnit::Fahrenheit => (self.amount - 32.0) * 5.0 / 9.0,
+            TemperatureUnit::GasMark => nearest_gas_mark(|(mark, _)| mark, self.amount).1,
+        }
+    }
+
+    /* Converts into `unit`, rounded the way oven dials are marked:
+     * to 5 degrees Celsius, 10 degrees Fahrenheit or the nearest gas mark. */
+    pub fn to_unit(&self, unit: &TemperatureUnit) -> Temperature {
+        if &self.unit == unit {
+            return self.clone();
+        }
+
+        let celsius = self.to_celsius();
+        let amount = match unit {
+            TemperatureUnit::Celsius => (celsius / 5.0).round() * 5.0,
+            TemperatureUnit::Fahrenheit => ((celsius * 9.0 / 5.0 + 32.0) / 10.0).round() * 10.0,
+            TemperatureUnit::GasMark => nearest_gas_mark(|(_, c)| c, celsius).0,
+        };
+
+        Temperature {
+            amount,
+            unit: unit.clone(),
+        }
+    }
+}
+
+// Looks up the (gas mark, celsius) pair whose `key` is closest to `value`
+fn nearest_gas_mark<F: Fn((f64, f64)) -> f64>(key: F, value: f64) -> (f64, f64) {
+    GAS_MARKS
+        .iter()
+        .copied()
+        .min_by(|a, b| (key(*a) - value).abs().total_cmp(&(key(*b) - value).abs()))
+        .unwrap()
+}
+
+impl std::fmt::Display for Temperature {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.unit {
+            TemperatureUnit::Celsius => write!(fmt, "{} °C", self.amount)?,
+            TemperatureUnit::Fahrenheit => write!(fmt, "{} °F", self.amount)?,
+            TemperatureUnit::GasMark => {
+                write!(fmt, "Gas Mark {}", quantity::format_fraction(self.amount))?
+            }
+        };
+        Ok(())
+    }
+}
@@ -1,10 +1,12 @@
 use serde::{Deserialize, Serialize};
 pub use std::str::FromStr;
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum TemperatureUnit {
     Celsius,
     Fahrenheit,
+    // Oven setting used in UK cookbooks, from 1/4 to 10
+    GasMark,
 }
 
 impl Default for TemperatureUnit {
@@ -18,8 +20,9 @@ impl FromStr for TemperatureUnit {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
-            "celsius" => Ok(TemperatureUnit::Celsius),
-            "fahrenheit" => Ok(TemperatureUnit::Fahrenheit),
+            "celsius" | "c" | "°c" => Ok(TemperatureUnit::Celsius),
+            "fahrenheit" | "f" | "°f" => Ok(TemperatureUnit::Fahrenheit),
+            "gas mark" | "gasmark" | "gas" => Ok(TemperatureUnit::GasMark),
             _ => Err(()),
         }
     }
@@ -30,6 +33,7 @@ impl std::fmt::Display for TemperatureUnit {
         match self {
             TemperatureUnit::Celsius => fmt.write_str("Celsius")?,
             TemperatureUnit::Fahrenheit => fmt.write_str("Fahrenheit")?,
+            TemperatureUnit::GasMark => fmt.write_str("Gas Mark")?,
         };
         Ok(())
     }
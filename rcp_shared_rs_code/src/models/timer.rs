@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use super::duration::Duration;
+
+/* A timer to set during a step, e.g. “bake for 25 minutes”.
+ * Amount and unit are kept as written, so the step text can still be matched against them. */
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct Timer {
+    /* What the timer is for, if the step names it. */
+    pub name: Option<String>,
+
+    pub amount: f64,
+
+    /* Usually “minutes” or “hours”. */
+    pub unit: String,
+}
+
+impl Timer {
+    pub fn duration(&self) -> Option<Duration> {
+        Duration::from_str(&format!("{} {}", self.amount, self.unit)).ok()
+    }
+}
+
+// As it reads in the step text, e.g. "25 minutes"
+impl std::fmt::Display for Timer {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(fmt, "{}", self.amount)?;
+        if !self.unit.is_empty() {
+            write!(fmt, " {}", self.unit)?;
+        }
+        Ok(())
+    }
+}
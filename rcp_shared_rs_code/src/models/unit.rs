@@ -0,0 +1,277 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::str::FromStr;
+
+use super::unit_system::UnitSystem;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MassUnit {
+    Milligram,
+    Gram,
+    Kilogram,
+    Ounce,
+    Pound,
+}
+
+impl MassUnit {
+    /* How many grams one of this unit weighs. */
+    pub fn grams(&self) -> f64 {
+        match self {
+            MassUnit::Milligram => 0.001,
+            MassUnit::Gram => 1.0,
+            MassUnit::Kilogram => 1000.0,
+            MassUnit::Ounce => 28.349523125,
+            MassUnit::Pound => 453.59237,
+        }
+    }
+
+    pub fn system(&self) -> UnitSystem {
+        match self {
+            MassUnit::Milligram | MassUnit::Gram | MassUnit::Kilogram => UnitSystem::Metric,
+            MassUnit::Ounce | MassUnit::Pound => UnitSystem::Imperial,
+        }
+    }
+
+    // Picks the unit a cook would use for the given weight
+    pub fn best_for(grams: f64, system: UnitSystem) -> Self {
+        match system {
+            UnitSystem::Metric if grams < 1.0 => MassUnit::Milligram,
+            UnitSystem::Metric if grams < 1000.0 => MassUnit::Gram,
+            UnitSystem::Metric => MassUnit::Kilogram,
+            UnitSystem::Imperial if grams < MassUnit::Pound.grams() => MassUnit::Ounce,
+            UnitSystem::Imperial => MassUnit::Pound,
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            MassUnit::Milligram => "mg",
+            MassUnit::Gram => "g",
+            MassUnit::Kilogram => "kg",
+            MassUnit::Ounce => "oz",
+            MassUnit::Pound => "lb",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VolumeUnit {
+    Milliliter,
+    Centiliter,
+    Deciliter,
+    Liter,
+    Teaspoon,
+    Tablespoon,
+    FluidOunce,
+    Cup,
+    Pint,
+    Quart,
+    Gallon,
+}
+
+impl VolumeUnit {
+    /* How many milliliters fit into one of this unit. Imperial units are US customary. */
+    pub fn milliliters(&self) -> f64 {
+        match self {
+            VolumeUnit::Milliliter => 1.0,
+            VolumeUnit::Centiliter => 10.0,
+            VolumeUnit::Deciliter => 100.0,
+            VolumeUnit::Liter => 1000.0,
+            VolumeUnit::Teaspoon => 4.92892159375,
+            VolumeUnit::Tablespoon => 14.78676478125,
+            VolumeUnit::FluidOunce => 29.5735295625,
+            VolumeUnit::Cup => 236.5882365,
+            VolumeUnit::Pint => 473.176473,
+            VolumeUnit::Quart => 946.352946,
+            VolumeUnit::Gallon => 3785.411784,
+        }
+    }
+
+    pub fn system(&self) -> UnitSystem {
+        match self {
+            VolumeUnit::Milliliter
+            | VolumeUnit::Centiliter
+            | VolumeUnit::Deciliter
+            | VolumeUnit::Liter => UnitSystem::Metric,
+            _ => UnitSystem::Imperial,
+        }
+    }
+
+    // Picks the unit a cook would use for the given volume
+    pub fn best_for(milliliters: f64, system: UnitSystem) -> Self {
+        match system {
+            UnitSystem::Metric if milliliters < 1000.0 => VolumeUnit::Milliliter,
+            UnitSystem::Metric => VolumeUnit::Liter,
+            UnitSystem::Imperial if milliliters < VolumeUnit::Tablespoon.milliliters() => {
+                VolumeUnit::Teaspoon
+            }
+            UnitSystem::Imperial if milliliters < VolumeUnit::Cup.milliliters() / 4.0 => {
+                VolumeUnit::Tablespoon
+            }
+            UnitSystem::Imperial if milliliters < VolumeUnit::Quart.milliliters() => {
+                VolumeUnit::Cup
+            }
+            UnitSystem::Imperial if milliliters < VolumeUnit::Gallon.milliliters() => {
+                VolumeUnit::Quart
+            }
+            UnitSystem::Imperial => VolumeUnit::Gallon,
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            VolumeUnit::Milliliter => "ml",
+            VolumeUnit::Centiliter => "cl",
+            VolumeUnit::Deciliter => "dl",
+            VolumeUnit::Liter => "l",
+            VolumeUnit::Teaspoon => "tsp",
+            VolumeUnit::Tablespoon => "tbsp",
+            VolumeUnit::FluidOunce => "fl oz",
+            VolumeUnit::Cup => "cup",
+            VolumeUnit::Pint => "pint",
+            VolumeUnit::Quart => "quart",
+            VolumeUnit::Gallon => "gallon",
+        }
+    }
+}
+
+/* The unit of an ingredient amount.
+ * Stored as a plain string (its symbol), so documents written before units were typed still deserialize. */
+#[derive(Clone, Debug, PartialEq, Default)]
+pub enum Unit {
+    Mass(MassUnit),
+    Volume(VolumeUnit),
+
+    /* Countable items without a measuring unit, e.g. “3 eggs”. */
+    #[default]
+    Count,
+
+    /* Anything we don't understand (“pinch”, “clove”, “can”). Kept verbatim and never converted. */
+    Other(String),
+}
+
+impl Unit {
+    pub fn system(&self) -> Option<UnitSystem> {
+        match self {
+            Unit::Mass(m) => Some(m.system()),
+            Unit::Volume(v) => Some(v.system()),
+            _ => None,
+        }
+    }
+
+    pub fn is_convertible_to(&self, other: &Unit) -> bool {
+        match (self, other) {
+            (Unit::Mass(_), Unit::Mass(_)) | (Unit::Volume(_), Unit::Volume(_)) => true,
+            (a, b) => a == b,
+        }
+    }
+
+    // Converts a value given in this unit into `to`. Returns None for incompatible units.
+    pub fn convert(&self, value: f64, to: &Unit) -> Option<f64> {
+        match (self, to) {
+            (Unit::Mass(from), Unit::Mass(to)) => Some(value * from.grams() / to.grams()),
+            (Unit::Volume(from), Unit::Volume(to)) => {
+                Some(value * from.milliliters() / to.milliliters())
+            }
+            (a, b) if a == b => Some(value),
+            _ => None,
+        }
+    }
+
+    // The unit as it should be shown next to `amount`, e.g. "cups" for 2 cups
+    pub fn label(&self, amount: f64) -> String {
+        let symbol = self.to_string();
+        let pluralizes = matches!(
+            self,
+            Unit::Volume(VolumeUnit::Cup)
+                | Unit::Volume(VolumeUnit::Pint)
+                | Unit::Volume(VolumeUnit::Quart)
+                | Unit::Volume(VolumeUnit::Gallon)
+        );
+        if pluralizes && amount > 1.0 {
+            symbol + "s"
+        } else {
+            symbol
+        }
+    }
+}
+
+impl FromStr for Unit {
+    type Err = ();
+
+    // Never fails: unknown spellings end up in Unit::Other
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim().trim_end_matches('.');
+
+        // Single letter abbreviations are case sensitive in cookbooks
+        match trimmed {
+            "t" => return Ok(Unit::Volume(VolumeUnit::Teaspoon)),
+            "T" => return Ok(Unit::Volume(VolumeUnit::Tablespoon)),
+            _ => {}
+        }
+
+        let unit = match trimmed.to_lowercase().as_str() {
+            "" | "x" | "each" | "pc" | "pcs" | "piece" | "pieces" | "stück" | "stk" => Unit::Count,
+
+            "mg" | "milligram" | "milligrams" | "milligramm" => Unit::Mass(MassUnit::Milligram),
+            "g" | "gr" | "gram" | "grams" | "gramm" | "gramme" | "grammes" => {
+                Unit::Mass(MassUnit::Gram)
+            }
+            "kg" | "kilo" | "kilos" | "kilogram" | "kilograms" | "kilogramm" => {
+                Unit::Mass(MassUnit::Kilogram)
+            }
+            "oz" | "ounce" | "ounces" => Unit::Mass(MassUnit::Ounce),
+            "lb" | "lbs" | "pound" | "pounds" => Unit::Mass(MassUnit::Pound),
+
+            "ml" | "milliliter" | "milliliters" | "millilitre" | "millilitres" => {
+                Unit::Volume(VolumeUnit::Milliliter)
+            }
+            "cl" | "centiliter" | "centiliters" | "centilitre" | "centilitres" => {
+                Unit::Volume(VolumeUnit::Centiliter)
+            }
+            "dl" | "deciliter" | "deciliters" | "decilitre" | "decilitres" => {
+                Unit::Volume(VolumeUnit::Deciliter)
+            }
+            "l" | "liter" | "liters" | "litre" | "litres" => Unit::Volume(VolumeUnit::Liter),
+            "tsp" | "tsps" | "teaspoon" | "teaspoons" | "tl" | "teelöffel" => {
+                Unit::Volume(VolumeUnit::Teaspoon)
+            }
+            "tbsp" | "tbsps" | "tbs" | "tbl" | "tablespoon" | "tablespoons" | "el"
+            | "esslöffel" => Unit::Volume(VolumeUnit::Tablespoon),
+            "fl oz" | "fl. oz" | "floz" | "fluid ounce" | "fluid ounces" => {
+                Unit::Volume(VolumeUnit::FluidOunce)
+            }
+            "c" | "cup" | "cups" => Unit::Volume(VolumeUnit::Cup),
+            "pt" | "pint" | "pints" => Unit::Volume(VolumeUnit::Pint),
+            "qt" | "quart" | "quarts" => Unit::Volume(VolumeUnit::Quart),
+            "gal" | "gallon" | "gallons" => Unit::Volume(VolumeUnit::Gallon),
+
+            _ => Unit::Other(s.trim().to_string()),
+        };
+        Ok(unit)
+    }
+}
+
+impl std::fmt::Display for Unit {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Unit::Mass(m) => fmt.write_str(m.symbol())?,
+            Unit::Volume(v) => fmt.write_str(v.symbol())?,
+            Unit::Count => {}
+            Unit::Other(s) => fmt.write_str(s)?,
+        };
+        Ok(())
+    }
+}
+
+impl Serialize for Unit {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Unit {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Ok(Unit::from_str(&s).unwrap_or_default())
+    }
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/* The measuring system an amount should be displayed in.
+ * Imperial volumes use the US customary definitions (1 cup = 236.59 ml), since that is what most recipes mean. */
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum UnitSystem {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+impl FromStr for UnitSystem {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "metric" => Ok(UnitSystem::Metric),
+            "imperial" | "us" => Ok(UnitSystem::Imperial),
+            _ => Err(()),
+        }
+    }
+}
+
+impl std::fmt::Display for UnitSystem {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            UnitSystem::Metric => fmt.write_str("Metric")?,
+            UnitSystem::Imperial => fmt.write_str("Imperial")?,
+        };
+        Ok(())
+    }
+}
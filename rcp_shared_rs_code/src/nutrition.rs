@@ -0,0 +1,400 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::models::ingredient::{Amount, IngredientData};
+use crate::models::recipe::Recipe;
+use crate::models::unit::Unit;
+use crate::quantity::Quantity;
+use crate::terms;
+
+// A few dozen common ingredients from USDA SR Legacy, with an extra "Aliases" column for matching ingredient names
+const BUNDLED_TABLE: &str = include_str!("../data/nutrients.csv");
+
+/* Energy and macronutrients, per 100 g in a NutrientTable and absolute in the results. */
+#[derive(Serialize, Deserialize, Default, Clone, Copy, Debug, PartialEq)]
+pub struct Nutrients {
+    pub energy_kcal: f64,
+    pub protein_g: f64,
+    pub fat_g: f64,
+    pub carbohydrates_g: f64,
+    pub fiber_g: f64,
+    pub sugar_g: f64,
+    pub sodium_mg: f64,
+}
+
+impl Nutrients {
+    pub fn scaled(&self, factor: f64) -> Nutrients {
+        Nutrients {
+            energy_kcal: self.energy_kcal * factor,
+            protein_g: self.protein_g * factor,
+            fat_g: self.fat_g * factor,
+            carbohydrates_g: self.carbohydrates_g * factor,
+            fiber_g: self.fiber_g * factor,
+            sugar_g: self.sugar_g * factor,
+            sodium_mg: self.sodium_mg * factor,
+        }
+    }
+}
+
+impl std::ops::Add for Nutrients {
+    type Output = Nutrients;
+
+    fn add(self, other: Nutrients) -> Nutrients {
+        Nutrients {
+            energy_kcal: self.energy_kcal + other.energy_kcal,
+            protein_g: self.protein_g + other.protein_g,
+            fat_g: self.fat_g + other.fat_g,
+            carbohydrates_g: self.carbohydrates_g + other.carbohydrates_g,
+            fiber_g: self.fiber_g + other.fiber_g,
+            sugar_g: self.sugar_g + other.sugar_g,
+            sodium_mg: self.sodium_mg + other.sodium_mg,
+        }
+    }
+}
+
+/* An entry of the nutrient table. */
+#[derive(Clone, Debug)]
+pub struct Food {
+    /* The USDA nutrient databank number, what IngredientData::usda_num refers to. */
+    pub ndb_no: String,
+    pub description: String,
+    /* Ingredient names that mean this food, as lowercase singular words (see terms::words). */
+    pub aliases: Vec<String>,
+    /* Per 100 g. */
+    pub nutrients: Nutrients,
+    /* Household measures like “1 cup” or “1 large” with their weight, for amounts that aren't weighed. */
+    pub portions: Vec<Portion>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Portion {
+    pub amount: f64,
+    /* Mass, volume or a piece like “large” or “clove”. */
+    pub unit: Unit,
+    pub grams: f64,
+}
+
+impl Food {
+    /* The weight of `amount` of this food, if its unit is a weight or one of the food's portions can be converted. */
+    pub fn grams(&self, amount: &Amount) -> Option<f64> {
+        let value = amount.amount;
+        match &amount.unit {
+            Unit::Mass(unit) => Some(value * unit.grams()),
+            Unit::Volume(unit) => self
+                .portions
+                .iter()
+                .find_map(|portion| match &portion.unit {
+                    Unit::Volume(portion_unit) => Some(
+                        value * unit.milliliters() * portion.grams
+                            / (portion.amount * portion_unit.milliliters()),
+                    ),
+                    _ => None,
+                }),
+            // "2 eggs" are two of the food's typical pieces, "1 large" or "1 medium"
+            Unit::Count => self
+                .portions
+                .iter()
+                .find(|portion| matches!(portion.unit, Unit::Other(_)))
+                .map(|portion| value * portion.grams / portion.amount),
+            // "3 cloves" garlic
+            Unit::Other(name) => self
+                .portions
+                .iter()
+                .find(|portion| match &portion.unit {
+                    Unit::Other(portion_name) => terms::words(portion_name) == terms::words(name),
+                    _ => false,
+                })
+                .map(|portion| value * portion.grams / portion.amount),
+        }
+    }
+}
+
+/* Why a nutrient table could not be read. */
+#[derive(Clone, Debug, PartialEq)]
+pub struct NutritionError(pub String);
+
+impl std::fmt::Display for NutritionError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.write_str(&self.0)
+    }
+}
+
+/* Nutrient data per food, read from a CSV file with the columns of the USDA SR Legacy “ABBREV” table:
+ * NDB_No, Shrt_Desc, Energ_Kcal, Protein_(g), Lipid_Tot_(g), Carbohydrt_(g), Fiber_TD_(g), Sugar_Tot_(g), Sodium_(mg)
+ * and the portions GmWt_1, GmWt_Desc1, GmWt_2, GmWt_Desc2. Other columns are ignored, so the full table can be used.
+ * An optional Aliases column lists ingredient names for the food, separated by “;”. */
+#[derive(Clone, Debug, Default)]
+pub struct NutrientTable {
+    pub foods: Vec<Food>,
+}
+
+impl NutrientTable {
+    /* The table that comes with rcp. */
+    pub fn bundled() -> Self {
+        Self::from_csv(BUNDLED_TABLE).expect("bundled nutrient table is invalid")
+    }
+
+    pub fn from_csv(csv: &str) -> Result<Self, NutritionError> {
+        let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+        let header = split_csv_line(
+            lines
+                .next()
+                .ok_or_else(|| NutritionError("Empty nutrient table".to_string()))?,
+        );
+        let column = |name: &str| header.iter().position(|h| h.eq_ignore_ascii_case(name));
+        let required = |name: &str| {
+            column(name).ok_or_else(|| NutritionError(format!("Missing column {}", name)))
+        };
+
+        let ndb_no = required("NDB_No")?;
+        let description = required("Shrt_Desc")?;
+        let energy = required("Energ_Kcal")?;
+        let protein = required("Protein_(g)")?;
+        let fat = required("Lipid_Tot_(g)")?;
+        let carbohydrates = required("Carbohydrt_(g)")?;
+        let fiber = column("Fiber_TD_(g)");
+        let sugar = column("Sugar_Tot_(g)");
+        let sodium = column("Sodium_(mg)");
+        let portions = [
+            (column("GmWt_1"), column("GmWt_Desc1")),
+            (column("GmWt_2"), column("GmWt_Desc2")),
+        ];
+        let aliases = column("Aliases");
+
+        let mut foods = vec![];
+        for (idx, line) in lines.enumerate() {
+            let fields = split_csv_line(line);
+            let text = |col: Option<usize>| {
+                col.and_then(|col| fields.get(col))
+                    .map(|f| f.trim())
+                    .unwrap_or("")
+            };
+            // Missing values are empty in the USDA tables
+            let number = |col: Option<usize>| -> Result<f64, NutritionError> {
+                let value = text(col);
+                if value.is_empty() {
+                    return Ok(0.0);
+                }
+                f64::from_str(value).map_err(|_| {
+                    NutritionError(format!("Invalid number \"{}\" in line {}", value, idx + 2))
+                })
+            };
+
+            foods.push(Food {
+                ndb_no: text(Some(ndb_no)).to_string(),
+                description: text(Some(description)).to_string(),
+                aliases: text(aliases)
+                    .split(';')
+                    .map(terms::words)
+                    .filter(|alias| !alias.is_empty())
+                    .collect(),
+                nutrients: Nutrients {
+                    energy_kcal: number(Some(energy))?,
+                    protein_g: number(Some(protein))?,
+                    fat_g: number(Some(fat))?,
+                    carbohydrates_g: number(Some(carbohydrates))?,
+                    fiber_g: number(fiber)?,
+                    sugar_g: number(sugar)?,
+                    sodium_mg: number(sodium)?,
+                },
+                portions: portions
+                    .iter()
+                    .filter_map(|(grams, desc)| parse_portion(number(*grams).ok()?, text(*desc)))
+                    .collect(),
+            });
+        }
+        Ok(Self { foods })
+    }
+
+    pub fn by_ndb_no(&self, ndb_no: &str) -> Option<&Food> {
+        let ndb_no = ndb_no.trim();
+        self.foods.iter().find(|food| food.ndb_no == ndb_no)
+    }
+
+    /* The food for an ingredient: its usda_num if set, otherwise matched by name, see find. */
+    pub fn lookup(&self, ingredient: &IngredientData) -> Option<&Food> {
+        match ingredient
+            .usda_num
+            .as_deref()
+            .filter(|n| !n.trim().is_empty())
+        {
+            Some(ndb_no) => self.by_ndb_no(ndb_no),
+            None => self.find(&ingredient.ingredient_name),
+        }
+    }
+
+    /* Matches an ingredient name like “large eggs” or “freshly ground black pepper” to a food:
+     * an alias equal to the name, the longest alias contained in it, or else the food with the shortest description
+     * that contains every word of the name (for tables without aliases). */
+    pub fn find(&self, name: &str) -> Option<&Food> {
+        let name = terms::words(name);
+        if name.is_empty() {
+            return None;
+        }
+
+        if let Some(food) = self.foods.iter().find(|food| food.aliases.contains(&name)) {
+            return Some(food);
+        }
+
+        let contained = self
+            .foods
+            .iter()
+            .flat_map(|food| food.aliases.iter().map(move |alias| (food, alias)))
+            .filter(|(_, alias)| terms::contains_words(&name, alias))
+            .max_by_key(|(_, alias)| alias.len());
+        if let Some((food, _)) = contained {
+            return Some(food);
+        }
+
+        self.foods
+            .iter()
+            .filter(|food| {
+                let description = terms::words(&food.description);
+                name.split(' ')
+                    .all(|word| terms::contains_words(&description, word))
+            })
+            .min_by_key(|food| food.description.len())
+    }
+}
+
+/* The nutrition of one ingredient of a recipe. */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct IngredientNutrition {
+    pub ingredient_name: String,
+    pub usda_num: String,
+    /* The description of the matched food, to check the match. */
+    pub description: String,
+    pub grams: f64,
+    pub nutrients: Nutrients,
+}
+
+/* Ingredients that are left out of the totals. */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MissingNutrition {
+    pub ingredient_name: String,
+    pub reason: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RecipeNutrition {
+    pub total: Nutrients,
+    /* The total divided by the yield, if the recipe has one. */
+    pub per_serving: Option<Nutrients>,
+    pub servings: Option<f64>,
+    pub ingredients: Vec<IngredientNutrition>,
+    /* If not empty, the totals are too low. */
+    pub missing: Vec<MissingNutrition>,
+}
+
+/* Calculates the nutrition of a recipe for the yield at `yield_idx`, with the ingredient amounts for that yield.
+ * Substitutions are not counted. Ingredients without an amount, like “salt to taste”, count as nothing. */
+pub fn recipe_nutrition(
+    recipe: &Recipe,
+    yield_idx: usize,
+    table: &NutrientTable,
+) -> RecipeNutrition {
+    let mut total = Nutrients::default();
+    let mut ingredients = vec![];
+    let mut missing = vec![];
+
+    for ing in recipe.ingredients.iter().flatten() {
+        let data = &ing.ingredient;
+        let missing_because = |reason: String| MissingNutrition {
+            ingredient_name: data.ingredient_name.clone(),
+            reason,
+        };
+
+        let food = match table.lookup(data) {
+            Some(food) => food,
+            None => {
+                missing.push(missing_because(match &data.usda_num {
+                    Some(ndb_no) => format!("USDA number {} is not in the nutrient table", ndb_no),
+                    None => "not in the nutrient table".to_string(),
+                }));
+                continue;
+            }
+        };
+        let amount = match data.amounts.get(yield_idx) {
+            Some(amount) if amount.amount > 0.0 => amount,
+            _ => continue,
+        };
+        let grams = match food.grams(amount) {
+            Some(grams) => grams,
+            None => {
+                missing.push(missing_because(format!(
+                    "can't convert \"{}\" to grams",
+                    amount
+                )));
+                continue;
+            }
+        };
+
+        let nutrients = food.nutrients.scaled(grams / 100.0);
+        total = total + nutrients;
+        ingredients.push(IngredientNutrition {
+            ingredient_name: data.ingredient_name.clone(),
+            usda_num: food.ndb_no.clone(),
+            description: food.description.clone(),
+            grams,
+            nutrients,
+        });
+    }
+
+    let servings = recipe
+        .yields
+        .as_ref()
+        .and_then(|yields| yields.get(yield_idx))
+        .map(|y| y.amount)
+        .filter(|amount| *amount > 0.0);
+    RecipeNutrition {
+        total,
+        per_serving: servings.map(|servings| total.scaled(1.0 / servings)),
+        servings,
+        ingredients,
+        missing,
+    }
+}
+
+// "1 cup, shredded" -> 1 cup; "1 large" -> 1 large
+fn parse_portion(grams: f64, desc: &str) -> Option<Portion> {
+    if grams <= 0.0 {
+        return None;
+    }
+    let desc = desc.split(',').next()?.trim();
+    let mut tokens = desc.splitn(2, ' ');
+    let amount = Quantity::from_str(tokens.next()?).ok()?.value;
+    let unit = Unit::from_str(tokens.next().unwrap_or("")).unwrap_or_default();
+    if amount <= 0.0 || unit == Unit::Count {
+        return None;
+    }
+    Some(Portion {
+        amount,
+        unit,
+        grams,
+    })
+}
+
+// Splits a line of comma separated values. Fields with commas are quoted with "..." or, in the USDA files, ~...~
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) if c == q && chars.peek() == Some(&q) => {
+                field.push(q);
+                chars.next();
+            }
+            Some(q) if c == q => quote = None,
+            Some(_) => field.push(c),
+            None => match c {
+                '"' | '~' if field.is_empty() => quote = Some(c),
+                ',' => fields.push(std::mem::take(&mut field)),
+                c => field.push(c),
+            },
+        }
+    }
+    fields.push(field);
+    fields
+}
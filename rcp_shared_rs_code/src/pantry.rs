@@ -0,0 +1,286 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::models::ingredient::{Amount, Ingredient, IngredientData};
+use crate::models::pantry_item::PantryItem;
+use crate::models::recipe::Recipe;
+use crate::models::unit::Unit;
+use crate::terms;
+
+// Unit conversions don't add up exactly, 1000 g taken from 1 kg has to leave nothing
+const EPSILON: f64 = 1e-9;
+
+/* A recipe ingredient the pantry has. */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PantryMatch {
+    pub ingredient_name: String,
+    /* The ingredient this one can replace, if the pantry only has the substitution. */
+    pub substitution_for: Option<String>,
+    /* The ingredient name of the pantry item, to check the match. */
+    pub pantry_item: String,
+}
+
+/* A recipe ingredient the pantry lacks, or has too little of. */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MissingIngredient {
+    pub ingredient_name: String,
+    pub reason: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CookableRecipe {
+    pub recipe_uuid: String,
+    pub recipe_name: Option<String>,
+    pub matched: Vec<PantryMatch>,
+    /* What has to be bought first. Empty if the recipe can be cooked with what is on hand. */
+    pub missing: Vec<MissingIngredient>,
+    /* The earliest expiry date of the pantry items the recipe uses. */
+    pub uses_up: Option<NaiveDate>,
+}
+
+/* What cooking a recipe takes from the pantry, see deduct_recipe. */
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct PantryDeduction {
+    /* The pantry items that were used, with what is left of them. */
+    pub updated: Vec<PantryItem>,
+    /* Ingredients that were not or not fully taken from the pantry. */
+    pub not_deducted: Vec<MissingIngredient>,
+}
+
+/* The recipes that can be cooked with the pantry items on hand, or with at most `max_missing` ingredients bought.
+ * An ingredient is on hand if a pantry item matches its name or the name of one of its substitutions
+ * and there is enough of it for the recipe's first yield. Amounts that can't be converted into the unit of the pantry item,
+ * like a cup of flour kept in “bags”, are assumed to be enough. Expired and used up items are left out.
+ * The recipes with the fewest missing ingredients come first, then those that use up what expires first. */
+pub fn cookable_recipes(
+    recipes: &[Recipe],
+    pantry: &[PantryItem],
+    max_missing: usize,
+    today: NaiveDate,
+) -> Vec<CookableRecipe> {
+    let mut result = vec![];
+
+    for recipe in recipes.iter() {
+        let mut cookable = CookableRecipe {
+            recipe_uuid: recipe.recipe_uuid.clone(),
+            recipe_name: recipe.recipe_name.clone(),
+            matched: vec![],
+            missing: vec![],
+            uses_up: None,
+        };
+
+        for ing in recipe.ingredients.iter().flatten() {
+            if terms::words(&ing.ingredient.ingredient_name).is_empty() {
+                continue;
+            }
+            match on_hand(pantry, ing, today) {
+                Ok((data, substitution, items)) => {
+                    cookable.matched.push(PantryMatch {
+                        ingredient_name: data.ingredient_name.clone(),
+                        substitution_for: Some(ing.ingredient.ingredient_name.clone())
+                            .filter(|_| substitution),
+                        pantry_item: pantry[items[0]].ingredient_name.clone(),
+                    });
+                    let expires = items
+                        .iter()
+                        .filter_map(|idx| pantry[*idx].expiry_date)
+                        .min();
+                    cookable.uses_up = match (cookable.uses_up, expires) {
+                        (Some(a), Some(b)) => Some(a.min(b)),
+                        (a, b) => a.or(b),
+                    };
+                }
+                Err(reason) => cookable.missing.push(MissingIngredient {
+                    ingredient_name: ing.ingredient.ingredient_name.clone(),
+                    reason,
+                }),
+            }
+        }
+
+        if !cookable.matched.is_empty() && cookable.missing.len() <= max_missing {
+            result.push(cookable);
+        }
+    }
+
+    result.sort_by_key(|cookable| {
+        (
+            cookable.missing.len(),
+            // Recipes that use nothing that expires last
+            cookable.uses_up.is_none(),
+            cookable.uses_up,
+            std::cmp::Reverse(cookable.matched.len()),
+            cookable
+                .recipe_name
+                .clone()
+                .unwrap_or_default()
+                .to_lowercase(),
+        )
+    });
+    result
+}
+
+/* Takes the ingredients of a recipe from the pantry, with the amounts of the recipe's first yield
+ * (scale the recipe first to cook another yield, see Recipe::scaled). Ingredients are matched like in cookable_recipes
+ * and taken from the items that expire first. Returns the changed pantry items, the pantry itself is left alone. */
+pub fn deduct_recipe(recipe: &Recipe, pantry: &[PantryItem], today: NaiveDate) -> PantryDeduction {
+    let mut left = pantry.to_vec();
+    let mut used = vec![false; pantry.len()];
+    let mut deduction = PantryDeduction::default();
+
+    for ing in recipe.ingredients.iter().flatten() {
+        if terms::words(&ing.ingredient.ingredient_name).is_empty() {
+            continue;
+        }
+        let (data, _, items) = match on_hand(&left, ing, today) {
+            Ok(on_hand) => on_hand,
+            Err(reason) => {
+                deduction.not_deducted.push(MissingIngredient {
+                    ingredient_name: ing.ingredient.ingredient_name.clone(),
+                    reason,
+                });
+                continue;
+            }
+        };
+        let amount = match data.amounts.first() {
+            Some(amount) if amount.amount > 0.0 => amount,
+            _ => continue,
+        };
+
+        let mut needed = amount.amount;
+        for idx in items.iter() {
+            let item = &mut left[*idx];
+            let available = match convert(item.quantity, &item.unit, &amount.unit) {
+                Some(available) if needed > EPSILON => available,
+                _ => continue,
+            };
+            let taken = available.min(needed);
+            needed -= taken;
+            item.quantity = if available - taken > EPSILON {
+                item.quantity * (1.0 - taken / available)
+            } else {
+                0.0
+            };
+            used[*idx] = true;
+        }
+        if needed > EPSILON {
+            deduction.not_deducted.push(MissingIngredient {
+                ingredient_name: data.ingredient_name.clone(),
+                reason: format!(
+                    "can't convert \"{}\" to the pantry unit \"{}\"",
+                    amount, left[items[0]].unit
+                ),
+            });
+        }
+    }
+
+    deduction.updated = left
+        .into_iter()
+        .zip(used)
+        .filter(|(_, used)| *used)
+        .map(|(item, _)| item)
+        .collect();
+    deduction
+}
+
+// The ingredient or else the first of its substitutions the pantry has enough of, whether it is a substitution,
+// and the pantry items to use. The reason the ingredient itself is missing otherwise.
+fn on_hand<'a>(
+    pantry: &[PantryItem],
+    ing: &'a Ingredient,
+    today: NaiveDate,
+) -> Result<(&'a IngredientData, bool, Vec<usize>), String> {
+    let reason = match in_stock(pantry, &ing.ingredient, today) {
+        Ok(items) => return Ok((&ing.ingredient, false, items)),
+        Err(reason) => reason,
+    };
+    ing.substitutions
+        .iter()
+        .find_map(|sub| {
+            in_stock(pantry, sub, today)
+                .ok()
+                .map(|items| (sub, true, items))
+        })
+        .ok_or(reason)
+}
+
+// The pantry items with enough of an ingredient for its first amount, or why there aren't any
+fn in_stock(
+    pantry: &[PantryItem],
+    data: &IngredientData,
+    today: NaiveDate,
+) -> Result<Vec<usize>, String> {
+    let items = matching_items(pantry, &data.ingredient_name, today);
+    if items.is_empty() {
+        return Err("not in the pantry".to_string());
+    }
+    let amount = match data.amounts.first() {
+        Some(amount) if amount.amount > 0.0 => amount,
+        _ => return Ok(items),
+    };
+
+    let available: Vec<f64> = items
+        .iter()
+        .filter_map(|idx| convert(pantry[*idx].quantity, &pantry[*idx].unit, &amount.unit))
+        .collect();
+    // There is no telling how much a cup of flour is in bags
+    if available.is_empty() {
+        return Ok(items);
+    }
+    let available: f64 = available.iter().sum();
+    if available + EPSILON < amount.amount {
+        return Err(format!(
+            "only {} in the pantry",
+            Amount {
+                amount: available,
+                amount_max: None,
+                unit: amount.unit.clone(),
+            }
+        ));
+    }
+    Ok(items)
+}
+
+// The pantry items an ingredient name is matched to, those that expire first first: the items with the same name,
+// singular or plural, or else those with the longest name contained in it as whole words, see costing::find_price.
+// Expired and used up items are left out.
+fn matching_items(pantry: &[PantryItem], name: &str, today: NaiveDate) -> Vec<usize> {
+    let name = terms::words(name);
+    if name.is_empty() {
+        return vec![];
+    }
+    let stocked: Vec<(usize, String)> = pantry
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.quantity > 0.0 && !item.is_expired(today))
+        .map(|(idx, item)| (idx, terms::words(&item.ingredient_name)))
+        .collect();
+
+    let mut items: Vec<usize> = stocked
+        .iter()
+        .filter(|(_, item_name)| *item_name == name)
+        .map(|(idx, _)| *idx)
+        .collect();
+    if items.is_empty() {
+        let contained: Vec<&(usize, String)> = stocked
+            .iter()
+            .filter(|(_, item_name)| terms::matches(&name, std::slice::from_ref(item_name), &[]))
+            .collect();
+        let longest = contained.iter().map(|(_, item_name)| item_name.len()).max();
+        items = contained
+            .iter()
+            .filter(|(_, item_name)| Some(item_name.len()) == longest)
+            .map(|(idx, _)| *idx)
+            .collect();
+    }
+    // Items without an expiry date last
+    items.sort_by_key(|idx| (pantry[*idx].expiry_date.is_none(), pantry[*idx].expiry_date));
+    items
+}
+
+// `quantity` of `from` in `to`. Units we don't understand (“bag”) only match the same unit.
+fn convert(quantity: f64, from: &Unit, to: &Unit) -> Option<f64> {
+    match (from, to) {
+        (Unit::Other(a), Unit::Other(b)) if terms::words(a) == terms::words(b) => Some(quantity),
+        _ => from.convert(quantity, to),
+    }
+}
@@ -0,0 +1,209 @@
+use std::str::FromStr;
+
+// Unicode vulgar fractions as cooks (and recipe websites) write them
+const VULGAR_FRACTIONS: [(char, &str); 18] = [
+    ('½', "1/2"),
+    ('⅓', "1/3"),
+    ('⅔', "2/3"),
+    ('¼', "1/4"),
+    ('¾', "3/4"),
+    ('⅕', "1/5"),
+    ('⅖', "2/5"),
+    ('⅗', "3/5"),
+    ('⅘', "4/5"),
+    ('⅙', "1/6"),
+    ('⅚', "5/6"),
+    ('⅐', "1/7"),
+    ('⅛', "1/8"),
+    ('⅜', "3/8"),
+    ('⅝', "5/8"),
+    ('⅞', "7/8"),
+    ('⅑', "1/9"),
+    ('⅒', "1/10"),
+];
+
+// Fractions that can be measured with common cups and spoons, as (numerator, denominator)
+const KITCHEN_FRACTIONS: [(u32, u32); 11] = [
+    (0, 1),
+    (1, 8),
+    (1, 4),
+    (1, 3),
+    (3, 8),
+    (1, 2),
+    (5, 8),
+    (2, 3),
+    (3, 4),
+    (7, 8),
+    (1, 1),
+];
+
+/* A quantity as written in a recipe: a single number like “1 1/2” or “¾”, or a range like “2-3”. */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quantity {
+    pub value: f64,
+
+    /* Upper end of a range, e.g. 3 for “2-3 cloves”. */
+    pub max: Option<f64>,
+}
+
+impl Quantity {
+    pub fn new(value: f64) -> Self {
+        Self { value, max: None }
+    }
+}
+
+impl FromStr for Quantity {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        // A leading dash would be a negative number, which makes no sense for an amount
+        let range_split = s
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| *c == '-' || *c == '–' || *c == '—')
+            .map(|(pos, c)| (&s[..pos], &s[pos + c.len_utf8()..]))
+            .or_else(|| {
+                let lower = s.to_lowercase();
+                lower.find(" to ").map(|pos| (&s[..pos], &s[pos + 4..]))
+            });
+
+        match range_split {
+            Some((low, high)) => {
+                let value = parse_number(low)?;
+                let max = parse_number(high)?;
+                if max < value {
+                    return Err(());
+                }
+                Ok(Quantity {
+                    value,
+                    max: Some(max),
+                })
+            }
+            None => parse_number(s).map(Quantity::new),
+        }
+    }
+}
+
+// Unlike format_fraction this doesn't round, so it can be used to edit a quantity
+impl std::fmt::Display for Quantity {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.write_str(&format_exact(self.value))?;
+        if let Some(max) = self.max {
+            write!(fmt, "-{}", format_exact(max))?;
+        }
+        Ok(())
+    }
+}
+
+fn format_exact(value: f64) -> String {
+    let is_kitchen_fraction = KITCHEN_FRACTIONS
+        .iter()
+        .any(|(n, d)| (value.fract() - f64::from(*n) / f64::from(*d)).abs() < 0.001);
+
+    if value < 10.0 && value.fract() > 0.0 && is_kitchen_fraction {
+        format_fraction(value)
+    } else {
+        value.to_string()
+    }
+}
+
+// Parses mixed numbers ("1 1/2", "1½"), fractions ("3/4", "¾") and decimals ("1.5", "1,5")
+fn parse_number(s: &str) -> Result<f64, ()> {
+    let mut normalized = String::with_capacity(s.len());
+    for c in s.trim().chars() {
+        match VULGAR_FRACTIONS.iter().find(|(vulgar, _)| *vulgar == c) {
+            Some((_, ascii)) => {
+                normalized.push(' ');
+                normalized.push_str(ascii);
+            }
+            // Unicode fraction slash, as in "1⁄2"
+            None if c == '⁄' => normalized.push('/'),
+            None => normalized.push(c),
+        }
+    }
+
+    let tokens: Vec<&str> = normalized.split_whitespace().collect();
+    match tokens.as_slice() {
+        [single] => parse_token(single),
+        [whole, fraction] if !whole.contains('/') && fraction.contains('/') => {
+            Ok(parse_token(whole)? + parse_token(fraction)?)
+        }
+        _ => Err(()),
+    }
+}
+
+fn parse_token(token: &str) -> Result<f64, ()> {
+    let value = match token.find('/') {
+        Some(pos) => {
+            let numerator: f64 = token[..pos].parse().map_err(|_| ())?;
+            let denominator: f64 = token[pos + 1..].parse().map_err(|_| ())?;
+            if denominator == 0.0 {
+                return Err(());
+            }
+            numerator / denominator
+        }
+        // European decimal comma
+        None => token.replace(',', ".").parse().map_err(|_| ())?,
+    };
+
+    if value.is_finite() && value >= 0.0 {
+        Ok(value)
+    } else {
+        Err(())
+    }
+}
+
+/* Renders an amount the way it would be measured with cups and spoons, e.g. 0.333 as “1/3” and 1.5 as “1 1/2”.
+ * Amounts of 10 and more are rounded to whole numbers, tiny amounts fall back to decimals. */
+pub fn format_fraction(value: f64) -> String {
+    // NaN can't be compared to the fractions below
+    if !value.is_finite() {
+        return format_decimal(value);
+    }
+    if value >= 10.0 {
+        return format!("{}", value.round());
+    }
+    if value < 1.0 / 16.0 {
+        return format_decimal(value);
+    }
+
+    let mut whole = value.trunc() as u32;
+    let rest = value.fract();
+    let (mut numerator, mut denominator) = KITCHEN_FRACTIONS
+        .iter()
+        .copied()
+        .min_by(|(n1, d1), (n2, d2)| {
+            let diff1 = (rest - f64::from(*n1) / f64::from(*d1)).abs();
+            let diff2 = (rest - f64::from(*n2) / f64::from(*d2)).abs();
+            diff1.partial_cmp(&diff2).unwrap()
+        })
+        .unwrap();
+
+    if numerator == denominator {
+        whole += 1;
+        numerator = 0;
+        denominator = 1;
+    }
+
+    match (whole, numerator) {
+        (whole, 0) => whole.to_string(),
+        (0, numerator) => format!("{}/{}", numerator, denominator),
+        (whole, numerator) => format!("{} {}/{}", whole, numerator, denominator),
+    }
+}
+
+/* Renders an amount with as many decimals as can be measured on a kitchen scale or jug:
+ * none from 10 upwards, at most one from 1 to 10 and at most two below 1. */
+pub fn format_decimal(value: f64) -> String {
+    let decimals = if value.abs() >= 10.0 {
+        0
+    } else if value.abs() >= 1.0 {
+        1
+    } else {
+        2
+    };
+    let precision = 10f64.powi(decimals);
+    format!("{}", (value * precision).round() / precision)
+}
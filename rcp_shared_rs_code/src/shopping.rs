@@ -0,0 +1,205 @@
+use serde::Deserialize;
+
+use crate::models::ingredient::Amount;
+use crate::models::r#yield::Yield;
+use crate::models::recipe::Recipe;
+use crate::models::shopping_list::ShoppingItem;
+use crate::models::unit::Unit;
+use crate::models::unit_system::UnitSystem;
+use crate::terms;
+
+// The aisles of a typical supermarket for ingredient names in English, maintained by hand
+const BUNDLED_AISLES: &str = include_str!("../data/aisles.yml");
+
+/* The aisle of ingredients no aisle claims, listed last. */
+pub const OTHER_AISLE: &str = "Other";
+
+/* Why an aisles file could not be read or a shopping list could not be made. */
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShoppingError(pub String);
+
+impl std::fmt::Display for ShoppingError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.write_str(&self.0)
+    }
+}
+
+/* The store aisles in the order they are walked, with the ingredient names found in them,
+ * read from a YAML file like data/aisles.yml. Terms match whole words, singular or plural, see allergens::AllergenRules. */
+#[derive(Clone, Debug, Default)]
+pub struct Aisles {
+    pub aisles: Vec<Aisle>,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct Aisle {
+    pub aisle: String,
+    pub contains: Vec<String>,
+    pub except: Vec<String>,
+}
+
+impl Aisles {
+    /* The aisles that come with rcp. */
+    pub fn bundled() -> Self {
+        Self::from_yaml(BUNDLED_AISLES).expect("bundled aisles are invalid")
+    }
+
+    pub fn from_yaml(yaml: &str) -> Result<Self, ShoppingError> {
+        let aisles: Vec<Aisle> = serde_yaml::from_str(yaml)
+            .map_err(|e| ShoppingError(format!("Invalid aisles: {}", e)))?;
+
+        let mut names: Vec<&str> = vec![];
+        for aisle in aisles.iter() {
+            let name = aisle.aisle.trim();
+            if name.is_empty() {
+                return Err(ShoppingError("Aisle without a name".to_string()));
+            }
+            if names.contains(&name) {
+                return Err(ShoppingError(format!("Aisle {} is listed twice", name)));
+            }
+            names.push(name);
+        }
+
+        let aisles = aisles
+            .iter()
+            .map(|aisle| Aisle {
+                aisle: aisle.aisle.trim().to_string(),
+                contains: aisle.contains.iter().map(|t| terms::words(t)).collect(),
+                except: aisle.except.iter().map(|t| terms::words(t)).collect(),
+            })
+            .collect();
+        Ok(Self { aisles })
+    }
+
+    /* The aisle of an ingredient name: the one with the longest term found in it, the first one listed if two are as long. */
+    pub fn aisle_of(&self, name: &str) -> &str {
+        let name = terms::words(name);
+        let mut best: Option<(&str, usize)> = None;
+        for aisle in self.aisles.iter() {
+            let longest = aisle
+                .contains
+                .iter()
+                .filter(|term| terms::matches(&name, std::slice::from_ref(*term), &aisle.except))
+                .map(|term| term.len())
+                .max();
+            if let Some(len) = longest {
+                let longer = match best {
+                    Some((_, best_len)) => len > best_len,
+                    None => true,
+                };
+                if longer {
+                    best = Some((&aisle.aisle, len));
+                }
+            }
+        }
+        best.map_or(OTHER_AISLE, |(aisle, _)| aisle)
+    }
+
+    // Where an aisle is walked, OTHER_AISLE and aisles not in the file last
+    fn position(&self, aisle: &str) -> usize {
+        self.aisles
+            .iter()
+            .position(|a| a.aisle == aisle)
+            .unwrap_or(self.aisles.len())
+    }
+}
+
+/* The ingredients to buy for the recipes, each scaled to its target yield (see Recipe::scaled).
+ * Ingredients with the same name, singular or plural, are added up: 200 g and 0.5 kg of butter are 700 g.
+ * Amounts that can't be converted into each other, like a cup and a gram, are listed separately.
+ * Metric amounts are expressed in the unit that fits best, see Amount::to_unit_system.
+ * Substitutions are not on the list. The items are sorted by aisle and then by name. */
+pub fn shopping_items(
+    recipes: &[(&Recipe, &Yield)],
+    aisles: &Aisles,
+) -> Result<Vec<ShoppingItem>, ShoppingError> {
+    // (name as in terms::words, item)
+    let mut items: Vec<(String, ShoppingItem)> = vec![];
+
+    for (recipe, target) in recipes.iter() {
+        let recipe_name = recipe
+            .recipe_name
+            .clone()
+            .unwrap_or_else(|| recipe.recipe_uuid.clone());
+        let scaled = recipe.scaled(target).ok_or_else(|| {
+            ShoppingError(format!(
+                "{} has no yield in {} to scale from",
+                recipe_name, target.unit
+            ))
+        })?;
+
+        for ing in scaled.ingredients.iter().flatten() {
+            let data = &ing.ingredient;
+            let key = terms::words(&data.ingredient_name);
+            if key.is_empty() {
+                continue;
+            }
+
+            let idx = match items.iter().position(|(k, _)| *k == key) {
+                Some(idx) => idx,
+                None => {
+                    items.push((
+                        key,
+                        ShoppingItem {
+                            ingredient_name: data.ingredient_name.trim().to_string(),
+                            aisle: aisles.aisle_of(&data.ingredient_name).to_string(),
+                            ..ShoppingItem::default()
+                        },
+                    ));
+                    items.len() - 1
+                }
+            };
+            let item = &mut items[idx].1;
+            if !item.recipes.contains(&recipe_name) {
+                item.recipes.push(recipe_name.clone());
+            }
+            if let Some(amount) = data.amounts.first().filter(|a| a.amount > 0.0) {
+                add_amount(&mut item.amounts, amount);
+            }
+        }
+    }
+
+    let mut items: Vec<ShoppingItem> = items.into_iter().map(|(_, item)| item).collect();
+    // 1200 g are 1.2 kg, but imperial amounts stay in the unit of the first recipe: 4 cups of flour, not 1 quart
+    for item in items.iter_mut() {
+        for amount in item.amounts.iter_mut() {
+            if amount.unit.system() == Some(UnitSystem::Metric) {
+                *amount = amount.to_unit_system(UnitSystem::Metric);
+            }
+        }
+    }
+    items.sort_by_key(|item| {
+        (
+            aisles.position(&item.aisle),
+            item.ingredient_name.to_lowercase(),
+        )
+    });
+    Ok(items)
+}
+
+// Adds `amount` to the amount of `amounts` it can be converted to, or else appends it
+fn add_amount(amounts: &mut Vec<Amount>, amount: &Amount) {
+    for sum in amounts.iter_mut() {
+        let converted = match (&amount.unit, &sum.unit) {
+            // "2 cloves" and "1 clove"
+            (Unit::Other(a), Unit::Other(b)) if terms::words(a) == terms::words(b) => {
+                Some(Amount {
+                    unit: sum.unit.clone(),
+                    ..amount.clone()
+                })
+            }
+            _ => amount.convert_to(&sum.unit),
+        };
+        if let Some(converted) = converted {
+            // A range stays a range: 1-2 tbsp and 1 tbsp are 2-3 tbsp
+            sum.amount_max = match (sum.amount_max, converted.amount_max) {
+                (None, None) => None,
+                (a, b) => Some(a.unwrap_or(sum.amount) + b.unwrap_or(converted.amount)),
+            };
+            sum.amount += converted.amount;
+            return;
+        }
+    }
+    amounts.push(amount.clone());
+}
@@ -0,0 +1,45 @@
+// Matching ingredient names against the terms of a rules file or table, see allergens, diets and nutrition
+
+// Lowercase singular words separated by single spaces, "Large Eggs" -> "large egg"
+pub fn words(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '\''))
+        .filter(|word| !word.is_empty())
+        .map(singular)
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+// Whether `name` (see words) contains one of the terms and none of the exceptions, as whole words
+pub fn matches(name: &str, contains: &[String], except: &[String]) -> bool {
+    contains.iter().any(|term| contains_words(name, term))
+        && !except.iter().any(|term| contains_words(name, term))
+}
+
+// The term of `contains` found in `name`, for explanations
+pub fn find<'a>(name: &str, contains: &'a [String], except: &[String]) -> Option<&'a str> {
+    if except.iter().any(|term| contains_words(name, term)) {
+        return None;
+    }
+    contains
+        .iter()
+        .find(|term| contains_words(name, term))
+        .map(String::as_str)
+}
+
+fn singular(word: &str) -> String {
+    if word.ends_with("oes") || word.ends_with("ches") || word.ends_with("shes") {
+        word[..word.len() - 2].to_string()
+    } else if let Some(stem) = word.strip_suffix("ies") {
+        format!("{}y", stem)
+    } else if word.ends_with('s') && !word.ends_with("ss") && word.len() > 3 {
+        word[..word.len() - 1].to_string()
+    } else {
+        word.to_string()
+    }
+}
+
+// Whether `words` appear in `text` as whole words, in order
+pub fn contains_words(text: &str, words: &str) -> bool {
+    !words.is_empty() && format!(" {} ", text).contains(&format!(" {} ", words))
+}
@@ -0,0 +1,249 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::catalogue_entry::CatalogueEntry;
+use crate::models::ingredient::{Amount, IngredientData};
+use crate::models::meal_plan::MealPlanEntry;
+use crate::models::pantry_item::PantryItem;
+use crate::models::price_item::PriceItem;
+use crate::models::recipe_request::RecipeRequest;
+use crate::models::step::Step;
+
+pub const MIN_RECIPE_NAME_LENGTH: usize = 4;
+
+/* A problem with a single field of a recipe.
+ * `field` is the path to the offending value as in the JSON document, e.g. “ingredients[2].ingredient.amounts[0]”. */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ValidationError {
+    pub field: String,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new(field: &str, message: &str) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(fmt, "{}: {}", self.field, self.message)
+    }
+}
+
+/* Checks everything the API and the forms require of a recipe before it is stored.
+ * Returns all problems at once, so a form can show them next to their fields. An empty list means the recipe is valid. */
+pub fn validate_recipe_request(recipe: &RecipeRequest) -> Vec<ValidationError> {
+    let mut errors = vec![];
+
+    let name_length = recipe
+        .recipe_name
+        .as_ref()
+        .map_or(0, |name| name.trim().chars().count());
+    if name_length < MIN_RECIPE_NAME_LENGTH {
+        errors.push(ValidationError::new(
+            "recipe_name",
+            &format!("Title needs at least {} characters", MIN_RECIPE_NAME_LENGTH),
+        ));
+    }
+
+    if let Some(temp) = &recipe.oven_temp {
+        if !temp.amount.is_finite() || temp.amount <= 0.0 {
+            errors.push(ValidationError::new(
+                "oven_temp.amount",
+                "Temperature must be greater than 0",
+            ));
+        }
+    }
+
+    let yields = recipe.yields.as_deref().unwrap_or(&[]);
+    for (idx, y) in yields.iter().enumerate() {
+        if !y.amount.is_finite() || y.amount <= 0.0 {
+            errors.push(ValidationError::new(
+                &format!("yields[{}].amount", idx),
+                "Yield must be greater than 0",
+            ));
+        }
+        if y.unit.trim().is_empty() {
+            errors.push(ValidationError::new(
+                &format!("yields[{}].unit", idx),
+                "Yield needs a unit, e.g. servings",
+            ));
+        }
+    }
+
+    // A recipe without yields has one implicit yield, like most ORF documents with a single amount per ingredient
+    let yield_count = yields.len().max(1);
+    for (idx, ingredient) in recipe.ingredients.iter().flatten().enumerate() {
+        let field = format!("ingredients[{}].ingredient", idx);
+        validate_ingredient_data(&ingredient.ingredient, &field, yield_count, &mut errors);
+
+        for (sub_idx, substitution) in ingredient.substitutions.iter().enumerate() {
+            let field = format!("ingredients[{}].substitutions[{}]", idx, sub_idx);
+            validate_ingredient_data(substitution, &field, yield_count, &mut errors);
+        }
+    }
+
+    for (idx, step) in recipe.steps.iter().flatten().enumerate() {
+        validate_step(step, &format!("steps[{}]", idx), &mut errors);
+    }
+
+    errors
+}
+
+// Every ingredient and substitution needs exactly one amount per yield, in the order of the yields
+fn validate_ingredient_data(
+    data: &IngredientData,
+    field: &str,
+    yield_count: usize,
+    errors: &mut Vec<ValidationError>,
+) {
+    if data.ingredient_name.trim().is_empty() {
+        errors.push(ValidationError::new(
+            &format!("{}.ingredient_name", field),
+            "Ingredient needs a name",
+        ));
+    }
+
+    if data.amounts.len() != yield_count {
+        errors.push(ValidationError::new(
+            &format!("{}.amounts", field),
+            "Ingredient needs exactly one amount per yield",
+        ));
+    }
+
+    for (idx, amount) in data.amounts.iter().enumerate() {
+        if let Some(message) = amount_error(amount) {
+            errors.push(ValidationError::new(
+                &format!("{}.amounts[{}]", field, idx),
+                message,
+            ));
+        }
+    }
+}
+
+fn amount_error(amount: &Amount) -> Option<&'static str> {
+    if !amount.amount.is_finite() || amount.amount < 0.0 {
+        return Some("Amount must not be negative");
+    }
+    match amount.amount_max {
+        Some(max) if !max.is_finite() || max < amount.amount => {
+            Some("Upper end of the range must not be smaller than the amount")
+        }
+        _ => None,
+    }
+}
+
+fn validate_step(step: &Step, field: &str, errors: &mut Vec<ValidationError>) {
+    if step.step.trim().is_empty() {
+        errors.push(ValidationError::new(
+            &format!("{}.step", field),
+            "Step needs a description",
+        ));
+    }
+
+    // See Step::haccp, a step has either a control point or a critical one
+    if let Some(haccp) = &step.haccp {
+        if !haccp.control_point.trim().is_empty() && !haccp.critical_control_point.trim().is_empty()
+        {
+            errors.push(ValidationError::new(
+                &format!("{}.haccp", field),
+                "Either a control point or a critical control point, not both",
+            ));
+        }
+    }
+}
+
+/* Checks a price list entry before it is stored. An empty list means it is valid. */
+pub fn validate_price_item(item: &PriceItem) -> Vec<ValidationError> {
+    let mut errors = vec![];
+
+    if item.ingredient_name.trim().is_empty() {
+        errors.push(ValidationError::new(
+            "ingredient_name",
+            "Ingredient name is required",
+        ));
+    }
+    if !item.pack_size.is_finite() || item.pack_size <= 0.0 {
+        errors.push(ValidationError::new(
+            "pack_size",
+            "Pack size must be greater than 0",
+        ));
+    }
+    if !item.price.is_finite() || item.price < 0.0 {
+        errors.push(ValidationError::new("price", "Price can't be negative"));
+    }
+    if !item.trim_yield.is_finite() || item.trim_yield <= 0.0 || item.trim_yield > 100.0 {
+        errors.push(ValidationError::new(
+            "trim_yield",
+            "Trim yield must be between 0 and 100 %",
+        ));
+    }
+    errors
+}
+
+/* Checks a catalogue entry before it is stored. */
+pub fn validate_catalogue_entry(entry: &CatalogueEntry) -> Vec<ValidationError> {
+    let mut errors = vec![];
+
+    if entry.canonical_name.trim().is_empty() {
+        errors.push(ValidationError::new(
+            "canonical_name",
+            "Canonical name is required",
+        ));
+    }
+    for (idx, synonym) in entry.synonyms.iter().enumerate() {
+        if synonym.trim().is_empty() {
+            errors.push(ValidationError::new(
+                &format!("synonyms[{}]", idx),
+                "Synonym can't be empty",
+            ));
+        }
+    }
+    if let Some(density) = entry.default_density {
+        if !density.is_finite() || density <= 0.0 {
+            errors.push(ValidationError::new(
+                "default_density",
+                "Density must be greater than 0",
+            ));
+        }
+    }
+    errors
+}
+
+/* Checks a pantry item before it is stored. */
+pub fn validate_pantry_item(item: &PantryItem) -> Vec<ValidationError> {
+    let mut errors = vec![];
+
+    if item.ingredient_name.trim().is_empty() {
+        errors.push(ValidationError::new(
+            "ingredient_name",
+            "Ingredient name is required",
+        ));
+    }
+    if !item.quantity.is_finite() || item.quantity < 0.0 {
+        errors.push(ValidationError::new(
+            "quantity",
+            "Quantity can't be negative",
+        ));
+    }
+    errors
+}
+
+/* Checks a meal plan entry before it is stored. Whether the recipe exists is up to the caller. */
+pub fn validate_meal_plan_entry(entry: &MealPlanEntry) -> Vec<ValidationError> {
+    let mut errors = vec![];
+
+    if entry.recipe_uuid.trim().is_empty() {
+        errors.push(ValidationError::new("recipe_uuid", "Recipe is required"));
+    }
+    if !entry.servings.is_finite() || entry.servings <= 0.0 {
+        errors.push(ValidationError::new(
+            "servings",
+            "Servings must be greater than 0",
+        ));
+    }
+    errors
+}
@@ -0,0 +1,145 @@
+use rcp_shared_rs_code::allergens::{recipe_allergens, AllergenError, AllergenRules};
+use rcp_shared_rs_code::models::allergen::Allergen;
+use rcp_shared_rs_code::models::catalogue_entry::CatalogueEntry;
+use rcp_shared_rs_code::models::ingredient::Ingredient;
+use rcp_shared_rs_code::models::recipe::Recipe;
+
+mod common;
+use common::{ingredient, orf_recipe, BANANA_BREAD};
+
+#[test]
+fn detects_allergens_in_names() {
+    let rules = AllergenRules::bundled();
+
+    assert_eq!(rules.detect("All-Purpose Flour"), vec![Allergen::Gluten]);
+    assert_eq!(rules.detect("large eggs"), vec![Allergen::Eggs]);
+    assert_eq!(rules.detect("eggplant"), vec![]);
+    assert_eq!(rules.detect("unsalted butter"), vec![Allergen::Milk]);
+    assert_eq!(
+        rules.detect("smooth peanut butter"),
+        vec![Allergen::Peanuts]
+    );
+    assert_eq!(rules.detect("coconut milk"), vec![]);
+    assert_eq!(rules.detect("gluten-free flour"), vec![]);
+    assert_eq!(rules.detect("toasted pine nuts"), vec![]);
+    assert_eq!(rules.detect("chopped walnuts"), vec![Allergen::Nuts]);
+    assert_eq!(
+        rules.detect("soy sauce"),
+        vec![Allergen::Gluten, Allergen::Soybeans]
+    );
+    assert_eq!(
+        rules.detect("mayonnaise"),
+        vec![Allergen::Eggs, Allergen::Mustard]
+    );
+}
+
+#[test]
+fn declares_recipe_allergens() {
+    let recipe = orf_recipe(BANANA_BREAD);
+    let allergens = recipe_allergens(&recipe, &AllergenRules::bundled(), &[]);
+
+    assert_eq!(allergens.contains, vec![Allergen::Gluten, Allergen::Milk]);
+    assert_eq!(allergens.may_contain, vec![]);
+    // Flour, baking soda, butter, its substitute margarine and bananas
+    assert_eq!(allergens.ingredients.len(), 5);
+    assert_eq!(
+        allergens.ingredients[3].substitution_for.as_deref(),
+        Some("butter")
+    );
+    assert!(allergens.is_free_from(&[Allergen::Eggs, Allergen::Nuts]));
+    assert!(!allergens.is_free_from(&[Allergen::Milk]));
+}
+
+#[test]
+fn uses_declared_allergens_and_substitutions() {
+    let mut stock = ingredient("house stock");
+    stock.allergens = Some(vec![Allergen::Celery, Allergen::Fish]);
+    let mut milk = Ingredient {
+        ingredient: ingredient("milk"),
+        substitutions: vec![ingredient("oat milk")],
+    };
+    // Nothing in the cheese, as declared by the cook
+    let mut cheese = ingredient("vegan cheese");
+    cheese.allergens = Some(vec![]);
+    milk.substitutions.push(cheese);
+
+    let recipe = Recipe {
+        ingredients: Some(vec![
+            Ingredient {
+                ingredient: stock,
+                substitutions: vec![],
+            },
+            milk,
+        ]),
+        ..Default::default()
+    };
+    let allergens = recipe_allergens(&recipe, &AllergenRules::bundled(), &[]);
+
+    assert_eq!(
+        allergens.contains,
+        vec![Allergen::Fish, Allergen::Milk, Allergen::Celery]
+    );
+    assert_eq!(allergens.may_contain, vec![Allergen::Gluten]);
+    assert!(allergens.ingredients[0].declared);
+    assert!(!allergens.is_free_from(&[Allergen::Gluten]));
+}
+
+#[test]
+fn uses_the_catalogue_entries_of_linked_ingredients() {
+    let catalogue = vec![
+        CatalogueEntry {
+            catalogue_uuid: "1".to_string(),
+            canonical_name: "spring onion".to_string(),
+            allergens: Some(vec![Allergen::Sulphites]),
+            ..CatalogueEntry::default()
+        },
+        CatalogueEntry {
+            catalogue_uuid: "2".to_string(),
+            canonical_name: "butter".to_string(),
+            ..CatalogueEntry::default()
+        },
+    ];
+    let mut scallions = ingredient("scallions");
+    scallions.catalogue_uuid = Some("1".to_string());
+    // Nothing in the name gives the butter away, its entry has no allergens so they come from the canonical name
+    let mut ghee = ingredient("ghee");
+    ghee.catalogue_uuid = Some("2".to_string());
+
+    let recipe = Recipe {
+        ingredients: Some(vec![
+            Ingredient {
+                ingredient: scallions,
+                substitutions: vec![],
+            },
+            Ingredient {
+                ingredient: ghee,
+                substitutions: vec![],
+            },
+        ]),
+        ..Default::default()
+    };
+    let allergens = recipe_allergens(&recipe, &AllergenRules::bundled(), &catalogue);
+
+    assert_eq!(
+        allergens.contains,
+        vec![Allergen::Milk, Allergen::Sulphites]
+    );
+    assert!(allergens.ingredients[0].declared);
+    assert!(!allergens.ingredients[1].declared);
+    assert_eq!(allergens.ingredients[1].ingredient_name, "ghee");
+}
+
+#[test]
+fn reads_rules_file() {
+    let rules = AllergenRules::from_yaml(
+        "Sesame:\n  contains: [tahini, sesame]\nSoy:\n  contains: [tofu]\n  except: [tofu-free]\n",
+    )
+    .unwrap();
+    assert_eq!(rules.detect("Tahini"), vec![Allergen::Sesame]);
+    assert_eq!(rules.detect("silken tofu"), vec![Allergen::Soybeans]);
+
+    assert_eq!(
+        AllergenRules::from_yaml("Chocolate:\n  contains: [cocoa]\n").unwrap_err(),
+        AllergenError("Unknown allergen Chocolate".to_string())
+    );
+}
@@ -0,0 +1,215 @@
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{Cursor, Write};
+
+use rcp_shared_rs_code::formats::mealie::from_mealie_json;
+use rcp_shared_rs_code::formats::paprika::from_paprika_json;
+use rcp_shared_rs_code::formats::tandoor::from_tandoor_json;
+use rcp_shared_rs_code::formats::{self, Format};
+use rcp_shared_rs_code::models::duration::Duration;
+use rcp_shared_rs_code::models::unit::{MassUnit, Unit, VolumeUnit};
+
+mod common;
+use common::ingredient_names;
+
+const PAPRIKA: &str = include_str!("data/paprika/pancakes.json");
+const MEALIE: &str = include_str!("data/mealie/lentil_soup.json");
+const TANDOOR: &str = include_str!("data/tandoor/recipe.json");
+
+// The exports are built here from the JSON fixtures, as the other recipe managers would
+fn zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(Cursor::new(vec![]));
+    for (name, content) in files {
+        writer
+            .start_file(*name, zip::write::FileOptions::default())
+            .unwrap();
+        writer.write_all(content).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+fn gzip(content: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(content).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[test]
+fn reads_paprika_recipe() {
+    let recipe = from_paprika_json(PAPRIKA).unwrap();
+    assert_eq!(recipe.validate(), vec![]);
+
+    assert_eq!(recipe.recipe_name.as_deref(), Some("Buttermilk Pancakes"));
+    assert_eq!(
+        recipe.source_url.as_deref(),
+        Some("https://example.com/pancakes")
+    );
+    assert_eq!(
+        recipe.source_authors,
+        Some(vec!["Grandma's notebook".to_string()])
+    );
+    assert_eq!(
+        recipe.notes.as_deref(),
+        Some("Fluffy weekend pancakes.\n\nKeep warm in a 90 °C oven.")
+    );
+    assert_eq!(
+        recipe.categories,
+        Some(vec!["Breakfast".to_string(), "Sweet".to_string()])
+    );
+    // The embedded photo wins over the link
+    assert_eq!(
+        recipe.image.as_deref(),
+        Some("data:image/jpeg;base64,/9j/4AAQSkZJRg==")
+    );
+    assert_eq!(recipe.prep_time, Some(Duration::from_minutes(10.0)));
+    assert_eq!(ingredient_names(&recipe).len(), 5);
+    assert_eq!(recipe.steps.as_ref().unwrap().len(), 3);
+}
+
+#[test]
+fn reads_paprika_archive() {
+    let broken = gzip(b"{ not json");
+    let archive = zip(&[
+        (
+            "Buttermilk Pancakes.paprikarecipe",
+            &gzip(PAPRIKA.as_bytes()),
+        ),
+        ("Broken.paprikarecipe", &broken),
+    ]);
+
+    let results = formats::import_bytes(&archive, None).unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(
+        results[0].as_ref().unwrap().recipe_name.as_deref(),
+        Some("Buttermilk Pancakes")
+    );
+    assert!(results[1]
+        .as_ref()
+        .unwrap_err()
+        .to_string()
+        .starts_with("Recipe 2 (Broken): Invalid Paprika recipe"));
+
+    // A single gzipped recipe
+    let results = formats::import_bytes(&gzip(PAPRIKA.as_bytes()), None).unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_ok());
+}
+
+#[test]
+fn reads_mealie_recipe() {
+    let results = from_mealie_json(MEALIE).unwrap();
+    let recipe = results[0].as_ref().unwrap();
+    assert_eq!(recipe.validate(), vec![]);
+
+    assert_eq!(recipe.recipe_name.as_deref(), Some("Red Lentil Soup"));
+    assert_eq!(
+        recipe.source_url.as_deref(),
+        Some("https://example.com/lentil-soup")
+    );
+    assert_eq!(
+        recipe.notes.as_deref(),
+        Some("A quick weeknight soup.\n\nTip: Finish with lemon juice.")
+    );
+    assert_eq!(
+        recipe.categories,
+        Some(vec!["Soup".to_string(), "Vegan".to_string()])
+    );
+    assert_eq!(recipe.equipment, Some(vec!["Dutch oven".to_string()]));
+    assert_eq!(recipe.cook_time, Some(Duration::from_minutes(30.0)));
+    // The image is a file of the export, not a URL
+    assert_eq!(recipe.image, None);
+
+    assert_eq!(
+        ingredient_names(recipe),
+        vec!["red lentils", "onion", "vegetable stock"]
+    );
+    let lentils = &recipe.ingredients.as_ref().unwrap()[0].ingredient;
+    assert_eq!(lentils.amounts[0].amount, 250.0);
+    assert_eq!(lentils.amounts[0].unit, Unit::Mass(MassUnit::Gram));
+    assert_eq!(lentils.notes.as_deref(), Some("rinsed"));
+}
+
+#[test]
+fn reads_mealie_archive_with_images() {
+    let archive = zip(&[
+        (
+            "recipes/red-lentil-soup/red-lentil-soup.json",
+            MEALIE.as_bytes(),
+        ),
+        ("recipes/red-lentil-soup/images/min-original.webp", b"small"),
+        ("recipes/red-lentil-soup/images/original.webp", b"photo"),
+        ("database.json", b"{\"users\": []}"),
+    ]);
+
+    let results = formats::import_bytes(&archive, None).unwrap();
+    assert_eq!(results.len(), 1);
+    let recipe = results[0].as_ref().unwrap();
+    assert_eq!(recipe.recipe_name.as_deref(), Some("Red Lentil Soup"));
+    assert_eq!(
+        recipe.image.as_deref(),
+        Some("data:image/webp;base64,cGhvdG8=")
+    );
+}
+
+#[test]
+fn reads_tandoor_recipe() {
+    let recipe = from_tandoor_json(TANDOOR).unwrap();
+    assert_eq!(recipe.validate(), vec![]);
+
+    assert_eq!(recipe.recipe_name.as_deref(), Some("Focaccia"));
+    assert_eq!(
+        recipe.notes.as_deref(),
+        Some("Overnight focaccia with rosemary.")
+    );
+    assert_eq!(
+        recipe.categories,
+        Some(vec!["Bread".to_string(), "Italian".to_string()])
+    );
+    let yields = recipe.yields.clone().unwrap();
+    assert_eq!((yields[0].amount, yields[0].unit.as_str()), (8.0, "pieces"));
+    assert_eq!(recipe.prep_time, Some(Duration::from_minutes(20.0)));
+    assert_eq!(recipe.rest_time, Some(Duration::from_minutes(720.0)));
+
+    // Ingredients of all steps, without the section headers
+    assert_eq!(
+        ingredient_names(&recipe),
+        vec!["flour", "water", "salt", "rosemary"]
+    );
+    let water = &recipe.ingredients.as_ref().unwrap()[1].ingredient;
+    assert_eq!(water.amounts[0].unit, Unit::Volume(VolumeUnit::Milliliter));
+    assert_eq!(water.notes.as_deref(), Some("lukewarm"));
+
+    let steps = recipe.steps.unwrap();
+    assert_eq!(steps.len(), 2);
+    assert!(steps[0].timers.is_empty());
+    assert_eq!(steps[1].timers[0].to_string(), "25 minutes");
+}
+
+#[test]
+fn reads_tandoor_archive() {
+    let focaccia = zip(&[("recipe.json", TANDOOR.as_bytes()), ("image.png", b"photo")]);
+    let empty = zip(&[("image.png", b"photo")]);
+    let archive = zip(&[("1.zip", &focaccia), ("2.zip", &empty)]);
+
+    let results = formats::import_bytes(&archive, None).unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(
+        results[0].as_ref().unwrap().image.as_deref(),
+        Some("data:image/png;base64,cGhvdG8=")
+    );
+    assert_eq!(
+        results[1].as_ref().unwrap_err().to_string(),
+        "Recipe 2 (2): No recipe.json found"
+    );
+}
+
+#[test]
+fn detects_exported_json() {
+    assert_eq!(Format::detect(PAPRIKA), Format::Paprika);
+    assert_eq!(Format::detect(MEALIE), Format::Mealie);
+    assert_eq!(Format::detect(TANDOOR), Format::Tandoor);
+    assert_eq!(
+        Format::detect(r#"{"@context": "https://schema.org", "@type": "Recipe"}"#),
+        Format::SchemaOrg
+    );
+}
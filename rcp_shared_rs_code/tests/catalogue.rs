@@ -0,0 +1,121 @@
+use rcp_shared_rs_code::catalogue::{
+    autocomplete, find_entry, linked_ingredient, propose_links, with_canonical_names,
+};
+use rcp_shared_rs_code::models::catalogue_entry::CatalogueEntry;
+
+mod common;
+use common::{orf_recipe, BANANA_BREAD};
+
+fn entry(catalogue_uuid: &str, canonical_name: &str, synonyms: &[&str]) -> CatalogueEntry {
+    CatalogueEntry {
+        catalogue_uuid: catalogue_uuid.to_string(),
+        canonical_name: canonical_name.to_string(),
+        synonyms: synonyms.iter().map(|s| s.to_string()).collect(),
+        ..CatalogueEntry::default()
+    }
+}
+
+fn catalogue() -> Vec<CatalogueEntry> {
+    vec![
+        entry("1", "spring onion", &["scallion", "green onion"]),
+        entry("2", "onion", &[]),
+        entry("3", "flour", &["plain flour", "all-purpose flour"]),
+        entry("4", "butter", &[]),
+        entry("5", "sparkling water", &["soda water"]),
+    ]
+}
+
+#[test]
+fn finds_entries_by_synonym() {
+    let catalogue = catalogue();
+    let canonical = |name: &str| find_entry(&catalogue, name).map(|e| e.canonical_name.as_str());
+
+    assert_eq!(canonical("Scallions"), Some("spring onion"));
+    assert_eq!(canonical("green onions, chopped"), Some("spring onion"));
+    // The longest name wins, a red onion is still an onion
+    assert_eq!(canonical("red onions"), Some("onion"));
+    assert_eq!(canonical("All-Purpose Flour"), Some("flour"));
+    assert_eq!(canonical("bananas"), None);
+}
+
+#[test]
+fn completes_names_as_they_are_typed() {
+    let catalogue = catalogue();
+    let matched = |query: &str, limit: usize| -> Vec<String> {
+        autocomplete(&catalogue, query, limit)
+            .into_iter()
+            .map(|s| s.matched)
+            .collect()
+    };
+
+    // Names starting with the query first, the canonical name before the synonym "green onion"
+    assert_eq!(matched("on", 10), vec!["onion", "spring onion"]);
+    assert_eq!(matched("Sp", 10), vec!["spring onion", "sparkling water"]);
+    assert_eq!(matched("sp", 1), vec!["spring onion"]);
+    assert_eq!(matched("scall", 10), vec!["scallion"]);
+    assert!(matched("", 10).is_empty());
+}
+
+#[test]
+fn proposes_links_for_unlinked_ingredients() {
+    let catalogue = catalogue();
+    let mut recipe = orf_recipe(BANANA_BREAD);
+    recipe.recipe_uuid = "bread".to_string();
+    recipe.ingredients.as_mut().unwrap()[2]
+        .ingredient
+        .catalogue_uuid = Some("4".to_string());
+
+    let proposals = propose_links(&recipe, &catalogue);
+    let proposed: Vec<(usize, Option<usize>, &str)> = proposals
+        .iter()
+        .map(|p| {
+            (
+                p.ingredient_idx,
+                p.substitution_idx,
+                p.canonical_name.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(proposed, vec![(0, None, "flour")]);
+    assert_eq!(proposals[0].recipe_uuid, "bread");
+    assert_eq!(proposals[0].ingredient_name, "all-purpose flour");
+    assert!(linked_ingredient(&recipe, &proposals[0]).is_some());
+
+    recipe.ingredients.as_mut().unwrap()[0]
+        .ingredient
+        .catalogue_uuid = Some(proposals[0].catalogue_uuid.clone());
+    let canonical = with_canonical_names(&recipe, &catalogue);
+    let names: Vec<&str> = canonical
+        .ingredients
+        .iter()
+        .flatten()
+        .map(|ing| ing.ingredient.ingredient_name.as_str())
+        .collect();
+    assert_eq!(names, vec!["flour", "baking soda", "butter", "bananas"]);
+}
+
+#[test]
+fn rejects_links_to_changed_ingredients() {
+    let mut recipe = orf_recipe(BANANA_BREAD);
+    let mut link = propose_links(&recipe, &catalogue()).remove(0);
+    assert_eq!(
+        linked_ingredient(&recipe, &link).map(|data| data.ingredient_name.as_str()),
+        Some("all-purpose flour")
+    );
+
+    link.substitution_idx = Some(0);
+    assert!(
+        linked_ingredient(&recipe, &link).is_none(),
+        "no substitution"
+    );
+    link.substitution_idx = None;
+    link.ingredient_idx = 4;
+    assert!(linked_ingredient(&recipe, &link).is_none(), "past the end");
+
+    // The flour was replaced while the link waited to be checked
+    link.ingredient_idx = 0;
+    recipe.ingredients.as_mut().unwrap()[0]
+        .ingredient
+        .ingredient_name = "bread flour".to_string();
+    assert!(linked_ingredient(&recipe, &link).is_none());
+}
@@ -0,0 +1,43 @@
+// Helpers shared by the integration tests. Every test file compiles its own copy and uses only some of them.
+#![allow(dead_code)]
+
+use rcp_shared_rs_code::formats::orf::from_orf_yaml;
+use rcp_shared_rs_code::models::ingredient::IngredientData;
+use rcp_shared_rs_code::models::recipe::Recipe;
+use rcp_shared_rs_code::models::recipe_request::RecipeRequest;
+
+pub const BANANA_BREAD: &str = include_str!("../data/orf/banana_bread.yml");
+pub const COOKIES: &str = include_str!("../data/orf/chocolate_chip_cookies.yml");
+pub const ROAST_CHICKEN: &str = include_str!("../data/orf/roast_chicken.yml");
+
+// One of the Open Recipe Format recipes above
+pub fn orf_recipe(yaml: &str) -> Recipe {
+    Recipe::from(from_orf_yaml(yaml).unwrap())
+}
+
+// An ingredient with nothing but a name and one empty amount
+pub fn ingredient(name: &str) -> IngredientData {
+    IngredientData {
+        ingredient_name: name.to_string(),
+        ..IngredientData::new()
+    }
+}
+
+pub fn ingredient_names(recipe: &RecipeRequest) -> Vec<String> {
+    recipe
+        .ingredients
+        .iter()
+        .flatten()
+        .map(|ing| ing.ingredient.ingredient_name.clone())
+        .collect()
+}
+
+// For sums of nutrients and prices, which don't add up exactly
+pub fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 0.01,
+        "{} != {}",
+        actual,
+        expected
+    );
+}
@@ -0,0 +1,176 @@
+use rcp_shared_rs_code::formats::cooklang::{from_cooklang, to_cooklang};
+use rcp_shared_rs_code::formats::orf::from_orf_yaml;
+use rcp_shared_rs_code::formats::Format;
+use rcp_shared_rs_code::models::duration::Duration;
+use rcp_shared_rs_code::models::recipe::Recipe;
+use rcp_shared_rs_code::models::unit::{MassUnit, Unit, VolumeUnit};
+
+mod common;
+use common::ingredient_names;
+
+const PANCAKES: &str = include_str!("data/cooklang/easy_pancakes.cook");
+const RISOTTO: &str = include_str!("data/cooklang/risotto.cook");
+
+#[test]
+fn samples_are_valid_recipes() {
+    for cook in [PANCAKES, RISOTTO].iter() {
+        let recipe = from_cooklang(cook).unwrap();
+        assert_eq!(recipe.validate(), vec![], "{:?}", recipe.recipe_name);
+    }
+}
+
+#[test]
+fn reads_markup_and_metadata() {
+    let recipe = from_cooklang(PANCAKES).unwrap();
+
+    assert_eq!(recipe.recipe_name.as_deref(), Some("Easy Pancakes"));
+    assert_eq!(recipe.total_time, Some(Duration::from_minutes(20.0)));
+    assert_eq!(
+        recipe.source_url.as_deref(),
+        Some("https://cooklang.org/docs/spec/")
+    );
+    let yields = recipe.yields.clone().unwrap();
+    assert_eq!(
+        (yields[0].amount, yields[0].unit.as_str()),
+        (4.0, "pancakes")
+    );
+
+    // The second mention of butter refers to the first one
+    assert_eq!(
+        ingredient_names(&recipe),
+        vec![
+            "eggs",
+            "plain flour",
+            "milk",
+            "sea salt",
+            "butter",
+            "lemon juice"
+        ]
+    );
+    let ingredients = recipe.ingredients.clone().unwrap();
+    assert_eq!(ingredients[0].ingredient.amounts[0].amount, 3.0);
+    assert_eq!(ingredients[0].ingredient.amounts[0].unit, Unit::Count);
+    assert_eq!(
+        ingredients[1].ingredient.amounts[0].unit,
+        Unit::Mass(MassUnit::Gram)
+    );
+    assert_eq!(
+        ingredients[2].ingredient.amounts[0].unit,
+        Unit::Volume(VolumeUnit::Milliliter)
+    );
+    assert_eq!(ingredients[4].ingredient.processing, vec!["unsalted"]);
+    assert_eq!(
+        ingredients[5].ingredient.notes.as_deref(),
+        Some("a squeeze")
+    );
+
+    assert_eq!(
+        recipe.equipment,
+        Some(vec![
+            "blender".to_string(),
+            "bowl".to_string(),
+            "large non-stick frying pan".to_string()
+        ])
+    );
+
+    let steps = recipe.steps.clone().unwrap();
+    assert_eq!(steps.len(), 4);
+    assert_eq!(
+        steps[0].step,
+        "Crack the eggs into a blender, then add the plain flour, milk and sea salt, and blitz until smooth."
+    );
+    assert_eq!(
+        steps[1].step,
+        "Pour into a bowl and leave to stand for 15 minutes."
+    );
+    assert_eq!(
+        steps[1].notes.as_deref(),
+        Some("Resting lets the flour soak up the milk.")
+    );
+    assert_eq!(
+        steps[1].timers[0].duration(),
+        Some(Duration::from_minutes(15.0))
+    );
+
+    // Comments are dropped
+    assert!(!steps[3].step.contains("between pancakes"));
+    let timers = &steps[3].timers;
+    assert_eq!(timers.len(), 2);
+    assert_eq!(timers[1].name.as_deref(), Some("side two"));
+    assert_eq!(timers[1].duration(), Some(Duration::from_minutes(1.0)));
+}
+
+#[test]
+fn reads_front_matter_and_sections() {
+    let recipe = from_cooklang(RISOTTO).unwrap();
+
+    assert_eq!(recipe.recipe_name.as_deref(), Some("Mushroom Risotto"));
+    assert_eq!(recipe.prep_time, Some(Duration::from_minutes(10.0)));
+    assert_eq!(recipe.cook_time, Some(Duration::from_minutes(30.0)));
+    assert_eq!(recipe.yields.clone().unwrap()[0].amount, 2.0);
+    assert_eq!(ingredient_names(&recipe).len(), 8);
+    assert_eq!(recipe.steps.clone().unwrap().len(), 3);
+}
+
+#[test]
+fn round_trips() {
+    for cook in [PANCAKES, RISOTTO].iter() {
+        let recipe = Recipe::from(from_cooklang(cook).unwrap());
+        let exported = to_cooklang(&recipe);
+        let reimported = Recipe::from(from_cooklang(&exported).unwrap());
+        assert_eq!(to_cooklang(&reimported), exported);
+
+        let steps: Vec<String> = reimported
+            .steps
+            .unwrap()
+            .into_iter()
+            .map(|s| s.step)
+            .collect();
+        let original: Vec<String> = recipe.steps.unwrap().into_iter().map(|s| s.step).collect();
+        assert_eq!(steps, original);
+    }
+}
+
+#[test]
+fn writes_recipes_from_other_formats() {
+    let yaml = include_str!("data/orf/banana_bread.yml");
+    let recipe = Recipe::from(from_orf_yaml(yaml).unwrap());
+    let cook = to_cooklang(&recipe);
+
+    assert!(cook.starts_with(">> title: Banana Bread\n"));
+    let reimported = from_cooklang(&cook).unwrap();
+    assert_eq!(
+        ingredient_names(&reimported).len(),
+        recipe.ingredients.unwrap().len()
+    );
+    assert_eq!(reimported.steps.unwrap().len(), recipe.steps.unwrap().len());
+}
+
+#[test]
+fn detects_cooklang() {
+    assert_eq!(Format::detect(PANCAKES), Format::Cooklang);
+    assert_eq!(Format::detect(RISOTTO), Format::Cooklang);
+    assert_eq!(
+        Format::detect(include_str!("data/orf/banana_bread.yml")),
+        Format::Orf
+    );
+    assert_eq!(Format::detect("Boil the @eggs{2}."), Format::Cooklang);
+}
+
+#[test]
+fn keeps_double_dashes_outside_of_comments() {
+    let recipe =
+        from_cooklang(">> source: https://host/a--b\n\nBoil the @eggs{2} -- not too long.\n")
+            .unwrap();
+    assert_eq!(recipe.source_url.as_deref(), Some("https://host/a--b"));
+    assert_eq!(recipe.steps.clone().unwrap()[0].step, "Boil the eggs");
+
+    let mut recipe = Recipe::from(recipe);
+    recipe.steps.as_mut().unwrap()[0].step = "Boil the eggs -- not too long.".to_string();
+    let reimported = from_cooklang(&to_cooklang(&recipe)).unwrap();
+    assert_eq!(reimported.source_url.as_deref(), Some("https://host/a--b"));
+    assert_eq!(
+        reimported.steps.unwrap()[0].step,
+        "Boil the eggs — not too long."
+    );
+}
@@ -0,0 +1,105 @@
+use rcp_shared_rs_code::costing::{find_price, recipe_cost};
+use rcp_shared_rs_code::models::price_item::PriceItem;
+use rcp_shared_rs_code::models::unit::{MassUnit, Unit};
+
+mod common;
+use common::{assert_close, orf_recipe, ROAST_CHICKEN};
+
+fn price(name: &str, purchase_unit: Unit, pack_size: f64, price: f64) -> PriceItem {
+    PriceItem {
+        ingredient_name: name.to_string(),
+        purchase_unit,
+        pack_size,
+        price,
+        ..PriceItem::default()
+    }
+}
+
+#[test]
+fn calculates_recipe_cost() {
+    let recipe = orf_recipe(ROAST_CHICKEN);
+    let prices = vec![
+        price("chickens", Unit::Count, 1.0, 9.0),
+        PriceItem {
+            trim_yield: 50.0,
+            ..price("lemon", Unit::Count, 4.0, 2.0)
+        },
+        price("thyme", Unit::Other("bunch".to_string()), 1.0, 1.5),
+    ];
+    let cost = recipe_cost(&recipe, 0, &prices, Some(12.0));
+
+    // Half of every lemon is usable, so one costs as much as two
+    let costs: Vec<f64> = cost.ingredients.iter().map(|i| i.cost).collect();
+    assert_eq!(costs, vec![9.0, 1.0]);
+    assert_close(cost.total, 10.0);
+    assert_close(cost.per_yield_unit.unwrap(), 2.5);
+    assert_eq!(cost.yield_unit.as_deref(), Some("servings"));
+    assert_close(cost.food_cost_percent.unwrap(), 2.5 / 12.0 * 100.0);
+
+    assert_eq!(cost.missing.len(), 1);
+    assert_eq!(cost.missing[0].ingredient_name, "thyme");
+    assert_eq!(
+        cost.missing[0].reason,
+        "can't convert \"4 sprigs\" to the purchase unit \"bunch\""
+    );
+}
+
+#[test]
+fn converts_to_the_purchase_unit() {
+    let mut recipe = orf_recipe(ROAST_CHICKEN);
+    let chicken = &mut recipe.ingredients.as_mut().unwrap()[0].ingredient;
+    chicken.amounts[0].amount = 1500.0;
+    chicken.amounts[0].unit = Unit::Mass(MassUnit::Gram);
+
+    let prices = vec![
+        price("whole chicken", Unit::Mass(MassUnit::Kilogram), 1.0, 1.0),
+        price("chicken", Unit::Mass(MassUnit::Kilogram), 2.0, 12.0),
+        price("thyme", Unit::Other("sprig".to_string()), 20.0, 2.0),
+    ];
+    let cost = recipe_cost(&recipe, 0, &prices, None);
+
+    assert_eq!(cost.ingredients[0].price_item, "chicken");
+    assert_close(cost.ingredients[0].quantity, 1.5);
+    assert_close(cost.ingredients[0].cost, 9.0);
+    assert_eq!(cost.ingredients[1].ingredient_name, "thyme");
+    assert_close(cost.ingredients[1].cost, 0.4);
+    assert_eq!(cost.food_cost_percent, None);
+
+    assert_eq!(cost.missing.len(), 1);
+    assert_eq!(cost.missing[0].ingredient_name, "lemon");
+    assert_eq!(cost.missing[0].reason, "not in the price list");
+}
+
+#[test]
+fn matches_price_names() {
+    let prices = vec![
+        price("butter", Unit::Mass(MassUnit::Kilogram), 1.0, 8.0),
+        price("peanut butter", Unit::Mass(MassUnit::Kilogram), 1.0, 6.0),
+        price("Eggs", Unit::Count, 10.0, 3.0),
+    ];
+    let name =
+        |ingredient: &str| find_price(&prices, ingredient).map(|p| p.ingredient_name.as_str());
+
+    assert_eq!(name("egg"), Some("Eggs"));
+    assert_eq!(name("large free-range eggs"), Some("Eggs"));
+    assert_eq!(name("unsalted butter"), Some("butter"));
+    assert_eq!(name("crunchy peanut butter"), Some("peanut butter"));
+    assert_eq!(name("eggplant"), None);
+}
+
+#[test]
+fn validates_price_items() {
+    assert!(price("flour", Unit::Mass(MassUnit::Kilogram), 25.0, 18.5)
+        .validate()
+        .is_empty());
+
+    let invalid = PriceItem {
+        trim_yield: 120.0,
+        ..price(" ", Unit::Count, 0.0, -1.0)
+    };
+    let fields: Vec<String> = invalid.validate().into_iter().map(|e| e.field).collect();
+    assert_eq!(
+        fields,
+        vec!["ingredient_name", "pack_size", "price", "trim_yield"]
+    );
+}
@@ -0,0 +1,99 @@
+use rcp_shared_rs_code::density::Densities;
+use rcp_shared_rs_code::models::catalogue_entry::CatalogueEntry;
+use rcp_shared_rs_code::models::ingredient::Amount;
+use rcp_shared_rs_code::models::unit::{MassUnit, Unit, VolumeUnit};
+
+mod common;
+use common::{ingredient, orf_recipe, BANANA_BREAD};
+
+fn amount(amount: f64, unit: Unit) -> Amount {
+    Amount {
+        amount,
+        amount_max: None,
+        unit,
+    }
+}
+
+#[test]
+fn converts_between_volume_and_weight() {
+    let densities = Densities::bundled();
+    let flour = ingredient("all-purpose flour");
+    let cups = amount(2.0, Unit::Volume(VolumeUnit::Cup));
+
+    let grams = densities
+        .convert(&flour, &cups, &Unit::Mass(MassUnit::Gram))
+        .unwrap();
+    assert_eq!(grams.to_string(), "251 g");
+
+    let back = densities
+        .convert(&flour, &grams, &Unit::Volume(VolumeUnit::Cup))
+        .unwrap();
+    assert!((back.amount - 2.0).abs() < 1e-9);
+
+    assert_eq!(
+        densities.density_of(&ingredient("Smooth Peanut Butter")),
+        Some(1.14),
+        "the longest name wins"
+    );
+    assert!(densities
+        .convert(&ingredient("saffron"), &cups, &Unit::Mass(MassUnit::Gram))
+        .is_none());
+    assert!(densities
+        .convert(
+            &flour,
+            &amount(1.0, Unit::Other("pinch".to_string())),
+            &Unit::Mass(MassUnit::Gram)
+        )
+        .is_none());
+}
+
+#[test]
+fn shows_a_recipe_in_grams() {
+    let bread = orf_recipe(BANANA_BREAD);
+    let in_grams = Densities::bundled().recipe_in_grams(&bread);
+
+    let amounts: Vec<String> = in_grams
+        .ingredients
+        .iter()
+        .flatten()
+        .map(|ing| ing.ingredient.get_amount_str(0))
+        .collect();
+    assert_eq!(amounts, vec!["251 g", "6 g", "114 g", "2-3"]);
+    assert_eq!(
+        in_grams.ingredients.as_ref().unwrap()[2].substitutions[0].get_amount_str(0),
+        "114 g",
+        "substitutions are converted as well"
+    );
+}
+
+#[test]
+fn user_densities_replace_bundled_ones() {
+    let mut densities = Densities::bundled();
+    let own = Densities::from_yaml("flour: 0.6\nsaffron: 0.4\n").unwrap();
+    densities.extend(&own);
+    assert_eq!(densities.density_of(&ingredient("flour")), Some(0.6));
+    assert_eq!(
+        densities.density_of(&ingredient("saffron threads")),
+        Some(0.4)
+    );
+    assert_eq!(
+        densities.density_of(&ingredient("all-purpose flour")),
+        Some(0.53),
+        "only the same name is replaced"
+    );
+
+    densities.extend_from_catalogue(&[CatalogueEntry {
+        catalogue_uuid: "1".to_string(),
+        canonical_name: "spring onion".to_string(),
+        synonyms: vec!["scallion".to_string()],
+        default_density: Some(0.25),
+        ..CatalogueEntry::default()
+    }]);
+    assert_eq!(
+        densities.density_of(&ingredient("chopped scallions")),
+        Some(0.25)
+    );
+
+    assert!(Densities::from_yaml("butter: -1").is_err());
+    assert!(Densities::from_yaml("oat: 0.4\noats: 0.38").is_err());
+}
@@ -0,0 +1,95 @@
+use std::collections::BTreeMap;
+
+use rcp_shared_rs_code::diets::{recipe_diets, DietError, DietTaxonomy, DietViolation};
+use rcp_shared_rs_code::models::allergen::Allergen;
+use rcp_shared_rs_code::models::diet::Diet;
+
+mod common;
+use common::{orf_recipe, BANANA_BREAD, ROAST_CHICKEN};
+
+#[test]
+fn classifies_recipes() {
+    let taxonomy = DietTaxonomy::bundled();
+
+    let chicken = orf_recipe(ROAST_CHICKEN);
+    assert_eq!(
+        recipe_diets(&chicken, &taxonomy).diets,
+        vec![Diet::GlutenFree, Diet::DairyFree]
+    );
+
+    let bread = orf_recipe(BANANA_BREAD);
+    let diets = recipe_diets(&bread, &taxonomy);
+    assert_eq!(
+        diets.diets,
+        vec![Diet::Vegetarian, Diet::Pescatarian, Diet::Halal]
+    );
+
+    // Which ingredient broke the flag, the margarine substitution doesn't count
+    let vegan = diets.flags.iter().find(|f| f.diet == Diet::Vegan).unwrap();
+    assert!(!vegan.suitable);
+    assert_eq!(
+        vegan.broken_by,
+        vec![DietViolation {
+            ingredient_name: "butter".to_string(),
+            category: "dairy".to_string(),
+            term: "butter".to_string(),
+        }]
+    );
+    let gluten_free = diets.flags.iter().find(|f| f.diet == Diet::GlutenFree);
+    assert_eq!(
+        gluten_free.unwrap().broken_by[0].ingredient_name,
+        "all-purpose flour"
+    );
+}
+
+#[test]
+fn uses_overrides_and_declared_allergens() {
+    let taxonomy = DietTaxonomy::bundled();
+    let mut chicken = orf_recipe(ROAST_CHICKEN);
+
+    // Made with certified meat
+    let mut overrides = BTreeMap::new();
+    overrides.insert(Diet::Halal, true);
+    overrides.insert(Diet::DairyFree, false);
+    chicken.diet_overrides = Some(overrides);
+    let diets = recipe_diets(&chicken, &taxonomy);
+    assert_eq!(diets.diets, vec![Diet::GlutenFree, Diet::Halal]);
+    let halal = diets.flags.iter().find(|f| f.diet == Diet::Halal).unwrap();
+    assert!(halal.overridden);
+    assert_eq!(halal.broken_by[0].category, "unlabelled_meat");
+
+    // Gluten-free bread, as declared on the ingredient
+    let mut bread = orf_recipe(BANANA_BREAD);
+    bread.ingredients.as_mut().unwrap()[0].ingredient.allergens = Some(vec![]);
+    let diets = recipe_diets(&bread, &taxonomy).diets;
+    assert!(diets.contains(&Diet::GlutenFree));
+    bread.ingredients.as_mut().unwrap()[2].ingredient.allergens = Some(vec![Allergen::Eggs]);
+    let diets = recipe_diets(&bread, &taxonomy).diets;
+    assert!(diets.contains(&Diet::DairyFree));
+}
+
+#[test]
+fn reads_taxonomy_file() {
+    let taxonomy = DietTaxonomy::from_yaml(
+        "categories:
+  meat:
+    contains: [beef, lamb]
+  honey:
+    contains: [honey]
+  animal:
+    include: [meat, honey]
+    except: [plant-based]
+diets:
+  vegan: [animal]
+",
+    )
+    .unwrap();
+    let recipe = orf_recipe(BANANA_BREAD);
+    assert_eq!(recipe_diets(&recipe, &taxonomy).diets, vec![Diet::Vegan]);
+    assert_eq!(taxonomy.categories.len(), 3);
+
+    assert_eq!(
+        DietTaxonomy::from_yaml("categories: {}\ndiets:\n  Vegan: [meat]\n").unwrap_err(),
+        DietError("Unknown category meat in Vegan".to_string())
+    );
+}
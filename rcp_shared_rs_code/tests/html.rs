@@ -0,0 +1,138 @@
+use rcp_shared_rs_code::formats::html::from_html;
+use rcp_shared_rs_code::models::duration::Duration;
+use rcp_shared_rs_code::models::recipe_request::RecipeRequest;
+
+mod common;
+use common::ingredient_names;
+
+// Saved pages reduced to the markup that matters, in the layouts of common recipe plugins and blogs
+const WPRM_JSON_LD: &str = include_str!("data/html/wprm_json_ld.html");
+const MICRODATA: &str = include_str!("data/html/microdata.html");
+const PLAIN_BLOG: &str = include_str!("data/html/plain_blog.html");
+const JSON_LD_IN_COMMENT: &str = include_str!("data/html/json_ld_in_comment.html");
+
+fn steps(recipe: &RecipeRequest) -> Vec<String> {
+    recipe
+        .steps
+        .iter()
+        .flatten()
+        .map(|step| step.step.clone())
+        .collect()
+}
+
+#[test]
+fn fixtures_are_valid_recipes() {
+    for html in [WPRM_JSON_LD, MICRODATA, PLAIN_BLOG, JSON_LD_IN_COMMENT].iter() {
+        let recipe = from_html(html, None).unwrap();
+        assert_eq!(recipe.validate(), vec![], "{:?}", recipe.recipe_name);
+    }
+}
+
+#[test]
+fn reads_json_ld_graph() {
+    let recipe = from_html(WPRM_JSON_LD, None).unwrap();
+
+    assert_eq!(recipe.recipe_name.as_deref(), Some("Easy Shakshuka"));
+    assert_eq!(
+        recipe.notes.as_deref(),
+        Some("Eggs poached in a spiced tomato & pepper sauce. Ready in 30 minutes.")
+    );
+    assert_eq!(recipe.source_authors, Some(vec!["Maya".to_string()]));
+    assert_eq!(recipe.total_time, Some(Duration::from_minutes(30.0)));
+    assert_eq!(
+        recipe.source_url.as_deref(),
+        Some("https://www.example-kitchen.com/easy-shakshuka/")
+    );
+
+    // The structured data lists all ingredients, the visible card only two
+    assert_eq!(ingredient_names(&recipe).len(), 8);
+    assert_eq!(ingredient_names(&recipe)[6], "eggs");
+    assert_eq!(
+        steps(&recipe)[1],
+        "Add garlic & cumin, then the tomatoes. Simmer for 10 minutes."
+    );
+}
+
+#[test]
+fn reads_microdata() {
+    let recipe = from_html(MICRODATA, None).unwrap();
+
+    assert_eq!(
+        recipe.recipe_name.as_deref(),
+        Some("Classic Buttermilk Biscuits")
+    );
+    assert_eq!(recipe.source_authors, Some(vec!["Grandma Lou".to_string()]));
+    assert_eq!(recipe.prep_time, Some(Duration::from_minutes(15.0)));
+
+    let yields = recipe.yields.clone().unwrap();
+    assert_eq!(
+        (yields[0].amount, yields[0].unit.as_str()),
+        (12.0, "biscuits")
+    );
+
+    assert_eq!(
+        ingredient_names(&recipe),
+        vec![
+            "all-purpose flour",
+            "baking powder",
+            "salt",
+            "cold butter",
+            "buttermilk"
+        ]
+    );
+    assert_eq!(steps(&recipe).len(), 3);
+    assert_eq!(
+        recipe.source_url.as_deref(),
+        Some("https://recipes.example.org/recipe/1234/classic-buttermilk-biscuits/")
+    );
+}
+
+#[test]
+fn falls_back_to_headed_lists() {
+    let recipe = from_html(PLAIN_BLOG, None).unwrap();
+
+    assert_eq!(recipe.recipe_name.as_deref(), Some("Red Lentil Soup"));
+    assert_eq!(ingredient_names(&recipe).len(), 6);
+    assert_eq!(ingredient_names(&recipe)[3], "red lentils");
+    assert_eq!(
+        steps(&recipe),
+        vec![
+            "Fry the onion and carrots in the oil until soft.",
+            "Add the lentils and stock and simmer for 20 minutes.",
+            "Blend until smooth and season."
+        ]
+    );
+    assert_eq!(
+        recipe.source_url.as_deref(),
+        Some("https://tinykitchen.example.net/2019/11/red-lentil-soup.html")
+    );
+}
+
+#[test]
+fn reads_json_ld_wrapped_in_comment() {
+    let recipe = from_html(JSON_LD_IN_COMMENT, None).unwrap();
+
+    assert_eq!(recipe.recipe_name.as_deref(), Some("Kaiserschmarrn"));
+    assert_eq!(
+        recipe.notes.as_deref(),
+        Some("Der Klassiker aus Österreich.\nMit Puderzucker und Apfelmus servieren.")
+    );
+    assert_eq!(recipe.cook_time, Some(Duration::from_minutes(15.0)));
+    assert_eq!(ingredient_names(&recipe)[0], "Eier");
+    assert_eq!(steps(&recipe).len(), 3);
+}
+
+#[test]
+fn given_source_url_wins() {
+    let recipe = from_html(PLAIN_BLOG, Some("https://example.com/saved")).unwrap();
+    assert_eq!(
+        recipe.source_url.as_deref(),
+        Some("https://example.com/saved")
+    );
+}
+
+#[test]
+fn rejects_pages_without_recipe() {
+    let html = "<html><head><title>News</title></head><body><ul><li>Item</li></ul></body></html>";
+    assert!(from_html(html, None).is_err());
+}
@@ -0,0 +1,112 @@
+use std::str::FromStr;
+
+use rcp_shared_rs_code::models::ingredient::Ingredient;
+
+// (line, amount, amount_max, unit, ingredient_name, processing, notes, substitution names)
+type Case = (
+    &'static str,
+    f64,
+    Option<f64>,
+    &'static str,
+    &'static str,
+    &'static [&'static str],
+    Option<&'static str>,
+    &'static [&'static str],
+);
+
+// Lines as they appear on recipe websites and in cookbooks
+#[rustfmt::skip]
+const CORPUS: &[Case] = &[
+    ("2 cups all-purpose flour, sifted (or cake flour)", 2.0, None, "cup", "all-purpose flour", &["sifted"], None, &["cake flour"]),
+    ("1 1/2 cups granulated sugar", 1.5, None, "cup", "granulated sugar", &[], None, &[]),
+    ("¾ tsp baking soda", 0.75, None, "tsp", "baking soda", &[], None, &[]),
+    ("1½ teaspoons vanilla extract", 1.5, None, "tsp", "vanilla extract", &[], None, &[]),
+    ("2-3 cloves garlic, minced", 2.0, Some(3.0), "cloves", "garlic", &["minced"], None, &[]),
+    ("200g butter, softened", 200.0, None, "g", "butter", &["softened"], None, &[]),
+    ("500 g Mehl", 500.0, None, "g", "Mehl", &[], None, &[]),
+    ("3 large eggs", 3.0, None, "", "eggs", &["large"], None, &[]),
+    ("1 medium onion, finely chopped", 1.0, None, "", "onion", &["medium", "finely chopped"], None, &[]),
+    ("1 cup chopped walnuts", 1.0, None, "cup", "walnuts", &["chopped"], None, &[]),
+    ("2 tbsp. olive oil", 2.0, None, "tbsp", "olive oil", &[], None, &[]),
+    ("1 T honey", 1.0, None, "tbsp", "honey", &[], None, &[]),
+    ("1 t salt", 1.0, None, "tsp", "salt", &[], None, &[]),
+    ("4 fl oz heavy cream", 4.0, None, "fl oz", "heavy cream", &[], None, &[]),
+    ("1 lb ground beef", 1.0, None, "lb", "ground beef", &[], None, &[]),
+    ("1 (14 oz) can diced tomatoes", 1.0, None, "can", "tomatoes", &["diced"], Some("14 oz"), &[]),
+    ("1 cup butter or margarine, melted", 1.0, None, "cup", "butter", &["melted"], None, &["margarine"]),
+    ("a pinch of salt", 1.0, None, "pinch", "salt", &[], None, &[]),
+    ("Salt and pepper to taste", 0.0, None, "", "Salt and pepper", &[], Some("to taste"), &[]),
+    ("freshly ground black pepper, to taste", 0.0, None, "", "freshly ground black pepper", &[], Some("to taste"), &[]),
+    ("1/2 cup milk (about 120 ml)", 0.5, None, "cup", "milk", &[], Some("about 120 ml"), &[]),
+    ("2 cups of water", 2.0, None, "cup", "water", &[], None, &[]),
+    ("1 kg potatoes, peeled and quartered", 1.0, None, "kg", "potatoes", &["peeled", "quartered"], None, &[]),
+    ("- 250 ml whole milk", 250.0, None, "ml", "whole milk", &[], None, &[]),
+    ("2 to 3 tablespoons lemon juice", 2.0, Some(3.0), "tbsp", "lemon juice", &[], None, &[]),
+    ("1 bunch fresh parsley, chopped, for garnish", 1.0, None, "bunch", "fresh parsley", &["chopped"], Some("for garnish"), &[]),
+    ("3 cups chicken stock (or 3 cups vegetable stock)", 3.0, None, "cup", "chicken stock", &[], None, &["vegetable stock"]),
+    ("1,5 l Wasser", 1.5, None, "l", "Wasser", &[], None, &[]),
+    ("2 EL Zucker", 2.0, None, "tbsp", "Zucker", &[], None, &[]),
+    ("1 sprig rosemary", 1.0, None, "sprig", "rosemary", &[], None, &[]),
+];
+
+#[test]
+fn parses_ingredient_line_corpus() {
+    for (line, amount, amount_max, unit, name, processing, notes, substitutions) in CORPUS {
+        let ingredient =
+            Ingredient::from_str(line).unwrap_or_else(|_| panic!("failed to parse {:?}", line));
+        let data = &ingredient.ingredient;
+
+        assert_eq!(data.amounts.len(), 1, "{:?}", line);
+        assert!(
+            (data.amounts[0].amount - amount).abs() < 1e-9,
+            "amount of {:?}: {:?}",
+            line,
+            data.amounts[0]
+        );
+        assert_eq!(
+            data.amounts[0].amount_max, *amount_max,
+            "amount_max of {:?}",
+            line
+        );
+        assert_eq!(
+            data.amounts[0].unit.to_string(),
+            *unit,
+            "unit of {:?}",
+            line
+        );
+        assert_eq!(data.ingredient_name, *name, "name of {:?}", line);
+        assert_eq!(data.processing, *processing, "processing of {:?}", line);
+        assert_eq!(data.notes.as_deref(), *notes, "notes of {:?}", line);
+
+        let substitution_names: Vec<&str> = ingredient
+            .substitutions
+            .iter()
+            .map(|s| s.ingredient_name.as_str())
+            .collect();
+        assert_eq!(
+            substitution_names, *substitutions,
+            "substitutions of {:?}",
+            line
+        );
+    }
+}
+
+#[test]
+fn substitutions_inherit_the_amount() {
+    let ingredient =
+        Ingredient::from_str("2 cups all-purpose flour, sifted (or cake flour)").unwrap();
+    let substitution = &ingredient.substitutions[0];
+    assert_eq!(substitution.amounts[0].amount, 2.0);
+    assert_eq!(substitution.amounts[0].unit.to_string(), "cup");
+
+    let ingredient =
+        Ingredient::from_str("3 cups chicken stock (or 3 cups vegetable stock)").unwrap();
+    assert_eq!(ingredient.substitutions[0].amounts[0].amount, 3.0);
+}
+
+#[test]
+fn rejects_lines_without_a_name() {
+    assert!(Ingredient::from_str("").is_err());
+    assert!(Ingredient::from_str("2 cups").is_err());
+    assert!(Ingredient::from_str("(optional)").is_err());
+}
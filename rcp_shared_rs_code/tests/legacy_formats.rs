@@ -0,0 +1,156 @@
+use rcp_shared_rs_code::formats::mealmaster::{from_mealmaster, mealmaster_unit};
+use rcp_shared_rs_code::formats::recipeml::from_recipeml;
+use rcp_shared_rs_code::formats::{self, Format};
+use rcp_shared_rs_code::models::duration::Duration;
+use rcp_shared_rs_code::models::unit::{MassUnit, Unit, VolumeUnit};
+
+mod common;
+use common::ingredient_names;
+
+const MEALMASTER: &str = include_str!("data/mealmaster/collection.mmf");
+const RECIPEML: &str = include_str!("data/recipeml/collection.xml");
+
+#[test]
+fn reads_every_mealmaster_recipe() {
+    let results = from_mealmaster(MEALMASTER).unwrap();
+    assert_eq!(results.len(), 3);
+
+    // The broken recipe is reported, the others are still read
+    let error = results[1].as_ref().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Recipe 2 (Broken Biscuits): invalid amount \"2/0\" in line 33"
+    );
+
+    for result in [&results[0], &results[2]].iter() {
+        let recipe = result.as_ref().unwrap();
+        assert_eq!(recipe.validate(), vec![], "{:?}", recipe.recipe_name);
+    }
+}
+
+#[test]
+fn reads_mealmaster_columns() {
+    let results = from_mealmaster(MEALMASTER).unwrap();
+    let recipe = results[0].as_ref().unwrap();
+
+    assert_eq!(recipe.recipe_name.as_deref(), Some("Banana Nut Bread"));
+    assert_eq!(
+        recipe.categories,
+        Some(vec!["Breads".to_string(), "Cakes".to_string()])
+    );
+    let yields = recipe.yields.clone().unwrap();
+    assert_eq!((yields[0].amount, yields[0].unit.as_str()), (1.0, "loaf"));
+
+    // Two columns are read row by row
+    assert_eq!(
+        ingredient_names(recipe),
+        vec![
+            "All-purpose flour",
+            "Baking soda",
+            "Salt",
+            "Butter",
+            "Brown sugar",
+            "Eggs",
+            "Bananas",
+            "Walnuts"
+        ]
+    );
+    let ingredients = recipe.ingredients.clone().unwrap();
+    let bananas = &ingredients[6].ingredient;
+    assert!((bananas.amounts[0].amount - 7.0 / 3.0).abs() < 1e-9);
+    assert_eq!(bananas.amounts[0].unit, Unit::Volume(VolumeUnit::Cup));
+    assert_eq!(bananas.processing, vec!["mashed"]);
+    assert_eq!(
+        ingredients[5].ingredient.amounts[0].unit,
+        Unit::Other("large".to_string())
+    );
+    assert_eq!(
+        ingredients[7].ingredient.processing,
+        vec!["chopped and toasted"]
+    );
+
+    let steps = recipe.steps.clone().unwrap();
+    assert_eq!(steps.len(), 3);
+    assert_eq!(
+        steps[1].step,
+        "Combine flour, baking soda and salt. In a separate bowl, cream together butter and brown sugar. Stir in eggs and mashed bananas."
+    );
+    assert_eq!(
+        recipe.notes.as_deref(),
+        Some("Source: Aunt Edna's recipe box")
+    );
+}
+
+#[test]
+fn reads_old_mealmaster_layout() {
+    let results = from_mealmaster(MEALMASTER).unwrap();
+    let recipe = results[2].as_ref().unwrap();
+
+    assert_eq!(recipe.recipe_name.as_deref(), Some("Garlic Butter"));
+    assert_eq!(recipe.yields.clone().unwrap()[0].unit, "servings");
+    assert_eq!(ingredient_names(recipe).len(), 4);
+    assert_eq!(recipe.steps.clone().unwrap().len(), 1);
+}
+
+#[test]
+fn maps_mealmaster_unit_codes() {
+    assert_eq!(mealmaster_unit("ts"), Unit::Volume(VolumeUnit::Teaspoon));
+    assert_eq!(mealmaster_unit("t"), Unit::Volume(VolumeUnit::Teaspoon));
+    assert_eq!(mealmaster_unit("T"), Unit::Volume(VolumeUnit::Tablespoon));
+    assert_eq!(mealmaster_unit("tb"), Unit::Volume(VolumeUnit::Tablespoon));
+    assert_eq!(mealmaster_unit("c"), Unit::Volume(VolumeUnit::Cup));
+    assert_eq!(mealmaster_unit("lb"), Unit::Mass(MassUnit::Pound));
+    assert_eq!(mealmaster_unit("x"), Unit::Count);
+    assert_eq!(mealmaster_unit("pn"), Unit::Other("pinch".to_string()));
+}
+
+#[test]
+fn reads_every_recipeml_recipe() {
+    let results = from_recipeml(RECIPEML).unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(
+        results[1].as_ref().unwrap_err().to_string(),
+        "Recipe 2 (Bad Pancakes): invalid amount \"lots\""
+    );
+
+    let cake = results[0].as_ref().unwrap();
+    assert_eq!(cake.validate(), vec![]);
+    assert_eq!(cake.recipe_name.as_deref(), Some("Lemon Drizzle Cake"));
+    assert_eq!(cake.categories, Some(vec!["Cakes".to_string()]));
+    assert_eq!(cake.prep_time, Some(Duration::from_minutes(15.0)));
+    assert_eq!(cake.cook_time, Some(Duration::from_minutes(45.0)));
+    assert_eq!(cake.source_authors, Some(vec!["Mary".to_string()]));
+    assert_eq!(
+        cake.source_url.as_deref(),
+        Some("https://example.org/lemon-drizzle")
+    );
+
+    let ingredients = cake.ingredients.clone().unwrap();
+    assert_eq!(ingredients.len(), 6);
+    assert_eq!(ingredients[3].substitutions.len(), 1);
+    assert_eq!(ingredients[4].ingredient.amounts[0].amount, 0.5);
+    assert_eq!(ingredients[5].ingredient.amounts[0].amount_max, Some(2.0));
+
+    let steps = cake.steps.clone().unwrap();
+    assert_eq!(
+        steps[0].step,
+        "Beat the butter and sugar until pale and creamy, then add the eggs one at a time."
+    );
+    assert_eq!(steps[1].notes.as_deref(), Some("Test with a skewer."));
+
+    let tea = results[2].as_ref().unwrap();
+    assert_eq!(tea.validate(), vec![]);
+    assert_eq!(tea.steps.clone().unwrap().len(), 2);
+}
+
+#[test]
+fn detects_legacy_formats() {
+    assert_eq!(Format::detect(MEALMASTER), Format::MealMaster);
+    assert_eq!(Format::detect(RECIPEML), Format::RecipeMl);
+
+    // A single import takes the first recipe
+    let recipe = formats::import(RECIPEML, None).unwrap();
+    assert_eq!(recipe.recipe_name.as_deref(), Some("Lemon Drizzle Cake"));
+    assert_eq!(formats::import_all(MEALMASTER, None).unwrap().len(), 3);
+    assert!(from_recipeml("<recipeml>").is_err());
+}
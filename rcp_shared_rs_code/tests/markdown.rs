@@ -0,0 +1,96 @@
+use rcp_shared_rs_code::formats::markdown::{to_markdown, to_plain_text};
+use rcp_shared_rs_code::formats::orf::from_orf_yaml;
+use rcp_shared_rs_code::models::recipe::Recipe;
+
+const ROAST_CHICKEN: &str = include_str!("data/orf/roast_chicken.yml");
+
+fn roast_chicken() -> Recipe {
+    let mut recipe = Recipe::from(from_orf_yaml(ROAST_CHICKEN).unwrap());
+    recipe.source_authors = Some(vec!["A. Cook".to_string()]);
+    recipe.source_url = Some("https://example.com/roast-chicken".to_string());
+    recipe.steps.as_mut().unwrap()[0].notes = Some("Pat the skin dry first.".to_string());
+    recipe
+}
+
+#[test]
+fn renders_markdown() {
+    let expected = "# Roast Chicken
+
+Rest for 10 minutes before carving.
+
+**Yield:** 4 servings  
+**Total time:** 1 h 20 min  
+**Oven:** Gas Mark 6, fan high, 1 h 20 min
+
+## Ingredients
+
+- 1 chicken (About 1.5 kg)
+- 1 lemon, halved
+- 4 sprigs thyme
+
+## Steps
+
+1. Stuff the chicken with lemon and thyme.
+
+   > *Note:* Pat the skin dry first.
+
+2. Roast until the juices run clear.
+
+   > **Critical control point:** Cook until the thickest part reaches an internal temperature of 74 °C.
+
+## Source
+
+**By:** A. Cook  
+**URL:** <https://example.com/roast-chicken>
+";
+    assert_eq!(to_markdown(&roast_chicken()), expected);
+}
+
+#[test]
+fn renders_plain_text() {
+    let expected = "Roast Chicken
+=============
+
+Rest for 10 minutes before carving.
+
+Yield: 4 servings
+Total time: 1 h 20 min
+Oven: Gas Mark 6, fan high, 1 h 20 min
+
+Ingredients
+-----------
+
+- 1 chicken (About 1.5 kg)
+- 1 lemon, halved
+- 4 sprigs thyme
+
+Steps
+-----
+
+1. Stuff the chicken with lemon and thyme.
+   Note: Pat the skin dry first.
+
+2. Roast until the juices run clear.
+   CRITICAL CONTROL POINT: Cook until the thickest part reaches an internal temperature of 74 °C.
+
+Source
+------
+
+By: A. Cook
+URL: https://example.com/roast-chicken
+";
+    assert_eq!(to_plain_text(&roast_chicken()), expected);
+}
+
+#[test]
+fn escapes_markdown() {
+    let recipe = Recipe {
+        recipe_name: Some("Mom's *best* pie".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(to_markdown(&recipe), "# Mom's \\*best\\* pie\n");
+    assert_eq!(
+        to_plain_text(&recipe),
+        "Mom's *best* pie\n================\n"
+    );
+}
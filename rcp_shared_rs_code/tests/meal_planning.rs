@@ -0,0 +1,115 @@
+use chrono::NaiveDate;
+use rcp_shared_rs_code::meal_planning::{plan_shopping_items, planned_yield, prep_schedule};
+use rcp_shared_rs_code::models::meal_plan::{MealPlanEntry, MealSlot};
+use rcp_shared_rs_code::models::recipe::Recipe;
+use rcp_shared_rs_code::shopping::Aisles;
+use std::str::FromStr;
+
+mod common;
+use common::{orf_recipe, BANANA_BREAD, ROAST_CHICKEN};
+
+fn entry(date: &str, meal_slot: MealSlot, recipe: &Recipe, servings: f64) -> MealPlanEntry {
+    MealPlanEntry {
+        date: NaiveDate::from_str(date).unwrap(),
+        meal_slot,
+        recipe_uuid: recipe.recipe_uuid.clone(),
+        servings,
+        ..MealPlanEntry::default()
+    }
+}
+
+fn recipe(orf: &str, recipe_uuid: &str) -> Recipe {
+    Recipe {
+        recipe_uuid: recipe_uuid.to_string(),
+        ..orf_recipe(orf)
+    }
+}
+
+#[test]
+fn schedules_prep_before_the_meal() {
+    let chicken = recipe(ROAST_CHICKEN, "chicken");
+    let bread = recipe(BANANA_BREAD, "bread");
+    let dinner = entry("2020-11-30", MealSlot::Dinner, &chicken, 4.0);
+    let breakfast = entry("2020-12-01", MealSlot::Breakfast, &bread, 1.0);
+    let first_breakfast = entry("2020-11-30", MealSlot::Breakfast, &bread, 1.0);
+
+    let tasks = prep_schedule(&[
+        (&breakfast, &bread),
+        (&dinner, &chicken),
+        (&first_breakfast, &bread),
+    ]);
+    let schedule: Vec<(String, &str)> = tasks
+        .iter()
+        .map(|task| (task.start.to_string(), task.recipe_uuid.as_str()))
+        .collect();
+    assert_eq!(
+        schedule,
+        vec![
+            ("2020-11-30 07:00:00".to_string(), "bread"),
+            ("2020-11-30 17:40:00".to_string(), "chicken"),
+            ("2020-12-01 07:00:00".to_string(), "bread"),
+        ]
+    );
+    assert_eq!(tasks[1].total_time.unwrap().minutes(), 80.0);
+    assert_eq!(tasks[1].recipe_name.as_deref(), Some("Roast Chicken"));
+
+    // A meal that takes longer than the morning starts the day before
+    let mut brined = chicken.clone();
+    brined.rest_time = Some(FromStr::from_str("12 h").unwrap());
+    brined.prep_time = Some(FromStr::from_str("30 min").unwrap());
+    let lunch = entry("2020-12-01", MealSlot::Lunch, &brined, 4.0);
+    let tasks = prep_schedule(&[(&lunch, &brined)]);
+    assert_eq!(tasks[0].start.to_string(), "2020-12-01 00:00:00");
+    let tasks = prep_schedule(&[(
+        &entry("2020-12-01", MealSlot::Breakfast, &brined, 4.0),
+        &brined,
+    )]);
+    assert_eq!(tasks[0].start.to_string(), "2020-11-30 19:30:00");
+}
+
+#[test]
+fn buys_for_the_planned_servings() {
+    let chicken = recipe(ROAST_CHICKEN, "chicken");
+    let bread = recipe(BANANA_BREAD, "bread");
+    let mut unscaled = recipe(ROAST_CHICKEN, "unscaled");
+    unscaled.recipe_name = Some("Chicken for Sunday".to_string());
+    unscaled.yields = None;
+
+    let monday = entry("2020-11-30", MealSlot::Dinner, &chicken, 8.0);
+    let tuesday = entry("2020-12-01", MealSlot::Snack, &bread, 2.0);
+    let sunday = entry("2020-12-06", MealSlot::Lunch, &unscaled, 4.0);
+    assert_eq!(planned_yield(&tuesday, &bread).unit, "loaves");
+    assert_eq!(planned_yield(&sunday, &unscaled).unit, "servings");
+
+    let items = plan_shopping_items(
+        &[
+            (&monday, &chicken),
+            (&tuesday, &bread),
+            (&sunday, &unscaled),
+        ],
+        &Aisles::bundled(),
+    )
+    .unwrap();
+    let amount = |name: &str| {
+        items
+            .iter()
+            .find(|item| item.ingredient_name == name)
+            .map(|item| item.amounts[0].to_string())
+    };
+    assert_eq!(amount("chicken"), Some("3".to_string()));
+    assert_eq!(amount("butter"), Some("1 cup".to_string()));
+    assert_eq!(amount("thyme"), Some("12 sprigs".to_string()));
+}
+
+#[test]
+fn validates_meal_plan_entries() {
+    assert_eq!(MealSlot::from_str("supper"), Ok(MealSlot::Dinner));
+    assert_eq!(MealSlot::from_str("brunch"), Err(()));
+
+    let invalid = MealPlanEntry {
+        servings: 0.0,
+        ..MealPlanEntry::default()
+    };
+    let fields: Vec<String> = invalid.validate().into_iter().map(|e| e.field).collect();
+    assert_eq!(fields, vec!["recipe_uuid", "servings"]);
+}
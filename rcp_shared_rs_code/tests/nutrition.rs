@@ -0,0 +1,84 @@
+use rcp_shared_rs_code::models::ingredient::{Amount, IngredientData};
+use rcp_shared_rs_code::models::unit::{Unit, VolumeUnit};
+use rcp_shared_rs_code::nutrition::{recipe_nutrition, NutrientTable, NutritionError};
+
+mod common;
+use common::{assert_close, orf_recipe, ROAST_CHICKEN};
+
+#[test]
+fn calculates_recipe_nutrition() {
+    let recipe = orf_recipe(ROAST_CHICKEN);
+    let nutrition = recipe_nutrition(&recipe, 0, &NutrientTable::bundled());
+
+    // 1 chicken of 1100 g and 1 lemon of 58 g
+    let grams: Vec<f64> = nutrition.ingredients.iter().map(|i| i.grams).collect();
+    assert_eq!(grams, vec![1100.0, 58.0]);
+    assert_eq!(nutrition.ingredients[1].usda_num, "09150");
+    assert_close(nutrition.total.energy_kcal, 11.0 * 215.0 + 0.58 * 29.0);
+    assert_close(nutrition.total.protein_g, 11.0 * 18.6 + 0.58 * 1.1);
+
+    assert_eq!(nutrition.servings, Some(4.0));
+    let per_serving = nutrition.per_serving.unwrap();
+    assert_close(per_serving.energy_kcal, nutrition.total.energy_kcal / 4.0);
+
+    assert_eq!(nutrition.missing.len(), 1);
+    assert_eq!(nutrition.missing[0].ingredient_name, "thyme");
+    assert_eq!(nutrition.missing[0].reason, "not in the nutrient table");
+}
+
+#[test]
+fn matches_ingredient_names() {
+    let table = NutrientTable::bundled();
+    let ndb_no = |name: &str| table.find(name).map(|food| food.ndb_no.as_str());
+
+    assert_eq!(ndb_no("Eggs"), Some("01123"));
+    assert_eq!(ndb_no("large free-range eggs"), Some("01123"));
+    assert_eq!(ndb_no("unsalted butter"), Some("01145"));
+    assert_eq!(ndb_no("juice of 1 lemon"), Some("09150"));
+    assert_eq!(ndb_no("lemon juice"), Some("09152"));
+    assert_eq!(ndb_no("thyme"), None);
+
+    // The override wins over the name
+    let ingredient = IngredientData {
+        ingredient_name: "butter".to_string(),
+        usda_num: Some("01145".to_string()),
+        ..IngredientData::new()
+    };
+    assert_eq!(table.lookup(&ingredient).unwrap().ndb_no, "01145");
+}
+
+#[test]
+fn reads_usda_csv() {
+    // Columns in another order, the "~" quoting of the USDA files and no aliases
+    let csv = "\
+Shrt_Desc,NDB_No,Energ_Kcal,Protein_(g),Lipid_Tot_(g),Carbohydrt_(g),GmWt_1,GmWt_Desc1
+~GARLIC,RAW~,~11215~,149,6.36,0.5,33.06,3,1 clove
+\"CHEESE,PARMESAN,GRATED\",01033,420,29.61,27.84,13.91,5,\"1 tbsp, grated\"
+";
+    let table = NutrientTable::from_csv(csv).unwrap();
+    assert_eq!(table.foods.len(), 2);
+
+    let garlic = table.find("garlic").unwrap();
+    assert_eq!(garlic.ndb_no, "11215");
+    assert_eq!(garlic.description, "GARLIC,RAW");
+    assert_eq!(garlic.nutrients.fiber_g, 0.0);
+    let cloves = Amount {
+        amount: 3.0,
+        unit: Unit::Other("cloves".to_string()),
+        ..Amount::new()
+    };
+    assert_eq!(garlic.grams(&cloves), Some(9.0));
+    let grated = Amount {
+        amount: 2.0,
+        unit: Unit::Volume(VolumeUnit::Tablespoon),
+        ..Amount::new()
+    };
+    let parmesan = table.find("grated parmesan cheese").unwrap();
+    assert_eq!(parmesan.ndb_no, "01033");
+    assert_eq!(parmesan.grams(&grated), Some(10.0));
+
+    assert_eq!(
+        NutrientTable::from_csv("NDB_No,Shrt_Desc\n").unwrap_err(),
+        NutritionError("Missing column Energ_Kcal".to_string())
+    );
+}
@@ -0,0 +1,105 @@
+use rcp_shared_rs_code::formats::orf::{from_orf_yaml, to_orf_yaml};
+use rcp_shared_rs_code::models::duration::Duration;
+use rcp_shared_rs_code::models::recipe::Recipe;
+use rcp_shared_rs_code::models::temperature_unit::TemperatureUnit;
+use rcp_shared_rs_code::models::unit::{Unit, VolumeUnit};
+
+mod common;
+use common::{BANANA_BREAD, COOKIES, ROAST_CHICKEN};
+
+const SAMPLES: [(&str, &str); 3] = [
+    ("banana bread", BANANA_BREAD),
+    ("cookies", COOKIES),
+    ("roast chicken", ROAST_CHICKEN),
+];
+
+#[test]
+fn samples_are_valid_recipes() {
+    for (name, yaml) in SAMPLES.iter() {
+        let mut recipe = from_orf_yaml(yaml).unwrap_or_else(|e| panic!("{}: {}", name, e));
+        assert_eq!(recipe.validate(), vec![], "{}", name);
+
+        // One amount per ingredient is also fine without yields
+        if recipe.yields.as_ref().map_or(0, Vec::len) == 1 {
+            recipe.yields = None;
+            assert_eq!(recipe.validate(), vec![], "{} without yields", name);
+        }
+    }
+}
+
+#[test]
+fn samples_round_trip() {
+    for (name, yaml) in SAMPLES.iter() {
+        let recipe = Recipe::from(from_orf_yaml(yaml).unwrap());
+        let reimported = Recipe::from(from_orf_yaml(&to_orf_yaml(&recipe)).unwrap());
+        assert_eq!(
+            serde_json::to_value(&reimported).unwrap(),
+            serde_json::to_value(&recipe).unwrap(),
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn reads_banana_bread() {
+    let recipe = from_orf_yaml(BANANA_BREAD).unwrap();
+
+    assert_eq!(recipe.recipe_name.as_deref(), Some("Banana Bread"));
+    assert_eq!(recipe.oven_time, Some(Duration::from_minutes(60.0)));
+    let temp = recipe.oven_temp.unwrap();
+    assert_eq!(
+        (temp.amount, temp.unit),
+        (350.0, TemperatureUnit::Fahrenheit)
+    );
+    assert_eq!(
+        recipe.notes.as_deref(),
+        Some("Keeps for three days wrapped in foil.\nFreezes well.")
+    );
+
+    let ingredients = recipe.ingredients.unwrap();
+    let butter = &ingredients[2];
+    assert_eq!(butter.ingredient.amounts[0].amount, 0.5);
+    assert_eq!(
+        butter.ingredient.amounts[0].unit,
+        Unit::Volume(VolumeUnit::Cup)
+    );
+    assert_eq!(butter.substitutions[0].ingredient_name, "margarine");
+
+    let bananas = &ingredients[3].ingredient;
+    assert_eq!(
+        (bananas.amounts[0].amount, bananas.amounts[0].amount_max),
+        (2.0, Some(3.0))
+    );
+    assert_eq!(bananas.amounts[0].unit, Unit::Count);
+    assert_eq!(bananas.processing, vec!["overripe", "mashed"]);
+
+    let haccp = recipe.steps.unwrap()[2].haccp.clone().unwrap();
+    assert_eq!(haccp.control_point, "Check the bread after 50 minutes.");
+    assert_eq!(haccp.critical_control_point, "");
+}
+
+#[test]
+fn reads_one_amount_per_yield() {
+    let recipe = from_orf_yaml(COOKIES).unwrap();
+
+    let yields = recipe.yields.unwrap();
+    assert_eq!(yields.len(), 2);
+    assert_eq!(
+        (yields[1].amount, yields[1].unit.as_str()),
+        (48.0, "cookies")
+    );
+    for ingredient in recipe.ingredients.unwrap() {
+        assert_eq!(ingredient.ingredient.amounts.len(), 2);
+    }
+}
+
+#[test]
+fn rejects_invalid_documents() {
+    assert!(from_orf_yaml("recipe_name: [unclosed").is_err());
+    assert!(from_orf_yaml("recipe_name: Toast\noven_fan: Turbo").is_err());
+    assert!(from_orf_yaml(
+        "recipe_name: Toast\ningredients:\n  - bread:\n      amounts:\n        - amount: lots\n"
+    )
+    .is_err());
+}
@@ -0,0 +1,147 @@
+use chrono::NaiveDate;
+use rcp_shared_rs_code::models::pantry_item::PantryItem;
+use rcp_shared_rs_code::models::recipe::Recipe;
+use rcp_shared_rs_code::models::unit::{MassUnit, Unit, VolumeUnit};
+use rcp_shared_rs_code::pantry::{cookable_recipes, deduct_recipe, PantryMatch};
+use std::str::FromStr;
+
+mod common;
+use common::{orf_recipe, BANANA_BREAD, ROAST_CHICKEN};
+
+fn date(date: &str) -> NaiveDate {
+    NaiveDate::from_str(date).unwrap()
+}
+
+fn item(name: &str, quantity: f64, unit: Unit, expiry_date: Option<&str>) -> PantryItem {
+    PantryItem {
+        ingredient_name: name.to_string(),
+        quantity,
+        unit,
+        expiry_date: expiry_date.map(date),
+        ..PantryItem::default()
+    }
+}
+
+fn recipes() -> Vec<Recipe> {
+    vec![orf_recipe(ROAST_CHICKEN), orf_recipe(BANANA_BREAD)]
+}
+
+#[test]
+fn ranks_cookable_recipes() {
+    let pantry = vec![
+        item("chicken", 1.0, Unit::Count, None),
+        item("lemons", 2.0, Unit::Count, Some("2020-12-05")),
+        // There is no telling how many cups are in a bag, so it has to do
+        item("flour", 1.0, Unit::Other("bag".to_string()), None),
+        item("baking soda", 100.0, Unit::Mass(MassUnit::Gram), None),
+        item("margarine", 1.0, Unit::Volume(VolumeUnit::Cup), None),
+        item("banana", 3.0, Unit::Count, Some("2020-12-02")),
+    ];
+
+    let cookable = cookable_recipes(&recipes(), &pantry, 0, date("2020-12-01"));
+    let names: Vec<&str> = cookable
+        .iter()
+        .filter_map(|c| c.recipe_name.as_deref())
+        .collect();
+    assert_eq!(names, vec!["Banana Bread"]);
+    assert_eq!(cookable[0].uses_up, Some(date("2020-12-02")));
+    assert_eq!(
+        cookable[0].matched[0].pantry_item, "flour",
+        "all-purpose flour is flour"
+    );
+    assert_eq!(
+        cookable[0].matched[2],
+        PantryMatch {
+            ingredient_name: "margarine".to_string(),
+            substitution_for: Some("butter".to_string()),
+            pantry_item: "margarine".to_string(),
+        }
+    );
+
+    let cookable = cookable_recipes(&recipes(), &pantry, 1, date("2020-12-01"));
+    let names: Vec<&str> = cookable
+        .iter()
+        .filter_map(|c| c.recipe_name.as_deref())
+        .collect();
+    assert_eq!(names, vec!["Banana Bread", "Roast Chicken"]);
+    assert_eq!(cookable[1].missing.len(), 1);
+    assert_eq!(cookable[1].missing[0].ingredient_name, "thyme");
+    assert_eq!(cookable[1].missing[0].reason, "not in the pantry");
+}
+
+#[test]
+fn leaves_out_expired_and_short_items() {
+    let pantry = vec![
+        item("chicken", 1.0, Unit::Count, Some("2020-11-30")),
+        item("lemon", 1.0, Unit::Count, None),
+        item("thyme", 10.0, Unit::Other("sprigs".to_string()), None),
+        item("butter", 250.0, Unit::Mass(MassUnit::Gram), None),
+        item("bananas", 1.0, Unit::Count, None),
+    ];
+
+    let cookable = cookable_recipes(&recipes(), &pantry, 10, date("2020-12-01"));
+    let chicken = cookable
+        .iter()
+        .find(|c| c.recipe_name.as_deref() == Some("Roast Chicken"))
+        .unwrap();
+    assert_eq!(chicken.missing.len(), 1);
+    assert_eq!(chicken.missing[0].ingredient_name, "chicken");
+
+    let bread = cookable
+        .iter()
+        .find(|c| c.recipe_name.as_deref() == Some("Banana Bread"))
+        .unwrap();
+    let bananas = bread
+        .missing
+        .iter()
+        .find(|m| m.ingredient_name == "bananas")
+        .unwrap();
+    assert_eq!(bananas.reason, "only 1 in the pantry");
+}
+
+#[test]
+fn deducts_from_what_expires_first() {
+    let bread = orf_recipe(BANANA_BREAD);
+    let pantry = vec![
+        item("flour", 1.0, Unit::Mass(MassUnit::Kilogram), None),
+        item("bananas", 5.0, Unit::Count, Some("2020-12-10")),
+        item("bananas", 2.0, Unit::Count, Some("2020-12-03")),
+        item("margarine", 1.0, Unit::Volume(VolumeUnit::Cup), None),
+    ];
+
+    let deduction = deduct_recipe(&bread, &pantry, date("2020-12-01"));
+    let updated: Vec<(&str, f64, Option<NaiveDate>)> = deduction
+        .updated
+        .iter()
+        .map(|item| {
+            (
+                item.ingredient_name.as_str(),
+                item.quantity,
+                item.expiry_date,
+            )
+        })
+        .collect();
+    assert_eq!(
+        updated,
+        vec![
+            ("bananas", 0.0, Some(date("2020-12-03"))),
+            ("margarine", 0.5, None),
+        ]
+    );
+
+    let not_deducted: Vec<(&str, &str)> = deduction
+        .not_deducted
+        .iter()
+        .map(|m| (m.ingredient_name.as_str(), m.reason.as_str()))
+        .collect();
+    assert_eq!(
+        not_deducted,
+        vec![
+            (
+                "all-purpose flour",
+                "can't convert \"2 cups\" to the pantry unit \"kg\""
+            ),
+            ("baking soda", "not in the pantry"),
+        ]
+    );
+}
@@ -0,0 +1,88 @@
+use rcp_shared_rs_code::formats::orf::from_orf_yaml;
+use rcp_shared_rs_code::formats::pdf::to_cookbook_pdf;
+use rcp_shared_rs_code::models::recipe::Recipe;
+
+const SAMPLES: [&str; 3] = [
+    include_str!("data/orf/banana_bread.yml"),
+    include_str!("data/orf/chocolate_chip_cookies.yml"),
+    include_str!("data/orf/roast_chicken.yml"),
+];
+
+fn samples() -> Vec<Recipe> {
+    SAMPLES
+        .iter()
+        .map(|yaml| Recipe::from(from_orf_yaml(yaml).unwrap()))
+        .collect()
+}
+
+fn contains(pdf: &[u8], text: &[u8]) -> bool {
+    pdf.windows(text.len()).any(|window| window == text)
+}
+
+fn find(pdf: &[u8], text: &[u8]) -> usize {
+    pdf.windows(text.len())
+        .position(|window| window == text)
+        .unwrap_or_else(|| panic!("{} not found", String::from_utf8_lossy(text)))
+}
+
+#[test]
+fn writes_a_valid_pdf() {
+    let pdf = to_cookbook_pdf("Kitchen Favourites", &samples());
+    assert!(pdf.starts_with(b"%PDF-1.4\n"));
+    assert!(pdf.ends_with(b"%%EOF\n"));
+
+    // Every cross-reference entry points at its object
+    let text = String::from_utf8_lossy(&pdf);
+    let startxref: usize = text
+        .rsplit("startxref\n")
+        .next()
+        .and_then(|rest| rest.lines().next())
+        .and_then(|offset| offset.parse().ok())
+        .unwrap();
+    let xref = String::from_utf8_lossy(&pdf[startxref..]);
+    let mut lines = xref.lines().skip(1);
+    let count: usize = lines.next().unwrap()[2..].parse().unwrap();
+    for (number, entry) in lines.skip(1).take(count - 1).enumerate() {
+        let offset: usize = entry[..10].parse().unwrap();
+        let header = format!("{} 0 obj\n", number + 1);
+        assert!(pdf[offset..].starts_with(header.as_bytes()), "{}", header);
+    }
+}
+
+#[test]
+fn lays_out_contents_recipes_and_index() {
+    let pdf = to_cookbook_pdf("Kitchen Favourites", &samples());
+
+    // Title page, contents, one page per recipe and the index
+    assert!(contains(&pdf, b"/Count 6 "));
+    assert!(contains(&pdf, b"/Title (Kitchen Favourites)"));
+
+    let contents = find(&pdf, b"(Contents)");
+    let roast_chicken = find(&pdf, b"(Roast Chicken)");
+    let index = find(&pdf, b"(Index of ingredients)");
+    assert!(contents < roast_chicken && roast_chicken < index);
+    assert!(contains(&pdf, b"(chicken, 5)"));
+    assert!(contains(&pdf, b"(butter, 3, 4)"));
+
+    // HACCP control points, with the degree sign in WinAnsiEncoding
+    assert!(contains(&pdf, b"(Critical control point: Cook until the"));
+    assert!(contains(&pdf, b"74 \xb0C"));
+}
+
+#[test]
+fn continues_long_contents_on_the_next_page() {
+    let recipes: Vec<Recipe> = (1..=40)
+        .map(|n| Recipe {
+            recipe_name: Some(format!("Recipe {}", n)),
+            ..Default::default()
+        })
+        .collect();
+    let pdf = to_cookbook_pdf("Many recipes", &recipes);
+
+    // Two pages of contents, so the first recipe is on page 4
+    assert!(contains(&pdf, b"/Count 43 "));
+    let first = find(&pdf, b"(Recipe 1)");
+    assert!(pdf[first..].starts_with(b"(Recipe 1) Tj"));
+    let number = find(&pdf[first..], b" Td (4) Tj");
+    assert!(number < 500);
+}
@@ -0,0 +1,109 @@
+use rcp_shared_rs_code::formats::orf::from_orf_yaml;
+use rcp_shared_rs_code::formats::schema_org::{from_schema_org_json, to_schema_org_json};
+use rcp_shared_rs_code::models::duration::Duration;
+use rcp_shared_rs_code::models::recipe::Recipe;
+use rcp_shared_rs_code::models::unit::{MassUnit, Unit};
+
+// As embedded by a typical recipe site, without any rcp extension properties
+const SITE_JSON_LD: &str = r#"{
+  "@context": "https://schema.org",
+  "@graph": [
+    { "@type": "WebSite", "name": "Example Recipes" },
+    {
+      "@type": "Recipe",
+      "name": "Weeknight Pancakes",
+      "description": "Fluffy pancakes in 20 minutes.",
+      "author": { "@type": "Person", "name": "Sam Cook" },
+      "prepTime": "PT5M",
+      "cookTime": "PT15M",
+      "totalTime": "PT20M",
+      "recipeYield": ["4", "4 servings"],
+      "recipeIngredient": [
+        "200g flour",
+        "2 large eggs",
+        "1 1/4 cups milk",
+        "a pinch of salt"
+      ],
+      "recipeInstructions": [
+        {
+          "@type": "HowToSection",
+          "name": "Batter",
+          "itemListElement": [
+            { "@type": "HowToStep", "text": "Whisk everything into a smooth batter." }
+          ]
+        },
+        { "@type": "HowToStep", "text": "Fry ladlefuls in a hot pan." }
+      ]
+    }
+  ]
+}"#;
+
+#[test]
+fn reads_site_json_ld() {
+    let recipe = from_schema_org_json(SITE_JSON_LD).unwrap();
+
+    assert_eq!(recipe.recipe_name.as_deref(), Some("Weeknight Pancakes"));
+    assert_eq!(recipe.source_authors, Some(vec!["Sam Cook".to_string()]));
+    assert_eq!(recipe.total_time, Some(Duration::from_minutes(20.0)));
+
+    let yields = recipe.yields.clone().unwrap();
+    assert_eq!(yields.len(), 1);
+    assert_eq!(
+        (yields[0].amount, yields[0].unit.as_str()),
+        (4.0, "servings")
+    );
+
+    let ingredients = recipe.ingredients.clone().unwrap();
+    assert_eq!(ingredients.len(), 4);
+    assert_eq!(ingredients[0].ingredient.ingredient_name, "flour");
+    assert_eq!(
+        ingredients[0].ingredient.amounts[0].unit,
+        Unit::Mass(MassUnit::Gram)
+    );
+
+    let steps = recipe.steps.clone().unwrap();
+    assert_eq!(steps.len(), 2);
+    assert_eq!(steps[1].step, "Fry ladlefuls in a hot pan.");
+
+    assert_eq!(recipe.validate(), vec![]);
+}
+
+#[test]
+fn round_trips_through_extension_properties() {
+    let yaml = include_str!("data/orf/banana_bread.yml");
+    let recipe = Recipe::from(from_orf_yaml(yaml).unwrap());
+
+    let json = to_schema_org_json(&recipe);
+    let reimported = Recipe::from(from_schema_org_json(&json).unwrap());
+    assert_eq!(to_schema_org_json(&reimported), json);
+
+    // Substitutions and HACCP values only survive through the extension properties
+    let ingredients = reimported.ingredients.unwrap();
+    assert_eq!(ingredients[2].substitutions[0].ingredient_name, "margarine");
+    let steps = reimported.steps.unwrap();
+    assert_eq!(
+        steps[2].haccp.as_ref().unwrap().control_point,
+        "Check the bread after 50 minutes."
+    );
+}
+
+#[test]
+fn writes_plain_schema_org_properties() {
+    let yaml = include_str!("data/orf/banana_bread.yml");
+    let recipe = Recipe::from(from_orf_yaml(yaml).unwrap());
+    let json: serde_json::Value = serde_json::from_str(&to_schema_org_json(&recipe)).unwrap();
+
+    assert_eq!(json["@type"], "Recipe");
+    assert_eq!(json["recipeYield"], "1 loaves");
+    assert_eq!(
+        json["recipeIngredient"][2],
+        "1/2 cup butter, softened (or 1/2 cup margarine)"
+    );
+    assert_eq!(json["recipeInstructions"][0]["@type"], "HowToStep");
+}
+
+#[test]
+fn rejects_documents_without_recipe() {
+    assert!(from_schema_org_json(r#"{"@type": "WebSite"}"#).is_err());
+    assert!(from_schema_org_json("not json").is_err());
+}
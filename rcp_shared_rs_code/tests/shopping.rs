@@ -0,0 +1,105 @@
+use rcp_shared_rs_code::models::ingredient::Amount;
+use rcp_shared_rs_code::models::r#yield::Yield;
+use rcp_shared_rs_code::models::unit::{MassUnit, Unit};
+use rcp_shared_rs_code::shopping::{shopping_items, Aisles, ShoppingError};
+
+mod common;
+use common::{orf_recipe, BANANA_BREAD, COOKIES, ROAST_CHICKEN};
+
+fn target(amount: f64, unit: &str) -> Yield {
+    Yield {
+        amount,
+        unit: unit.to_string(),
+    }
+}
+
+#[test]
+fn adds_up_ingredients_across_recipes() {
+    let cookies = orf_recipe(COOKIES);
+    let mut shortbread = cookies.clone();
+    shortbread.recipe_name = Some("Shortbread".to_string());
+    let butter = &mut shortbread.ingredients.as_mut().unwrap()[0].ingredient;
+    for amount in butter.amounts.iter_mut() {
+        *amount = Amount {
+            amount: 0.5,
+            amount_max: None,
+            unit: Unit::Mass(MassUnit::Kilogram),
+        };
+    }
+    let banana_bread = orf_recipe(BANANA_BREAD);
+
+    let (cookies_yield, bread_yield) = (target(24.0, "cookies"), target(2.0, "loaves"));
+    let items = shopping_items(
+        &[
+            (&cookies, &cookies_yield),
+            (&shortbread, &cookies_yield),
+            (&banana_bread, &bread_yield),
+        ],
+        &Aisles::bundled(),
+    )
+    .unwrap();
+
+    let list: Vec<(&str, &str, Vec<String>)> = items
+        .iter()
+        .map(|item| {
+            (
+                item.aisle.as_str(),
+                item.ingredient_name.as_str(),
+                item.amounts.iter().map(|a| a.to_string()).collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        list,
+        vec![
+            ("Produce", "bananas", vec!["4-6".to_string()]),
+            (
+                "Dairy & Eggs",
+                "butter",
+                vec!["725 g".to_string(), "1 cup".to_string()]
+            ),
+            ("Dairy & Eggs", "egg", vec!["4".to_string()]),
+            ("Baking", "all-purpose flour", vec!["4 cups".to_string()]),
+            ("Baking", "baking soda", vec!["2 tsp".to_string()]),
+            ("Baking", "brown sugar", vec!["400 g".to_string()]),
+            ("Baking", "chocolate chips", vec!["680 g".to_string()]),
+            ("Baking", "flour", vec!["560 g".to_string()]),
+        ]
+    );
+    assert_eq!(
+        items[1].recipes,
+        vec!["Chocolate Chip Cookies", "Shortbread", "Banana Bread"]
+    );
+    assert!(items.iter().all(|item| !item.checked));
+}
+
+#[test]
+fn sorts_ingredients_into_aisles() {
+    let aisles = Aisles::bundled();
+
+    assert_eq!(aisles.aisle_of("unsalted butter"), "Dairy & Eggs");
+    assert_eq!(aisles.aisle_of("Peanut Butter"), "Condiments & Spreads");
+    assert_eq!(aisles.aisle_of("garlic cloves"), "Produce");
+    assert_eq!(aisles.aisle_of("ground beef"), "Meat & Fish");
+    assert_eq!(aisles.aisle_of("chicken stock"), "Canned & Jarred");
+    assert_eq!(aisles.aisle_of("lemon juice"), "Produce");
+    assert_eq!(aisles.aisle_of("dragon fruit"), "Other");
+}
+
+#[test]
+fn rejects_recipes_without_the_target_yield() {
+    let chicken = orf_recipe(ROAST_CHICKEN);
+    let result = shopping_items(&[(&chicken, &target(12.0, "cookies"))], &Aisles::bundled());
+    assert_eq!(
+        result.err(),
+        Some(ShoppingError(
+            "Roast Chicken has no yield in cookies to scale from".to_string()
+        ))
+    );
+
+    let duplicate = "- aisle: Produce\n- aisle: Produce\n";
+    assert_eq!(
+        Aisles::from_yaml(duplicate).err(),
+        Some(ShoppingError("Aisle Produce is listed twice".to_string()))
+    );
+}
@@ -4,8 +4,18 @@ use mongodb::bson::{doc, document::Document, oid::ObjectId, Bson};
 use mongodb::results::{DeleteResult, InsertOneResult, UpdateResult};
 use mongodb::{options::ClientOptions, Client, Collection};
 use std::str::FromStr;
-
+use std::sync::Arc;
+
+use rcp_shared_rs_code::diets::{self, DietTaxonomy};
+use chrono::NaiveDate;
+use rcp_shared_rs_code::catalogue::LinkProposal;
+use rcp_shared_rs_code::models::catalogue_entry::CatalogueEntry;
+use rcp_shared_rs_code::models::diet::Diet;
+use rcp_shared_rs_code::models::meal_plan::MealPlanEntry;
+use rcp_shared_rs_code::models::pantry_item::PantryItem;
+use rcp_shared_rs_code::models::price_item::PriceItem;
 use rcp_shared_rs_code::models::recipe::Recipe;
+use rcp_shared_rs_code::models::shopping_list::ShoppingList;
 use rcp_shared_rs_code::models::step::Step;
 use rcp_shared_rs_code::models::temperature::Temperature;
 use rcp_shared_rs_code::models::{book_source::BookSource, ingredient::Ingredient};
@@ -17,6 +27,11 @@ use rcp_shared_rs_code::models::{
 
 const DB_NAME: &str = "rcp_db";
 const RECIPE_COLL: &str = "recipe";
+const PRICE_COLL: &str = "price";
+const SHOPPING_LIST_COLL: &str = "shopping_list";
+const MEAL_PLAN_COLL: &str = "meal_plan";
+const PANTRY_COLL: &str = "pantry";
+const CATALOGUE_COLL: &str = "catalogue";
 
 const RECIPE_UUID: &str = "_id";
 const RECIPE_NAME: &str = "recipe_name";
@@ -30,29 +45,127 @@ const SOURCE_URL: &str = "source_url";
 const INGREDIENTS: &str = "ingredients";
 const STEPS: &str = "steps";
 const YIELDS: &str = "yields";
+// Derived from the ingredients when a recipe is stored, for filtering by diet
+const DIETS: &str = "diets";
+
+const PRICE_UUID: &str = "_id";
+// Stored as the document id instead
+const PRICE_UUID_FIELD: &str = "price_uuid";
+
+const SHOPPING_LIST_UUID: &str = "_id";
+// Stored as the document id instead
+const SHOPPING_LIST_UUID_FIELD: &str = "shopping_list_uuid";
+const SHOPPING_ITEMS: &str = "items";
+
+const MEAL_PLAN_UUID: &str = "_id";
+// Stored as the document id instead
+const MEAL_PLAN_UUID_FIELD: &str = "meal_plan_uuid";
+// Stored as "2020-11-30", so dates compare as strings
+const MEAL_DATE: &str = "date";
+
+const PANTRY_UUID: &str = "_id";
+// Stored as the document id instead
+const PANTRY_UUID_FIELD: &str = "pantry_uuid";
+
+const CATALOGUE_UUID: &str = "_id";
+// Stored as the document id instead
+const CATALOGUE_UUID_FIELD: &str = "catalogue_uuid";
 
 #[derive(Clone, Debug)]
 pub struct DB {
     pub client: Client,
+    pub diets: Arc<DietTaxonomy>,
 }
 
 impl DB {
-    pub async fn init() -> Result<Self> {
+    pub async fn init(diets: Arc<DietTaxonomy>) -> Result<Self> {
         let mut client_options = ClientOptions::parse("mongodb://127.0.0.1:27017").await?;
         client_options.app_name = Some(DB_NAME.to_string());
 
-        Ok(Self {
+        let db = Self {
             client: Client::with_options(client_options)?,
-        })
+            diets,
+        };
+        db.create_indexes().await?;
+        Ok(db)
+    }
+
+    async fn create_indexes(&self) -> Result<()> {
+        self.client
+            .database(DB_NAME)
+            .run_command(
+                doc! {
+                    "createIndexes": RECIPE_COLL,
+                    "indexes": [{ "key": { DIETS: 1 }, "name": DIETS }],
+                },
+                None,
+            )
+            .await
+            .map_err(MongoQueryError)?;
+        Ok(())
     }
 
-    pub async fn fetch_recipes(&self) -> Result<Vec<Recipe>> {
+    // Stores the diets of every recipe again, for recipes stored before the taxonomy changed.
+    // Recipes that can't be read or updated are logged and skipped. Returns (updated, failed).
+    pub async fn update_recipe_diets(&self) -> Result<(usize, usize)> {
         let mut cursor = self
             .get_recipe_collection()
             .find(None, None)
             .await
             .map_err(MongoQueryError)?;
 
+        let (mut updated, mut failed) = (0, 0);
+        while let Some(doc) = cursor.next().await {
+            let doc = match doc {
+                Ok(doc) => doc,
+                Err(e) => {
+                    eprintln!("could not read a recipe: {}", e);
+                    failed += 1;
+                    continue;
+                }
+            };
+            let id = doc
+                .get_object_id(RECIPE_UUID)
+                .map_or_else(|_| "without id".to_string(), ObjectId::to_hex);
+            match self.update_recipe_diet(doc).await {
+                Ok(()) => updated += 1,
+                Err(e) => {
+                    eprintln!("could not update the diets of recipe {}: {}", id, e);
+                    failed += 1;
+                }
+            }
+        }
+        Ok((updated, failed))
+    }
+
+    async fn update_recipe_diet(&self, doc: Document) -> Result<()> {
+        let oid = doc.get_object_id(RECIPE_UUID)?.clone();
+        let recipe = self.doc_to_recipe(doc)?;
+        self.get_recipe_collection()
+            .update_one(
+                doc! { RECIPE_UUID: oid },
+                doc! { "$set": { DIETS: self.diets_bson(&recipe)? } },
+                None,
+            )
+            .await
+            .map_err(MongoQueryError)?;
+        Ok(())
+    }
+
+    // All recipes, or only those suitable for `diet`
+    pub async fn fetch_recipes(&self, diet: Option<Diet>) -> Result<Vec<Recipe>> {
+        let filter = match diet {
+            Some(diet) => Some(doc! {
+                DIETS: bson::to_bson(&diet)?,
+            }),
+            None => None,
+        };
+        let mut cursor = self
+            .get_recipe_collection()
+            .find(filter, None)
+            .await
+            .map_err(MongoQueryError)?;
+
         let mut result: Vec<Recipe> = Vec::new();
         while let Some(doc) = cursor.next().await {
             result.push(self.doc_to_recipe(doc?)?);
@@ -80,7 +193,7 @@ impl DB {
     }
 
     pub async fn create_recipe(&self, entry: &RecipeRequest) -> Result<String> {
-        let doc = self.doc_from_recipe_request(&entry);
+        let doc = self.doc_from_recipe_request(&entry)?;
 
         let _result: InsertOneResult = self
             .get_recipe_collection()
@@ -104,14 +217,18 @@ impl DB {
             RECIPE_UUID: oid,
         };
 
-        let doc = self.doc_from_recipe_request(&entry);
+        // The whole recipe is replaced, so its diets are derived again
+        let doc = self.doc_from_recipe_request(&entry)?;
 
-        let _result: UpdateResult = self
+        let result: UpdateResult = self
             .get_recipe_collection()
-            .update_one(query, doc, None)
+            .replace_one(query, doc, None)
             .await
             .map_err(MongoQueryError)?;
 
+        if result.matched_count == 0 {
+            return Err(InvalidIDError(id.to_string()));
+        }
         Ok(())
     }
 
@@ -129,15 +246,440 @@ impl DB {
         Ok(())
     }
 
+    pub async fn fetch_prices(&self) -> Result<Vec<PriceItem>> {
+        let mut cursor = self
+            .get_price_collection()
+            .find(None, None)
+            .await
+            .map_err(MongoQueryError)?;
+
+        let mut result: Vec<PriceItem> = Vec::new();
+        while let Some(doc) = cursor.next().await {
+            result.push(self.doc_to_price_item(doc?)?);
+        }
+        Ok(result)
+    }
+
+    pub async fn create_price(&self, entry: &PriceItem) -> Result<String> {
+        let result: InsertOneResult = self
+            .get_price_collection()
+            .insert_one(self.doc_from_price_item(entry), None)
+            .await
+            .map_err(MongoQueryError)?;
+
+        match result.inserted_id {
+            Bson::ObjectId(oid) => Ok(oid.to_hex()),
+            _ => panic!("_id is not an ObjectId!"),
+        }
+    }
+
+    pub async fn edit_price(&self, id: &str, entry: &PriceItem) -> Result<()> {
+        let oid: ObjectId = ObjectId::with_string(id).map_err(|_| InvalidIDError(id.to_owned()))?;
+        let result: UpdateResult = self
+            .get_price_collection()
+            .update_one(
+                doc! { PRICE_UUID: oid },
+                doc! { "$set": self.doc_from_price_item(entry) },
+                None,
+            )
+            .await
+            .map_err(MongoQueryError)?;
+
+        if result.matched_count == 0 {
+            return Err(InvalidIDError(id.to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn delete_price(&self, id: &str) -> Result<()> {
+        let oid: ObjectId = ObjectId::with_string(id).map_err(|_| InvalidIDError(id.to_owned()))?;
+        let _result: DeleteResult = self
+            .get_price_collection()
+            .delete_one(doc! { PRICE_UUID: oid }, None)
+            .await
+            .map_err(MongoQueryError)?;
+        Ok(())
+    }
+
+    pub async fn fetch_shopping_lists(&self) -> Result<Vec<ShoppingList>> {
+        let mut cursor = self
+            .get_shopping_list_collection()
+            .find(None, None)
+            .await
+            .map_err(MongoQueryError)?;
+
+        let mut result: Vec<ShoppingList> = Vec::new();
+        while let Some(doc) = cursor.next().await {
+            result.push(self.doc_to_shopping_list(doc?)?);
+        }
+        Ok(result)
+    }
+
+    pub async fn fetch_shopping_list(&self, id: &str) -> Result<ShoppingList> {
+        let oid: ObjectId = ObjectId::with_string(id).map_err(|_| InvalidIDError(id.to_owned()))?;
+        let doc = self
+            .get_shopping_list_collection()
+            .find_one(doc! { SHOPPING_LIST_UUID: oid }, None)
+            .await
+            .map_err(MongoQueryError)?;
+
+        match doc {
+            Some(doc) => self.doc_to_shopping_list(doc),
+            None => Err(InvalidIDError(id.to_string())),
+        }
+    }
+
+    pub async fn create_shopping_list(&self, entry: &ShoppingList) -> Result<String> {
+        let mut doc = bson::to_document(entry).unwrap();
+        doc.remove(SHOPPING_LIST_UUID_FIELD);
+
+        let result: InsertOneResult = self
+            .get_shopping_list_collection()
+            .insert_one(doc, None)
+            .await
+            .map_err(MongoQueryError)?;
+
+        match result.inserted_id {
+            Bson::ObjectId(oid) => Ok(oid.to_hex()),
+            _ => panic!("_id is not an ObjectId!"),
+        }
+    }
+
+    // Checks off the item at `idx` of the list, or unchecks it
+    pub async fn check_shopping_item(&self, id: &str, idx: usize, checked: bool) -> Result<()> {
+        let oid: ObjectId = ObjectId::with_string(id).map_err(|_| InvalidIDError(id.to_owned()))?;
+        let item = format!("{}.{}", SHOPPING_ITEMS, idx);
+        let item_checked = format!("{}.checked", item);
+        let result: UpdateResult = self
+            .get_shopping_list_collection()
+            .update_one(
+                doc! { SHOPPING_LIST_UUID: oid, item.as_str(): { "$exists": true } },
+                doc! { "$set": { item_checked: checked } },
+                None,
+            )
+            .await
+            .map_err(MongoQueryError)?;
+
+        if result.matched_count == 0 {
+            return Err(InvalidIDError(format!("{}/{}", id, item)));
+        }
+        Ok(())
+    }
+
+    pub async fn delete_shopping_list(&self, id: &str) -> Result<()> {
+        let oid: ObjectId = ObjectId::with_string(id).map_err(|_| InvalidIDError(id.to_owned()))?;
+        let _result: DeleteResult = self
+            .get_shopping_list_collection()
+            .delete_one(doc! { SHOPPING_LIST_UUID: oid }, None)
+            .await
+            .map_err(MongoQueryError)?;
+        Ok(())
+    }
+
+    // The planned meals from `from` to `to`, both included, by date and meal
+    pub async fn fetch_meal_plan(
+        &self,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<MealPlanEntry>> {
+        let mut range = Document::new();
+        if let Some(from) = from {
+            range.insert("$gte", from.to_string());
+        }
+        if let Some(to) = to {
+            range.insert("$lte", to.to_string());
+        }
+        let filter = if range.is_empty() {
+            None
+        } else {
+            Some(doc! { MEAL_DATE: range })
+        };
+        let mut cursor = self
+            .get_meal_plan_collection()
+            .find(filter, None)
+            .await
+            .map_err(MongoQueryError)?;
+
+        let mut result: Vec<MealPlanEntry> = Vec::new();
+        while let Some(doc) = cursor.next().await {
+            result.push(self.doc_to_meal_plan_entry(doc?)?);
+        }
+        result.sort_by(|a, b| (a.date, a.meal_slot).cmp(&(b.date, b.meal_slot)));
+        Ok(result)
+    }
+
+    pub async fn create_meal_plan_entry(&self, entry: &MealPlanEntry) -> Result<String> {
+        let result: InsertOneResult = self
+            .get_meal_plan_collection()
+            .insert_one(self.doc_from_meal_plan_entry(entry), None)
+            .await
+            .map_err(MongoQueryError)?;
+
+        match result.inserted_id {
+            Bson::ObjectId(oid) => Ok(oid.to_hex()),
+            _ => panic!("_id is not an ObjectId!"),
+        }
+    }
+
+    pub async fn edit_meal_plan_entry(&self, id: &str, entry: &MealPlanEntry) -> Result<()> {
+        let oid: ObjectId = ObjectId::with_string(id).map_err(|_| InvalidIDError(id.to_owned()))?;
+        let result: UpdateResult = self
+            .get_meal_plan_collection()
+            .update_one(
+                doc! { MEAL_PLAN_UUID: oid },
+                doc! { "$set": self.doc_from_meal_plan_entry(entry) },
+                None,
+            )
+            .await
+            .map_err(MongoQueryError)?;
+
+        if result.matched_count == 0 {
+            return Err(InvalidIDError(id.to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn delete_meal_plan_entry(&self, id: &str) -> Result<()> {
+        let oid: ObjectId = ObjectId::with_string(id).map_err(|_| InvalidIDError(id.to_owned()))?;
+        let _result: DeleteResult = self
+            .get_meal_plan_collection()
+            .delete_one(doc! { MEAL_PLAN_UUID: oid }, None)
+            .await
+            .map_err(MongoQueryError)?;
+        Ok(())
+    }
+
+    pub async fn fetch_catalogue(&self) -> Result<Vec<CatalogueEntry>> {
+        let mut cursor = self
+            .get_catalogue_collection()
+            .find(None, None)
+            .await
+            .map_err(MongoQueryError)?;
+
+        let mut result: Vec<CatalogueEntry> = Vec::new();
+        while let Some(doc) = cursor.next().await {
+            result.push(self.doc_to_catalogue_entry(doc?)?);
+        }
+        Ok(result)
+    }
+
+    pub async fn create_catalogue_entry(&self, entry: &CatalogueEntry) -> Result<String> {
+        let result: InsertOneResult = self
+            .get_catalogue_collection()
+            .insert_one(self.doc_from_catalogue_entry(entry), None)
+            .await
+            .map_err(MongoQueryError)?;
+
+        match result.inserted_id {
+            Bson::ObjectId(oid) => Ok(oid.to_hex()),
+            _ => panic!("_id is not an ObjectId!"),
+        }
+    }
+
+    pub async fn edit_catalogue_entry(&self, id: &str, entry: &CatalogueEntry) -> Result<()> {
+        let oid: ObjectId = ObjectId::with_string(id).map_err(|_| InvalidIDError(id.to_owned()))?;
+        let result: UpdateResult = self
+            .get_catalogue_collection()
+            .update_one(
+                doc! { CATALOGUE_UUID: oid },
+                doc! { "$set": self.doc_from_catalogue_entry(entry) },
+                None,
+            )
+            .await
+            .map_err(MongoQueryError)?;
+
+        if result.matched_count == 0 {
+            return Err(InvalidIDError(id.to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn delete_catalogue_entry(&self, id: &str) -> Result<()> {
+        let oid: ObjectId = ObjectId::with_string(id).map_err(|_| InvalidIDError(id.to_owned()))?;
+        let _result: DeleteResult = self
+            .get_catalogue_collection()
+            .delete_one(doc! { CATALOGUE_UUID: oid }, None)
+            .await
+            .map_err(MongoQueryError)?;
+        Ok(())
+    }
+
+    // Sets the catalogue_uuid of the ingredients and substitutions of a recipe, leaving the rest of it alone.
+    // The links have to point at existing ingredients, see catalogue::linked_ingredient
+    pub async fn link_ingredients(&self, recipe_id: &str, links: &[LinkProposal]) -> Result<()> {
+        let oid: ObjectId =
+            ObjectId::with_string(recipe_id).map_err(|_| InvalidIDError(recipe_id.to_owned()))?;
+        let mut set = Document::new();
+        for link in links {
+            let field = match link.substitution_idx {
+                Some(sub) => format!(
+                    "{}.{}.substitutions.{}.{}",
+                    INGREDIENTS, link.ingredient_idx, sub, CATALOGUE_UUID_FIELD
+                ),
+                None => format!(
+                    "{}.{}.ingredient.{}",
+                    INGREDIENTS, link.ingredient_idx, CATALOGUE_UUID_FIELD
+                ),
+            };
+            set.insert(field, link.catalogue_uuid.clone());
+        }
+        let result: UpdateResult = self
+            .get_recipe_collection()
+            .update_one(doc! { RECIPE_UUID: oid }, doc! { "$set": set }, None)
+            .await
+            .map_err(MongoQueryError)?;
+
+        if result.matched_count == 0 {
+            return Err(InvalidIDError(recipe_id.to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn fetch_pantry(&self) -> Result<Vec<PantryItem>> {
+        let mut cursor = self
+            .get_pantry_collection()
+            .find(None, None)
+            .await
+            .map_err(MongoQueryError)?;
+
+        let mut result: Vec<PantryItem> = Vec::new();
+        while let Some(doc) = cursor.next().await {
+            result.push(self.doc_to_pantry_item(doc?)?);
+        }
+        Ok(result)
+    }
+
+    pub async fn create_pantry_item(&self, item: &PantryItem) -> Result<String> {
+        let result: InsertOneResult = self
+            .get_pantry_collection()
+            .insert_one(self.doc_from_pantry_item(item), None)
+            .await
+            .map_err(MongoQueryError)?;
+
+        match result.inserted_id {
+            Bson::ObjectId(oid) => Ok(oid.to_hex()),
+            _ => panic!("_id is not an ObjectId!"),
+        }
+    }
+
+    pub async fn edit_pantry_item(&self, id: &str, item: &PantryItem) -> Result<()> {
+        let oid: ObjectId = ObjectId::with_string(id).map_err(|_| InvalidIDError(id.to_owned()))?;
+        let result: UpdateResult = self
+            .get_pantry_collection()
+            .update_one(
+                doc! { PANTRY_UUID: oid },
+                doc! { "$set": self.doc_from_pantry_item(item) },
+                None,
+            )
+            .await
+            .map_err(MongoQueryError)?;
+
+        if result.matched_count == 0 {
+            return Err(InvalidIDError(id.to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn delete_pantry_item(&self, id: &str) -> Result<()> {
+        let oid: ObjectId = ObjectId::with_string(id).map_err(|_| InvalidIDError(id.to_owned()))?;
+        let _result: DeleteResult = self
+            .get_pantry_collection()
+            .delete_one(doc! { PANTRY_UUID: oid }, None)
+            .await
+            .map_err(MongoQueryError)?;
+        Ok(())
+    }
+
     fn get_recipe_collection(&self) -> Collection {
         self.client.database(DB_NAME).collection(RECIPE_COLL)
     }
 
-    fn doc_from_recipe_request(&self, recipe_request: &RecipeRequest) -> Document {
+    fn get_price_collection(&self) -> Collection {
+        self.client.database(DB_NAME).collection(PRICE_COLL)
+    }
+
+    fn get_shopping_list_collection(&self) -> Collection {
+        self.client.database(DB_NAME).collection(SHOPPING_LIST_COLL)
+    }
+
+    fn get_meal_plan_collection(&self) -> Collection {
+        self.client.database(DB_NAME).collection(MEAL_PLAN_COLL)
+    }
+
+    fn get_catalogue_collection(&self) -> Collection {
+        self.client.database(DB_NAME).collection(CATALOGUE_COLL)
+    }
+
+    fn doc_from_catalogue_entry(&self, entry: &CatalogueEntry) -> Document {
+        let mut doc = bson::to_document(entry).unwrap();
+        doc.remove(CATALOGUE_UUID_FIELD);
+        doc
+    }
+
+    fn doc_to_catalogue_entry(&self, doc: Document) -> Result<CatalogueEntry> {
+        let catalogue_uuid = doc.get_object_id(CATALOGUE_UUID)?.to_hex();
+        let mut entry: CatalogueEntry = bson::from_document(doc)?;
+        entry.catalogue_uuid = catalogue_uuid;
+        Ok(entry)
+    }
+
+    fn get_pantry_collection(&self) -> Collection {
+        self.client.database(DB_NAME).collection(PANTRY_COLL)
+    }
+
+    fn doc_from_pantry_item(&self, item: &PantryItem) -> Document {
+        let mut doc = bson::to_document(item).unwrap();
+        doc.remove(PANTRY_UUID_FIELD);
+        doc
+    }
+
+    fn doc_to_pantry_item(&self, doc: Document) -> Result<PantryItem> {
+        let pantry_uuid = doc.get_object_id(PANTRY_UUID)?.to_hex();
+        let mut item: PantryItem = bson::from_document(doc)?;
+        item.pantry_uuid = pantry_uuid;
+        Ok(item)
+    }
+
+    fn doc_from_meal_plan_entry(&self, entry: &MealPlanEntry) -> Document {
+        let mut doc = bson::to_document(entry).unwrap();
+        doc.remove(MEAL_PLAN_UUID_FIELD);
+        doc
+    }
+
+    fn doc_to_meal_plan_entry(&self, doc: Document) -> Result<MealPlanEntry> {
+        let meal_plan_uuid = doc.get_object_id(MEAL_PLAN_UUID)?.to_hex();
+        let mut entry: MealPlanEntry = bson::from_document(doc)?;
+        entry.meal_plan_uuid = meal_plan_uuid;
+        Ok(entry)
+    }
+
+    fn doc_to_shopping_list(&self, doc: Document) -> Result<ShoppingList> {
+        let shopping_list_uuid = doc.get_object_id(SHOPPING_LIST_UUID)?.to_hex();
+        let mut list: ShoppingList = bson::from_document(doc)?;
+        list.shopping_list_uuid = shopping_list_uuid;
+        Ok(list)
+    }
+
+    fn doc_from_price_item(&self, item: &PriceItem) -> Document {
+        let mut doc = bson::to_document(item).unwrap();
+        doc.remove(PRICE_UUID_FIELD);
+        doc
+    }
+
+    fn doc_to_price_item(&self, doc: Document) -> Result<PriceItem> {
+        let price_uuid = doc.get_object_id(PRICE_UUID)?.to_hex();
+        let mut item: PriceItem = bson::from_document(doc)?;
+        item.price_uuid = price_uuid;
+        Ok(item)
+    }
+
+    fn doc_from_recipe_request(&self, recipe_request: &RecipeRequest) -> Result<Document> {
         println!("RecipeRequest to convert: {:#?}", recipe_request);
-        let doc = bson::to_document(&recipe_request).unwrap();
+        let mut doc = bson::to_document(&recipe_request)?;
+        let recipe = Recipe::from(recipe_request.clone());
+        doc.insert(DIETS, self.diets_bson(&recipe)?);
         println!("Document: {:#?}", doc);
-        doc
+        Ok(doc)
 
         // let oven_time: Bson = match recipe_request.oven_time {
         //     Some(t) => Bson::Double(t),
@@ -224,6 +766,12 @@ impl DB {
         // doc
     }
 
+    fn diets_bson(&self, recipe: &Recipe) -> Result<Bson> {
+        Ok(bson::to_bson(
+            &diets::recipe_diets(recipe, &self.diets).diets,
+        )?)
+    }
+
     fn doc_to_recipe(&self, doc: Document) -> Result<Recipe> {
         let recipe_uuid = doc.get_object_id(RECIPE_UUID)?.to_hex();
         let req: RecipeRequest = bson::from_document(doc)?;
@@ -1,4 +1,5 @@
 use mongodb::bson;
+use rcp_shared_rs_code::validation::ValidationError;
 use serde::Serialize;
 use std::convert::Infallible;
 use thiserror::Error;
@@ -15,7 +16,47 @@ pub enum Error {
     #[error("invalid id used: {0}")]
     InvalidIDError(String),
     #[error("bson conversion error: {0}")]
-    BsonConversionError(bson::de::Error)
+    BsonConversionError(bson::de::Error),
+    #[error("bson serialization error: {0}")]
+    BsonSerializationError(bson::ser::Error),
+    #[error("recipe cannot be scaled to yield: {0}")]
+    InvalidYieldError(String),
+    #[error("invalid recipe: {0:?}")]
+    InvalidRecipeError(Vec<ValidationError>),
+    #[error("could not import recipe: {0}")]
+    ImportError(String),
+    #[error("invalid cookbook: {0}")]
+    InvalidCookbookError(String),
+    #[error("could not read nutrient table: {0}")]
+    NutrientTableError(String),
+    #[error("could not read allergen rules: {0}")]
+    AllergenRulesError(String),
+    #[error("unknown allergen: {0}")]
+    UnknownAllergenError(String),
+    #[error("could not read diet taxonomy: {0}")]
+    DietTaxonomyError(String),
+    #[error("unknown diet: {0}")]
+    UnknownDietError(String),
+    #[error("invalid price: {0:?}")]
+    InvalidPriceError(Vec<ValidationError>),
+    #[error("could not read aisles: {0}")]
+    AislesError(String),
+    #[error("could not read densities: {0}")]
+    DensitiesError(String),
+    #[error("invalid shopping list: {0}")]
+    InvalidShoppingListError(String),
+    #[error("invalid catalogue entry: {0:?}")]
+    InvalidCatalogueEntryError(Vec<ValidationError>),
+    #[error("unknown catalogue entry: {0}")]
+    UnknownCatalogueEntryError(String),
+    #[error("ingredient to link has changed: {0}")]
+    OutdatedLinkError(String),
+    #[error("invalid pantry item: {0:?}")]
+    InvalidPantryItemError(Vec<ValidationError>),
+    #[error("invalid meal plan: {0:?}")]
+    InvalidMealPlanError(Vec<ValidationError>),
+    #[error("invalid date: {0}")]
+    InvalidDateError(String),
 }
 
 impl From<bson::de::Error> for Error {
@@ -24,9 +65,19 @@ impl From<bson::de::Error> for Error {
     }
 }
 
+impl From<bson::ser::Error> for Error {
+    fn from(err: bson::ser::Error) -> Self {
+        Error::BsonSerializationError(err)
+    }
+}
+
 #[derive(Serialize)]
 struct ErrorResponse {
     message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<ValidationError>,
 }
 
 impl warp::reject::Reject for Error {}
@@ -34,6 +85,8 @@ impl warp::reject::Reject for Error {}
 pub async fn handle_rejection(err: Rejection) -> std::result::Result<Box<dyn Reply>, Infallible> {
     let code;
     let message;
+    let mut detail = None;
+    let mut errors = vec![];
 
     if err.is_not_found() {
         code = StatusCode::NOT_FOUND;
@@ -43,6 +96,81 @@ pub async fn handle_rejection(err: Rejection) -> std::result::Result<Box<dyn Rep
         message = "Invalid Body";
     } else if let Some(e) = err.find::<Error>() {
         match e {
+            // Malformed ids can't belong to anything either
+            Error::InvalidIDError(id) => {
+                code = StatusCode::NOT_FOUND;
+                message = "Not Found";
+                detail = Some(id.clone());
+            }
+            Error::InvalidYieldError(_) => {
+                code = StatusCode::BAD_REQUEST;
+                message = "Invalid Yield";
+            }
+            Error::InvalidRecipeError(validation_errors) => {
+                code = StatusCode::UNPROCESSABLE_ENTITY;
+                message = "Invalid Recipe";
+                errors = validation_errors.clone();
+            }
+            Error::InvalidPriceError(validation_errors) => {
+                code = StatusCode::UNPROCESSABLE_ENTITY;
+                message = "Invalid Price";
+                errors = validation_errors.clone();
+            }
+            Error::ImportError(import_detail) => {
+                code = StatusCode::BAD_REQUEST;
+                message = "Invalid Recipe Document";
+                detail = Some(import_detail.clone());
+            }
+            Error::InvalidCookbookError(cookbook_detail) => {
+                code = StatusCode::BAD_REQUEST;
+                message = "Invalid Cookbook";
+                detail = Some(cookbook_detail.clone());
+            }
+            Error::InvalidCatalogueEntryError(validation_errors) => {
+                code = StatusCode::UNPROCESSABLE_ENTITY;
+                message = "Invalid Catalogue Entry";
+                errors = validation_errors.clone();
+            }
+            Error::UnknownCatalogueEntryError(catalogue_uuid) => {
+                code = StatusCode::BAD_REQUEST;
+                message = "Unknown Catalogue Entry";
+                detail = Some(catalogue_uuid.clone());
+            }
+            Error::OutdatedLinkError(link) => {
+                code = StatusCode::CONFLICT;
+                message = "Outdated Link";
+                detail = Some(link.clone());
+            }
+            Error::InvalidPantryItemError(validation_errors) => {
+                code = StatusCode::UNPROCESSABLE_ENTITY;
+                message = "Invalid Pantry Item";
+                errors = validation_errors.clone();
+            }
+            Error::InvalidMealPlanError(validation_errors) => {
+                code = StatusCode::UNPROCESSABLE_ENTITY;
+                message = "Invalid Meal Plan";
+                errors = validation_errors.clone();
+            }
+            Error::InvalidDateError(date) => {
+                code = StatusCode::BAD_REQUEST;
+                message = "Invalid Date";
+                detail = Some(date.clone());
+            }
+            Error::InvalidShoppingListError(shopping_list_detail) => {
+                code = StatusCode::BAD_REQUEST;
+                message = "Invalid Shopping List";
+                detail = Some(shopping_list_detail.clone());
+            }
+            Error::UnknownAllergenError(name) => {
+                code = StatusCode::BAD_REQUEST;
+                message = "Unknown Allergen";
+                detail = Some(name.clone());
+            }
+            Error::UnknownDietError(name) => {
+                code = StatusCode::BAD_REQUEST;
+                message = "Unknown Diet";
+                detail = Some(name.clone());
+            }
             _ => {
                 eprintln!("unhandled application error: {:?}", err);
                 code = StatusCode::INTERNAL_SERVER_ERROR;
@@ -60,6 +188,8 @@ pub async fn handle_rejection(err: Rejection) -> std::result::Result<Box<dyn Rep
 
     let json = reply::json(&ErrorResponse {
         message: message.into(),
+        detail,
+        errors,
     });
 
     Ok(Box::new(reply::with_status(json, code)))
@@ -1,17 +1,291 @@
+use chrono::{Local, NaiveDate};
 use crate::OvenFanValue;
-use crate::{db::DB, WebResult};
+use crate::{db::DB, error::Error, error::Error::*, WebResult};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::sync::Arc;
+use warp::hyper::body::Bytes;
 use warp::{http::StatusCode, reject, reply::json, Reply};
 pub use rcp_shared_rs_code::models::recipe_request::RecipeRequest;
+use rcp_shared_rs_code::allergens::{self, AllergenRules};
+use rcp_shared_rs_code::catalogue::{self, LinkProposal};
+use rcp_shared_rs_code::costing;
+use rcp_shared_rs_code::density::Densities;
+use rcp_shared_rs_code::diets;
+use rcp_shared_rs_code::formats::{self, cooklang, html, markdown, orf, pdf, schema_org, Format};
+use rcp_shared_rs_code::meal_planning;
+use rcp_shared_rs_code::models::allergen::Allergen;
+use rcp_shared_rs_code::models::catalogue_entry::CatalogueEntry;
+use rcp_shared_rs_code::models::diet::Diet;
+use rcp_shared_rs_code::models::ingredient::Ingredient;
+use rcp_shared_rs_code::models::meal_plan::MealPlanEntry;
+use rcp_shared_rs_code::models::pantry_item::PantryItem;
+use rcp_shared_rs_code::models::price_item::PriceItem;
+use rcp_shared_rs_code::models::r#yield::Yield;
+use rcp_shared_rs_code::models::recipe::Recipe;
+use rcp_shared_rs_code::models::shopping_list::{ShoppingList, ShoppingListRecipe};
+use rcp_shared_rs_code::nutrition::{self, NutrientTable};
+use rcp_shared_rs_code::pantry;
+use rcp_shared_rs_code::shopping::{self, Aisles};
+use rcp_shared_rs_code::validation::ValidationError;
 
-pub async fn recipes_list_handler(db: DB) -> WebResult<impl Reply> {
-    let recipes = db.fetch_recipes().await.map_err(|e| reject::custom(e))?;
+#[derive(Deserialize, Debug)]
+pub struct RecipesQuery {
+    // Only recipes suitable for this diet, e.g. "vegan" or "gluten-free"
+    pub diet: Option<String>,
+    // Comma separated allergens the recipes must not contain, e.g. "milk,eggs"
+    pub free_from: Option<String>,
+}
+
+pub async fn recipes_list_handler(
+    query: RecipesQuery,
+    db: DB,
+    allergen_rules: Arc<AllergenRules>,
+) -> WebResult<impl Reply> {
+    let diet = match query.diet.as_deref() {
+        Some(name) => Some(
+            Diet::from_str(name)
+                .map_err(|_| reject::custom(UnknownDietError(name.trim().to_string())))?,
+        ),
+        None => None,
+    };
+    let mut recipes = db
+        .fetch_recipes(diet)
+        .await
+        .map_err(|e| reject::custom(e))?;
+
+    if let Some(free_from) = query.free_from.as_deref() {
+        let free_from = free_from
+            .split(',')
+            .filter(|name| !name.trim().is_empty())
+            .map(|name| {
+                Allergen::from_str(name)
+                    .map_err(|_| reject::custom(UnknownAllergenError(name.trim().to_string())))
+            })
+            .collect::<Result<Vec<Allergen>, _>>()?;
+        let catalogue = db.fetch_catalogue().await.map_err(|e| reject::custom(e))?;
+        recipes.retain(|recipe| {
+            allergens::recipe_allergens(recipe, &allergen_rules, &catalogue)
+                .is_free_from(&free_from)
+        });
+    }
     Ok(json(&recipes))
 }
 
-pub async fn recipe_handler(id: String, db: DB) -> WebResult<impl Reply> {
+#[derive(Deserialize, Debug)]
+pub struct RecipeQuery {
+    // Scale the recipe to this many servings
+    pub servings: Option<f64>,
+    // The unit of `servings`, e.g. "loaves". The unit of the recipe's first yield if left out
+    pub unit: Option<String>,
+    // Weigh the ingredients: volumes with a known density and all weights in grams
+    pub grams: Option<bool>,
+}
+
+// Responds with JSON, or with Markdown or plain text if the Accept header asks for it
+pub async fn recipe_handler(
+    id: String,
+    query: RecipeQuery,
+    accept: Option<String>,
+    db: DB,
+    densities: Arc<Densities>,
+) -> WebResult<Box<dyn Reply>> {
+    let mut recipe = db.fetch_recipe(&id).await.map_err(|e| reject::custom(e))?;
+
+    if let Some(servings) = query.servings {
+        recipe = scaled_to_servings(&recipe, servings, query.unit.as_deref())?;
+    }
+    if query.grams.unwrap_or(false) {
+        // The default densities of the catalogue come on top of the file
+        let catalogue = db.fetch_catalogue().await.map_err(|e| reject::custom(e))?;
+        let mut densities = (*densities).clone();
+        densities.extend_from_catalogue(&catalogue);
+        recipe = densities.recipe_in_grams(&recipe);
+    }
+
+    Ok(match accept.as_deref().and_then(text_media_type) {
+        Some("text/markdown") => Box::new(warp::reply::with_header(
+            markdown::to_markdown(&recipe),
+            "content-type",
+            "text/markdown; charset=utf-8",
+        )),
+        Some(_) => Box::new(warp::reply::with_header(
+            markdown::to_plain_text(&recipe),
+            "content-type",
+            "text/plain; charset=utf-8",
+        )),
+        None => Box::new(json(&recipe)),
+    })
+}
+
+// Scales to `servings` in `unit`, or else in the unit of the recipe's first yield ("loaves", "pieces")
+fn scaled_to_servings(recipe: &Recipe, servings: f64, unit: Option<&str>) -> WebResult<Recipe> {
+    let unit = match unit.map(str::trim).filter(|unit| !unit.is_empty()) {
+        Some(unit) => unit.to_string(),
+        None => recipe
+            .yields
+            .as_ref()
+            .and_then(|yields| yields.first())
+            .map_or_else(|| Yield::new().unit, |y| y.unit.clone()),
+    };
+    let target = Yield {
+        amount: servings,
+        unit,
+    };
+    let invalid = || reject::custom(InvalidYieldError(format!("{} {}", servings, target.unit)));
+    if !servings.is_finite() || servings <= 0.0 {
+        return Err(invalid());
+    }
+    recipe.scaled(&target).ok_or_else(invalid)
+}
+
+// The first of "text/markdown" and "text/plain" in an Accept header, unless JSON or anything is listed before.
+// Quality values are ignored, clients list the type they prefer first.
+fn text_media_type(accept: &str) -> Option<&'static str> {
+    for media_type in accept.split(',') {
+        let media_type = media_type.split(';').next().unwrap_or("").trim();
+        match media_type.to_lowercase().as_str() {
+            "text/markdown" | "text/x-markdown" => return Some("text/markdown"),
+            "text/plain" => return Some("text/plain"),
+            "application/json" | "*/*" => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+#[derive(Deserialize, Debug)]
+pub struct NutritionQuery {
+    // The index of the yield in the recipe's yields, the first one if left out
+    pub yield_idx: Option<usize>,
+}
+
+// Calories and macros of one of the recipe's yields, in total and per serving, see nutrition::recipe_nutrition
+pub async fn recipe_nutrition_handler(
+    id: String,
+    query: NutritionQuery,
+    db: DB,
+    nutrients: Arc<NutrientTable>,
+) -> WebResult<impl Reply> {
+    let recipe = db.fetch_recipe(&id).await.map_err(|e| reject::custom(e))?;
+    let recipe = with_canonical_names(vec![recipe], &db).await?.remove(0);
+    Ok(json(&nutrition::recipe_nutrition(
+        &recipe,
+        query.yield_idx.unwrap_or(0),
+        &nutrients,
+    )))
+}
+
+// The allergens of the recipe's ingredients and substitutions, see allergens::recipe_allergens
+pub async fn recipe_allergens_handler(
+    id: String,
+    db: DB,
+    allergen_rules: Arc<AllergenRules>,
+) -> WebResult<impl Reply> {
+    let recipe = db.fetch_recipe(&id).await.map_err(|e| reject::custom(e))?;
+    let catalogue = db.fetch_catalogue().await.map_err(|e| reject::custom(e))?;
+    Ok(json(&allergens::recipe_allergens(
+        &recipe,
+        &allergen_rules,
+        &catalogue,
+    )))
+}
+
+// The diets the recipe is suitable for and which ingredients rule out the others, see diets::recipe_diets
+pub async fn recipe_diets_handler(id: String, db: DB) -> WebResult<impl Reply> {
+    let recipe = db.fetch_recipe(&id).await.map_err(|e| reject::custom(e))?;
+    Ok(json(&diets::recipe_diets(&recipe, &db.diets)))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CostQuery {
+    // What one serving (or whatever the yield unit is) sells for, to get the food cost percentage
+    pub selling_price: Option<f64>,
+}
+
+// The ingredient cost of the recipe's first yield with the stored price list, see costing::recipe_cost
+pub async fn recipe_cost_handler(id: String, query: CostQuery, db: DB) -> WebResult<impl Reply> {
     let recipe = db.fetch_recipe(&id).await.map_err(|e| reject::custom(e))?;
-    Ok(json(&recipe))
+    let recipe = with_canonical_names(vec![recipe], &db).await?.remove(0);
+    let prices = db.fetch_prices().await.map_err(|e| reject::custom(e))?;
+    Ok(json(&costing::recipe_cost(
+        &recipe,
+        0,
+        &prices,
+        query.selling_price,
+    )))
+}
+
+// Path parameter "{id}.yaml"
+pub struct YamlId(pub String);
+
+impl FromStr for YamlId {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_suffix(".yaml") {
+            Some(id) if !id.is_empty() => Ok(YamlId(id.to_string())),
+            _ => Err(()),
+        }
+    }
+}
+
+// Open Recipe Format, see formats::orf
+pub async fn recipe_yaml_handler(id: YamlId, db: DB) -> WebResult<impl Reply> {
+    let recipe = db.fetch_recipe(&id.0).await.map_err(|e| reject::custom(e))?;
+    Ok(warp::reply::with_header(
+        orf::to_orf_yaml(&recipe),
+        "content-type",
+        "application/yaml; charset=utf-8",
+    ))
+}
+
+// Path parameter "{id}.jsonld"
+pub struct JsonLdId(pub String);
+
+impl FromStr for JsonLdId {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_suffix(".jsonld") {
+            Some(id) if !id.is_empty() => Ok(JsonLdId(id.to_string())),
+            _ => Err(()),
+        }
+    }
+}
+
+// schema.org Recipe, see formats::schema_org
+pub async fn recipe_json_ld_handler(id: JsonLdId, db: DB) -> WebResult<impl Reply> {
+    let recipe = db.fetch_recipe(&id.0).await.map_err(|e| reject::custom(e))?;
+    Ok(warp::reply::with_header(
+        schema_org::to_schema_org_json(&recipe),
+        "content-type",
+        "application/ld+json; charset=utf-8",
+    ))
+}
+
+// Path parameter "{id}.cook"
+pub struct CookId(pub String);
+
+impl FromStr for CookId {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_suffix(".cook") {
+            Some(id) if !id.is_empty() => Ok(CookId(id.to_string())),
+            _ => Err(()),
+        }
+    }
+}
+
+// Cooklang, see formats::cooklang
+pub async fn recipe_cooklang_handler(id: CookId, db: DB) -> WebResult<impl Reply> {
+    let recipe = db.fetch_recipe(&id.0).await.map_err(|e| reject::custom(e))?;
+    Ok(warp::reply::with_header(
+        cooklang::to_cooklang(&recipe),
+        "content-type",
+        "text/plain; charset=utf-8",
+    ))
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -20,7 +294,18 @@ pub struct CreateRecipeResponse {
     pub recipe_uuid: String,
 }
 
+fn check_recipe_request(recipe_request: &RecipeRequest) -> WebResult<()> {
+    let errors = recipe_request.validate();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(reject::custom(InvalidRecipeError(errors)))
+    }
+}
+
 pub async fn create_recipe_handler(body: RecipeRequest, db: DB) -> WebResult<impl Reply> {
+    check_recipe_request(&body)?;
+
     let _id = db
         .create_recipe(&body)
         .await
@@ -35,7 +320,812 @@ pub async fn create_recipe_handler(body: RecipeRequest, db: DB) -> WebResult<imp
     Ok(json)
 }
 
+pub async fn prices_handler(db: DB) -> WebResult<impl Reply> {
+    let prices = db.fetch_prices().await.map_err(|e| reject::custom(e))?;
+    Ok(json(&prices))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreatePriceResponse {
+    pub status: u16,
+    pub price_uuid: String,
+}
+
+fn check_price_item(item: &PriceItem) -> WebResult<()> {
+    let errors = item.validate();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(reject::custom(InvalidPriceError(errors)))
+    }
+}
+
+pub async fn create_price_handler(body: PriceItem, db: DB) -> WebResult<impl Reply> {
+    check_price_item(&body)?;
+
+    let price_uuid = db
+        .create_price(&body)
+        .await
+        .map_err(|e| reject::custom(e))?;
+
+    Ok(json(&CreatePriceResponse {
+        status: StatusCode::CREATED.as_u16(),
+        price_uuid,
+    }))
+}
+
+pub async fn edit_price_handler(id: String, body: PriceItem, db: DB) -> WebResult<impl Reply> {
+    check_price_item(&body)?;
+
+    db.edit_price(&id, &body)
+        .await
+        .map_err(|e| reject::custom(e))?;
+    Ok(StatusCode::OK)
+}
+
+pub async fn delete_price_handler(id: String, db: DB) -> WebResult<impl Reply> {
+    db.delete_price(&id).await.map_err(|e| reject::custom(e))?;
+    Ok(StatusCode::OK)
+}
+
+pub async fn pantry_handler(db: DB) -> WebResult<impl Reply> {
+    let pantry = db.fetch_pantry().await.map_err(|e| reject::custom(e))?;
+    Ok(json(&pantry))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreatePantryItemResponse {
+    pub status: u16,
+    pub pantry_uuid: String,
+}
+
+fn check_pantry_item(item: &PantryItem) -> WebResult<()> {
+    let errors = item.validate();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(reject::custom(InvalidPantryItemError(errors)))
+    }
+}
+
+pub async fn create_pantry_item_handler(body: PantryItem, db: DB) -> WebResult<impl Reply> {
+    check_pantry_item(&body)?;
+
+    let pantry_uuid = db
+        .create_pantry_item(&body)
+        .await
+        .map_err(|e| reject::custom(e))?;
+
+    Ok(json(&CreatePantryItemResponse {
+        status: StatusCode::CREATED.as_u16(),
+        pantry_uuid,
+    }))
+}
+
+pub async fn edit_pantry_item_handler(
+    id: String,
+    body: PantryItem,
+    db: DB,
+) -> WebResult<impl Reply> {
+    check_pantry_item(&body)?;
+
+    db.edit_pantry_item(&id, &body)
+        .await
+        .map_err(|e| reject::custom(e))?;
+    Ok(StatusCode::OK)
+}
+
+pub async fn delete_pantry_item_handler(id: String, db: DB) -> WebResult<impl Reply> {
+    db.delete_pantry_item(&id)
+        .await
+        .map_err(|e| reject::custom(e))?;
+    Ok(StatusCode::OK)
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CookableQuery {
+    // Also list recipes that lack up to this many ingredients, 0 if left out
+    pub max_missing: Option<usize>,
+}
+
+// The recipes that can be cooked with what is in the pantry, see pantry::cookable_recipes
+pub async fn cookable_recipes_handler(query: CookableQuery, db: DB) -> WebResult<impl Reply> {
+    let recipes = db
+        .fetch_recipes(None)
+        .await
+        .map_err(|e| reject::custom(e))?;
+    let recipes = with_canonical_names(recipes, &db).await?;
+    let catalogue = db.fetch_catalogue().await.map_err(|e| reject::custom(e))?;
+    let pantry = db.fetch_pantry().await.map_err(|e| reject::custom(e))?;
+    let pantry = pantry_with_canonical_names(&pantry, &catalogue);
+    Ok(json(&pantry::cookable_recipes(
+        &recipes,
+        &pantry,
+        query.max_missing.unwrap_or(0),
+        Local::today().naive_local(),
+    )))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CookQuery {
+    // Cook the recipe for this many servings instead of its first yield
+    pub servings: Option<f64>,
+    // The unit of `servings`, see RecipeQuery
+    pub unit: Option<String>,
+    // Take the ingredients from the pantry. Otherwise only shows what would be taken.
+    pub deduct: Option<bool>,
+}
+
+// What cooking the recipe takes from the pantry, see pantry::deduct_recipe.
+// Pantry items that are used up are removed.
+pub async fn cook_recipe_handler(id: String, query: CookQuery, db: DB) -> WebResult<impl Reply> {
+    let mut recipe = db.fetch_recipe(&id).await.map_err(|e| reject::custom(e))?;
+    if let Some(servings) = query.servings {
+        recipe = scaled_to_servings(&recipe, servings, query.unit.as_deref())?;
+    }
+    // Matched by canonical names like in cookable_recipes_handler
+    let recipe = with_canonical_names(vec![recipe], &db).await?.remove(0);
+    let catalogue = db.fetch_catalogue().await.map_err(|e| reject::custom(e))?;
+    let pantry = db.fetch_pantry().await.map_err(|e| reject::custom(e))?;
+    let mut deduction = pantry::deduct_recipe(
+        &recipe,
+        &pantry_with_canonical_names(&pantry, &catalogue),
+        Local::today().naive_local(),
+    );
+    // The pantry items keep their own names
+    for item in deduction.updated.iter_mut() {
+        if let Some(stored) = pantry.iter().find(|p| p.pantry_uuid == item.pantry_uuid) {
+            item.ingredient_name = stored.ingredient_name.clone();
+        }
+    }
+
+    if query.deduct.unwrap_or(false) {
+        for item in deduction.updated.iter() {
+            if item.quantity > 0.0 {
+                db.edit_pantry_item(&item.pantry_uuid, item)
+                    .await
+                    .map_err(|e| reject::custom(e))?;
+            } else {
+                db.delete_pantry_item(&item.pantry_uuid)
+                    .await
+                    .map_err(|e| reject::custom(e))?;
+            }
+        }
+    }
+    Ok(json(&deduction))
+}
+
+pub async fn catalogue_handler(db: DB) -> WebResult<impl Reply> {
+    let catalogue = db.fetch_catalogue().await.map_err(|e| reject::custom(e))?;
+    Ok(json(&catalogue))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateCatalogueEntryResponse {
+    pub status: u16,
+    pub catalogue_uuid: String,
+}
+
+fn check_catalogue_entry(entry: &CatalogueEntry) -> WebResult<()> {
+    let errors = entry.validate();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(reject::custom(InvalidCatalogueEntryError(errors)))
+    }
+}
+
+pub async fn create_catalogue_entry_handler(body: CatalogueEntry, db: DB) -> WebResult<impl Reply> {
+    check_catalogue_entry(&body)?;
+
+    let catalogue_uuid = db
+        .create_catalogue_entry(&body)
+        .await
+        .map_err(|e| reject::custom(e))?;
+
+    Ok(json(&CreateCatalogueEntryResponse {
+        status: StatusCode::CREATED.as_u16(),
+        catalogue_uuid,
+    }))
+}
+
+pub async fn edit_catalogue_entry_handler(
+    id: String,
+    body: CatalogueEntry,
+    db: DB,
+) -> WebResult<impl Reply> {
+    check_catalogue_entry(&body)?;
+
+    db.edit_catalogue_entry(&id, &body)
+        .await
+        .map_err(|e| reject::custom(e))?;
+    Ok(StatusCode::OK)
+}
+
+pub async fn delete_catalogue_entry_handler(id: String, db: DB) -> WebResult<impl Reply> {
+    db.delete_catalogue_entry(&id)
+        .await
+        .map_err(|e| reject::custom(e))?;
+    Ok(StatusCode::OK)
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AutocompleteQuery {
+    // What has been typed so far, e.g. "spr"
+    pub q: String,
+    pub limit: Option<usize>,
+}
+
+// Catalogue entries for an ingredient name being typed, see catalogue::autocomplete
+pub async fn autocomplete_handler(query: AutocompleteQuery, db: DB) -> WebResult<impl Reply> {
+    let catalogue = db.fetch_catalogue().await.map_err(|e| reject::custom(e))?;
+    Ok(json(&catalogue::autocomplete(
+        &catalogue,
+        &query.q,
+        query.limit.unwrap_or(10),
+    )))
+}
+
+// Catalogue entries for the ingredients of every stored recipe that aren't linked yet, see catalogue::propose_links
+pub async fn catalogue_links_handler(db: DB) -> WebResult<impl Reply> {
+    let catalogue = db.fetch_catalogue().await.map_err(|e| reject::custom(e))?;
+    let recipes = db
+        .fetch_recipes(None)
+        .await
+        .map_err(|e| reject::custom(e))?;
+    let proposals: Vec<LinkProposal> = recipes
+        .iter()
+        .flat_map(|recipe| catalogue::propose_links(recipe, &catalogue))
+        .collect();
+    Ok(json(&proposals))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LinkIngredientsResponse {
+    pub linked: usize,
+}
+
+// Links the ingredients of the proposals, e.g. those of GET "catalogue/links" that were checked
+pub async fn link_ingredients_handler(body: Vec<LinkProposal>, db: DB) -> WebResult<impl Reply> {
+    let catalogue = db.fetch_catalogue().await.map_err(|e| reject::custom(e))?;
+    if let Some(link) = body.iter().find(|link| {
+        !catalogue
+            .iter()
+            .any(|entry| entry.catalogue_uuid == link.catalogue_uuid)
+    }) {
+        return Err(reject::custom(UnknownCatalogueEntryError(
+            link.catalogue_uuid.clone(),
+        )));
+    }
+
+    let mut recipe_uuids: Vec<&str> = body.iter().map(|link| link.recipe_uuid.as_str()).collect();
+    recipe_uuids.sort_unstable();
+    recipe_uuids.dedup();
+
+    // Links proposed before a recipe was edited can point past its ingredients or at another one
+    for recipe_uuid in recipe_uuids.iter() {
+        let recipe = db
+            .fetch_recipe(recipe_uuid)
+            .await
+            .map_err(|e| reject::custom(e))?;
+        if let Some(link) = body.iter().find(|link| {
+            link.recipe_uuid == *recipe_uuid
+                && catalogue::linked_ingredient(&recipe, link).is_none()
+        }) {
+            return Err(reject::custom(OutdatedLinkError(format!(
+                "{} of recipe {}",
+                link.ingredient_name, recipe_uuid
+            ))));
+        }
+    }
+
+    for recipe_uuid in recipe_uuids {
+        let links: Vec<LinkProposal> = body
+            .iter()
+            .filter(|link| link.recipe_uuid == recipe_uuid)
+            .cloned()
+            .collect();
+        db.link_ingredients(recipe_uuid, &links)
+            .await
+            .map_err(|e| reject::custom(e))?;
+    }
+    Ok(json(&LinkIngredientsResponse { linked: body.len() }))
+}
+
+// Linked ingredients named by their catalogue entry, so "scallions" and "spring onions" are the same,
+// see catalogue::with_canonical_names
+async fn with_canonical_names(recipes: Vec<Recipe>, db: &DB) -> WebResult<Vec<Recipe>> {
+    let catalogue = db.fetch_catalogue().await.map_err(|e| reject::custom(e))?;
+    Ok(recipes
+        .iter()
+        .map(|recipe| catalogue::with_canonical_names(recipe, &catalogue))
+        .collect())
+}
+
+// A recipe a request refers to in its body. An unknown id is reported with `invalid` rather than as a 404,
+// which is kept for ids in the path
+async fn fetch_referenced_recipe<F: FnOnce() -> Error>(
+    id: &str,
+    db: &DB,
+    invalid: F,
+) -> WebResult<Recipe> {
+    match db.fetch_recipe(id).await {
+        Ok(recipe) => Ok(recipe),
+        Err(InvalidIDError(_)) => Err(reject::custom(invalid())),
+        Err(e) => Err(reject::custom(e)),
+    }
+}
+
+// "scallions" in the pantry are the "spring onion" of recipes with canonical names
+fn pantry_with_canonical_names(
+    pantry: &[PantryItem],
+    catalogue: &[CatalogueEntry],
+) -> Vec<PantryItem> {
+    let mut pantry = pantry.to_vec();
+    for item in pantry.iter_mut() {
+        if let Some(entry) = catalogue::find_entry(catalogue, &item.ingredient_name) {
+            item.ingredient_name = entry.canonical_name.clone();
+        }
+    }
+    pantry
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ShoppingListRequest {
+    pub name: Option<String>,
+    // The recipes with the yield to buy for, e.g. {"recipe_uuid": "...", "target": {"amount": 6, "unit": "servings"}}
+    pub recipes: Vec<ShoppingListRecipe>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateShoppingListResponse {
+    pub status: u16,
+    pub shopping_list_uuid: String,
+}
+
+// Adds up the ingredients of the recipes and stores them as a new list, see shopping::shopping_items
+pub async fn create_shopping_list_handler(
+    body: ShoppingListRequest,
+    db: DB,
+    aisles: Arc<Aisles>,
+) -> WebResult<impl Reply> {
+    if body.recipes.is_empty() {
+        return Err(reject::custom(InvalidShoppingListError(
+            "recipes is empty".to_string(),
+        )));
+    }
+
+    let mut recipes: Vec<Recipe> = vec![];
+    for entry in &body.recipes {
+        if !entry.target.amount.is_finite() || entry.target.amount <= 0.0 {
+            return Err(reject::custom(InvalidYieldError(
+                entry.target.amount.to_string(),
+            )));
+        }
+        recipes.push(
+            fetch_referenced_recipe(&entry.recipe_uuid, &db, || {
+                InvalidShoppingListError(format!("unknown recipe {}", entry.recipe_uuid))
+            })
+            .await?,
+        );
+    }
+    let canonical = with_canonical_names(recipes.clone(), &db).await?;
+    let targets: Vec<(&Recipe, &Yield)> = canonical
+        .iter()
+        .zip(body.recipes.iter().map(|entry| &entry.target))
+        .collect();
+    let items = shopping::shopping_items(&targets, &aisles)
+        .map_err(|e| reject::custom(InvalidShoppingListError(e.to_string())))?;
+
+    let list = ShoppingList {
+        shopping_list_uuid: "".to_string(),
+        name: body.name.filter(|name| !name.trim().is_empty()),
+        recipes: body
+            .recipes
+            .iter()
+            .zip(recipes.iter())
+            .map(|(entry, recipe)| ShoppingListRecipe {
+                recipe_name: recipe.recipe_name.clone(),
+                ..entry.clone()
+            })
+            .collect(),
+        items,
+    };
+    let shopping_list_uuid = db
+        .create_shopping_list(&list)
+        .await
+        .map_err(|e| reject::custom(e))?;
+
+    Ok(json(&CreateShoppingListResponse {
+        status: StatusCode::CREATED.as_u16(),
+        shopping_list_uuid,
+    }))
+}
+
+pub async fn shopping_lists_handler(db: DB) -> WebResult<impl Reply> {
+    let lists = db
+        .fetch_shopping_lists()
+        .await
+        .map_err(|e| reject::custom(e))?;
+    Ok(json(&lists))
+}
+
+pub async fn shopping_list_handler(id: String, db: DB) -> WebResult<impl Reply> {
+    let list = db
+        .fetch_shopping_list(&id)
+        .await
+        .map_err(|e| reject::custom(e))?;
+    Ok(json(&list))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CheckItemRequest {
+    pub checked: bool,
+}
+
+// Checks off an item of a list, by its position in the list's items
+pub async fn check_shopping_item_handler(
+    id: String,
+    idx: usize,
+    body: CheckItemRequest,
+    db: DB,
+) -> WebResult<impl Reply> {
+    let list = db
+        .fetch_shopping_list(&id)
+        .await
+        .map_err(|e| reject::custom(e))?;
+    if idx >= list.items.len() {
+        return Err(reject::custom(InvalidShoppingListError(format!(
+            "no item {}, the list has {}",
+            idx,
+            list.items.len()
+        ))));
+    }
+    db.check_shopping_item(&id, idx, body.checked)
+        .await
+        .map_err(|e| reject::custom(e))?;
+    Ok(StatusCode::OK)
+}
+
+pub async fn delete_shopping_list_handler(id: String, db: DB) -> WebResult<impl Reply> {
+    db.delete_shopping_list(&id)
+        .await
+        .map_err(|e| reject::custom(e))?;
+    Ok(StatusCode::OK)
+}
+
+#[derive(Deserialize, Debug)]
+pub struct MealPlanQuery {
+    // First and last day of the plan, e.g. "2020-11-30", both included. The whole plan if left out.
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+impl MealPlanQuery {
+    fn range(&self) -> WebResult<(Option<NaiveDate>, Option<NaiveDate>)> {
+        let date = |date: &Option<String>| match date.as_deref() {
+            Some(date) => NaiveDate::from_str(date.trim())
+                .map(Some)
+                .map_err(|_| reject::custom(InvalidDateError(date.trim().to_string()))),
+            None => Ok(None),
+        };
+        Ok((date(&self.from)?, date(&self.to)?))
+    }
+}
+
+pub async fn meal_plan_handler(query: MealPlanQuery, db: DB) -> WebResult<impl Reply> {
+    let (from, to) = query.range()?;
+    let entries = db
+        .fetch_meal_plan(from, to)
+        .await
+        .map_err(|e| reject::custom(e))?;
+    Ok(json(&entries))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateMealPlanEntryResponse {
+    pub status: u16,
+    pub meal_plan_uuid: String,
+}
+
+// Rejects invalid entries and entries for recipes that don't exist
+async fn check_meal_plan_entry(entry: &MealPlanEntry, db: &DB) -> WebResult<()> {
+    let errors = entry.validate();
+    if !errors.is_empty() {
+        return Err(reject::custom(InvalidMealPlanError(errors)));
+    }
+    fetch_referenced_recipe(&entry.recipe_uuid, db, || {
+        InvalidMealPlanError(vec![ValidationError::new(
+            "recipe_uuid",
+            &format!("Unknown recipe {}", entry.recipe_uuid),
+        )])
+    })
+    .await?;
+    Ok(())
+}
+
+pub async fn create_meal_plan_entry_handler(body: MealPlanEntry, db: DB) -> WebResult<impl Reply> {
+    check_meal_plan_entry(&body, &db).await?;
+
+    let meal_plan_uuid = db
+        .create_meal_plan_entry(&body)
+        .await
+        .map_err(|e| reject::custom(e))?;
+
+    Ok(json(&CreateMealPlanEntryResponse {
+        status: StatusCode::CREATED.as_u16(),
+        meal_plan_uuid,
+    }))
+}
+
+pub async fn edit_meal_plan_entry_handler(
+    id: String,
+    body: MealPlanEntry,
+    db: DB,
+) -> WebResult<impl Reply> {
+    check_meal_plan_entry(&body, &db).await?;
+
+    db.edit_meal_plan_entry(&id, &body)
+        .await
+        .map_err(|e| reject::custom(e))?;
+    Ok(StatusCode::OK)
+}
+
+pub async fn delete_meal_plan_entry_handler(id: String, db: DB) -> WebResult<impl Reply> {
+    db.delete_meal_plan_entry(&id)
+        .await
+        .map_err(|e| reject::custom(e))?;
+    Ok(StatusCode::OK)
+}
+
+// The planned meals of the query's range with their recipes
+async fn fetch_planned_meals(
+    query: &MealPlanQuery,
+    db: &DB,
+) -> WebResult<Vec<(MealPlanEntry, Recipe)>> {
+    let (from, to) = query.range()?;
+    let entries = db
+        .fetch_meal_plan(from, to)
+        .await
+        .map_err(|e| reject::custom(e))?;
+
+    let mut planned: Vec<(MealPlanEntry, Recipe)> = vec![];
+    for entry in entries {
+        let recipe = match planned
+            .iter()
+            .find(|(e, _)| e.recipe_uuid == entry.recipe_uuid)
+        {
+            Some((_, recipe)) => recipe.clone(),
+            None => db
+                .fetch_recipe(&entry.recipe_uuid)
+                .await
+                .map_err(|e| reject::custom(e))?,
+        };
+        planned.push((entry, recipe));
+    }
+    Ok(planned)
+}
+
+// Stores a shopping list for the planned meals of the range, see meal_planning::plan_shopping_items
+pub async fn meal_plan_shopping_list_handler(
+    query: MealPlanQuery,
+    db: DB,
+    aisles: Arc<Aisles>,
+) -> WebResult<impl Reply> {
+    let planned = fetch_planned_meals(&query, &db).await?;
+    if planned.is_empty() {
+        return Err(reject::custom(InvalidShoppingListError(
+            "no meals planned".to_string(),
+        )));
+    }
+
+    let canonical =
+        with_canonical_names(planned.iter().map(|(_, r)| r.clone()).collect(), &db).await?;
+    let items = meal_planning::plan_shopping_items(
+        &planned
+            .iter()
+            .zip(canonical.iter())
+            .map(|((e, _), r)| (e, r))
+            .collect::<Vec<(&MealPlanEntry, &Recipe)>>(),
+        &aisles,
+    )
+    .map_err(|e| reject::custom(InvalidShoppingListError(e.to_string())))?;
+
+    let (first, last) = (planned[0].0.date, planned[planned.len() - 1].0.date);
+    let list = ShoppingList {
+        shopping_list_uuid: "".to_string(),
+        name: Some(format!("Meal plan {} to {}", first, last)),
+        recipes: planned
+            .iter()
+            .map(|(entry, recipe)| ShoppingListRecipe {
+                recipe_uuid: entry.recipe_uuid.clone(),
+                recipe_name: recipe.recipe_name.clone(),
+                target: meal_planning::planned_yield(entry, recipe),
+            })
+            .collect(),
+        items,
+    };
+    let shopping_list_uuid = db
+        .create_shopping_list(&list)
+        .await
+        .map_err(|e| reject::custom(e))?;
+
+    Ok(json(&CreateShoppingListResponse {
+        status: StatusCode::CREATED.as_u16(),
+        shopping_list_uuid,
+    }))
+}
+
+// When to start cooking the planned meals of the range, see meal_planning::prep_schedule
+pub async fn meal_plan_prep_handler(query: MealPlanQuery, db: DB) -> WebResult<impl Reply> {
+    let planned = fetch_planned_meals(&query, &db).await?;
+    let planned: Vec<(&MealPlanEntry, &Recipe)> = planned.iter().map(|(e, r)| (e, r)).collect();
+    Ok(json(&meal_planning::prep_schedule(&planned)))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ImportQuery {
+    // See formats::Format, detected from the document if missing
+    pub format: Option<String>,
+    // Recipe name for documents that don't have one, e.g. the name of an uploaded .cook file
+    pub name: Option<String>,
+    // Only read and validate the upload, nothing is stored
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+// Stores a recipe from an uploaded document, e.g. Open Recipe Format YAML, schema.org JSON-LD or Cooklang.
+// Of archives and documents with several recipes only the first one is stored, see bulk_import_handler.
+// A dry run returns the recipe as it would be stored.
+pub async fn import_recipe_handler(
+    query: ImportQuery,
+    body: Bytes,
+    db: DB,
+) -> WebResult<Box<dyn Reply>> {
+    let format = import_format(query.format)?;
+
+    let mut recipe_request = formats::import_bytes(&body, format)
+        .and_then(|mut results| results.remove(0))
+        .map_err(|e| reject::custom(ImportError(e.to_string())))?;
+    if recipe_request.recipe_name.is_none() {
+        recipe_request.recipe_name = query.name;
+    }
+
+    if query.dry_run {
+        check_recipe_request(&recipe_request)?;
+        return Ok(Box::new(json(&recipe_request)));
+    }
+    Ok(Box::new(create_recipe_handler(recipe_request, db).await?))
+}
+
+fn import_format(format: Option<String>) -> WebResult<Option<Format>> {
+    match format {
+        Some(format) => Format::from_str(&format)
+            .map(Some)
+            .map_err(|_| reject::custom(ImportError(format!("Unknown format: {}", format)))),
+        None => Ok(None),
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct ImportedRecipe {
+    // Position of the recipe in the uploaded file, starting at 0
+    pub index: usize,
+    pub recipe_name: Option<String>,
+    // None in a dry run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipe_uuid: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct FailedImport {
+    pub index: usize,
+    pub recipe_name: Option<String>,
+    pub message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<ValidationError>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct BulkImportResponse {
+    // If set, "created" lists the recipes that would have been created
+    pub dry_run: bool,
+    pub created: Vec<ImportedRecipe>,
+    pub failed: Vec<FailedImport>,
+}
+
+// Stores every recipe of a file like a MealMaster or RecipeML archive, or a Paprika, Mealie or Tandoor export.
+// Recipes that can't be read or are invalid are reported in the response instead of failing the whole upload.
+pub async fn bulk_import_handler(
+    query: ImportQuery,
+    body: Bytes,
+    db: DB,
+) -> WebResult<impl Reply> {
+    let format = import_format(query.format)?;
+
+    let results = formats::import_bytes(&body, format)
+        .map_err(|e| reject::custom(ImportError(e.to_string())))?;
+
+    let mut response = BulkImportResponse {
+        dry_run: query.dry_run,
+        created: vec![],
+        failed: vec![],
+    };
+    for (index, result) in results.into_iter().enumerate() {
+        let recipe_request = match result {
+            Ok(recipe_request) => recipe_request,
+            Err(e) => {
+                response.failed.push(FailedImport {
+                    index,
+                    recipe_name: None,
+                    message: e.to_string(),
+                    errors: vec![],
+                });
+                continue;
+            }
+        };
+
+        let errors = recipe_request.validate();
+        if !errors.is_empty() {
+            response.failed.push(FailedImport {
+                index,
+                recipe_name: recipe_request.recipe_name,
+                message: format!("Recipe {}: invalid recipe", index + 1),
+                errors,
+            });
+            continue;
+        }
+
+        // A failing insert is reported like the other failures, so the recipes stored so far are still listed
+        let recipe_uuid = if query.dry_run {
+            None
+        } else {
+            match db.create_recipe(&recipe_request).await {
+                Ok(recipe_uuid) => Some(recipe_uuid),
+                Err(e) => {
+                    eprintln!("could not store imported recipe {}: {}", index + 1, e);
+                    response.failed.push(FailedImport {
+                        index,
+                        recipe_name: recipe_request.recipe_name,
+                        message: format!("Recipe {}: could not be stored", index + 1),
+                        errors: vec![],
+                    });
+                    continue;
+                }
+            }
+        };
+        response.created.push(ImportedRecipe {
+            index,
+            recipe_name: recipe_request.recipe_name,
+            recipe_uuid,
+        });
+    }
+
+    Ok(json(&response))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct HtmlImportQuery {
+    // Where the page was saved from, if it doesn't say so itself
+    pub source_url: Option<String>,
+}
+
+// Stores the recipe found in an HTML page, see formats::html
+pub async fn import_html_handler(
+    query: HtmlImportQuery,
+    body: Bytes,
+    db: DB,
+) -> WebResult<impl Reply> {
+    let text = String::from_utf8_lossy(&body);
+    let recipe_request = html::from_html(&text, query.source_url.as_deref())
+        .map_err(|e| reject::custom(ImportError(e.to_string())))?;
+
+    create_recipe_handler(recipe_request, db).await
+}
+
 pub async fn edit_recipe_handler(id: String, body: RecipeRequest, db: DB) -> WebResult<impl Reply> {
+    check_recipe_request(&body)?;
+
     db.edit_recipe(&id, &body)
         .await
         .map_err(|e| reject::custom(e))?;
@@ -47,3 +1137,65 @@ pub async fn delete_recipe_handler(id: String, db: DB) -> WebResult<impl Reply>
     Ok(StatusCode::OK)
 }
 
+#[derive(Serialize, Debug)]
+pub struct ParsedIngredientLine {
+    pub line: String,
+    // None if the line could not be understood
+    pub ingredient: Option<Ingredient>,
+}
+
+// Parses a plain text body with one ingredient per line
+pub async fn parse_ingredients_handler(body: Bytes) -> WebResult<impl Reply> {
+    let text = String::from_utf8_lossy(&body);
+    let parsed: Vec<ParsedIngredientLine> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| ParsedIngredientLine {
+            line: line.to_string(),
+            ingredient: Ingredient::from_str(line).ok(),
+        })
+        .collect();
+
+    Ok(json(&parsed))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CookbookRequest {
+    // Printed on the title page and stored as the PDF title
+    pub title: Option<String>,
+    // The recipes in the order they are printed
+    pub recipe_ids: Vec<String>,
+}
+
+// A printable cookbook of the given recipes, see formats::pdf
+pub async fn cookbook_pdf_handler(body: CookbookRequest, db: DB) -> WebResult<impl Reply> {
+    if body.recipe_ids.is_empty() {
+        return Err(reject::custom(InvalidCookbookError(
+            "recipe_ids is empty".to_string(),
+        )));
+    }
+
+    let mut recipes = vec![];
+    for id in &body.recipe_ids {
+        recipes.push(
+            fetch_referenced_recipe(id, &db, || {
+                InvalidCookbookError(format!("unknown recipe {}", id))
+            })
+            .await?,
+        );
+    }
+    let title = body
+        .title
+        .filter(|title| !title.trim().is_empty())
+        .unwrap_or_else(|| "Cookbook".to_string());
+
+    Ok(warp::reply::with_header(
+        warp::reply::with_header(
+            pdf::to_cookbook_pdf(&title, &recipes),
+            "content-type",
+            "application/pdf",
+        ),
+        "content-disposition",
+        "inline; filename=\"cookbook.pdf\"",
+    ))
+}
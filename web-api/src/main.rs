@@ -7,11 +7,33 @@
 use db::DB;
 use serde::{Deserialize, Serialize};
 use std::convert::Infallible;
+use std::sync::Arc;
 use warp::http::header::HeaderName;
 use warp::{http::Method, Filter, Rejection};
 
 use rcp_shared_rs_code::models::oven_fan_value::OvenFanValue;
 use rcp_shared_rs_code::models::recipe_request::RecipeRequest;
+use rcp_shared_rs_code::allergens::AllergenRules;
+use rcp_shared_rs_code::density::Densities;
+use rcp_shared_rs_code::diets::DietTaxonomy;
+use rcp_shared_rs_code::nutrition::NutrientTable;
+use rcp_shared_rs_code::shopping::Aisles;
+
+const MAX_TEXT_BODY_SIZE: u64 = 1024 * 1024;
+// Recipe archives with thousands of recipes
+const MAX_BULK_BODY_SIZE: u64 = 32 * 1024 * 1024;
+// Path of a nutrient table to use instead of the bundled one, e.g. the full USDA SR Legacy ABBREV.csv
+const NUTRIENT_TABLE_VAR: &str = "RCP_NUTRIENT_TABLE";
+// Path of allergen rules to use instead of the bundled ones, see rcp_shared_rs_code/data/allergens.yml
+const ALLERGEN_RULES_VAR: &str = "RCP_ALLERGEN_RULES";
+// Path of a diet taxonomy to use instead of the bundled one, see rcp_shared_rs_code/data/diets.yml
+const DIET_TAXONOMY_VAR: &str = "RCP_DIET_TAXONOMY";
+// Stores the diets of every recipe again and exits, after the diet taxonomy changed
+const UPDATE_DIETS_COMMAND: &str = "update-diets";
+// Path of store aisles to use instead of the bundled ones, see rcp_shared_rs_code/data/aisles.yml
+const AISLES_VAR: &str = "RCP_AISLES";
+// Path of densities to add to the bundled ones, see rcp_shared_rs_code/data/densities.yml
+const DENSITIES_VAR: &str = "RCP_DENSITIES";
 
 type Result<T> = std::result::Result<T, error::Error>;
 type WebResult<T> = std::result::Result<T, Rejection>;
@@ -22,10 +44,55 @@ mod handler;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let db = DB::init().await?;
+    let db = DB::init(Arc::new(load_diet_taxonomy()?)).await?;
+    if std::env::args().nth(1).as_deref() == Some(UPDATE_DIETS_COMMAND) {
+        let (updated, failed) = db.update_recipe_diets().await?;
+        println!(
+            "Updated the diets of {} recipes, {} failed",
+            updated, failed
+        );
+        return Ok(());
+    }
+    let nutrients = Arc::new(load_nutrient_table()?);
+    let allergens = Arc::new(load_allergen_rules()?);
+    let aisles = Arc::new(load_aisles()?);
+    let densities = Arc::new(load_densities()?);
 
     let recipe = warp::path("recipe");
 
+    // POST "recipe/import?format={format}&name={name}&dry_run={bool}", before the json route which would reject the body
+    let recipe_import_routes = recipe
+        .and(warp::path("import"))
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(warp::query::<handler::ImportQuery>())
+        .and(warp::body::content_length_limit(MAX_TEXT_BODY_SIZE))
+        .and(warp::body::bytes())
+        .and(with_db(db.clone()))
+        .and_then(handler::import_recipe_handler)
+        // POST "recipe/import/html?source_url={url}"
+        .or(recipe
+            .and(warp::path("import"))
+            .and(warp::path("html"))
+            .and(warp::path::end())
+            .and(warp::post())
+            .and(warp::query::<handler::HtmlImportQuery>())
+            .and(warp::body::content_length_limit(MAX_TEXT_BODY_SIZE))
+            .and(warp::body::bytes())
+            .and(with_db(db.clone()))
+            .and_then(handler::import_html_handler))
+        // POST "recipe/import/bulk?format={format}&dry_run={bool}"
+        .or(recipe
+            .and(warp::path("import"))
+            .and(warp::path("bulk"))
+            .and(warp::path::end())
+            .and(warp::post())
+            .and(warp::query::<handler::ImportQuery>())
+            .and(warp::body::content_length_limit(MAX_BULK_BODY_SIZE))
+            .and(warp::body::bytes())
+            .and(with_db(db.clone()))
+            .and_then(handler::bulk_import_handler));
+
     let recipe_routes = recipe
         .and(warp::post())
         .and(warp::body::json())
@@ -44,24 +111,335 @@ async fn main() -> Result<()> {
             .and(warp::path::param())
             .and(with_db(db.clone()))
             .and_then(handler::delete_recipe_handler))
-        // GET "recipe/{id}"
+        // GET "recipe/{id}.yaml"
+        .or(recipe
+            .and(warp::get())
+            .and(warp::path::param::<handler::YamlId>())
+            .and(with_db(db.clone()))
+            .and_then(handler::recipe_yaml_handler))
+        // GET "recipe/{id}.jsonld"
+        .or(recipe
+            .and(warp::get())
+            .and(warp::path::param::<handler::JsonLdId>())
+            .and(with_db(db.clone()))
+            .and_then(handler::recipe_json_ld_handler))
+        // GET "recipe/{id}.cook"
+        .or(recipe
+            .and(warp::get())
+            .and(warp::path::param::<handler::CookId>())
+            .and(with_db(db.clone()))
+            .and_then(handler::recipe_cooklang_handler))
+        // GET "recipe/{id}/nutrition?yield_idx={idx}"
+        .or(recipe
+            .and(warp::get())
+            .and(warp::path::param())
+            .and(warp::path("nutrition"))
+            .and(warp::path::end())
+            .and(warp::query::<handler::NutritionQuery>())
+            .and(with_db(db.clone()))
+            .and(with_nutrients(nutrients.clone()))
+            .and_then(handler::recipe_nutrition_handler))
+        // GET "recipe/{id}/allergens"
+        .or(recipe
+            .and(warp::get())
+            .and(warp::path::param())
+            .and(warp::path("allergens"))
+            .and(warp::path::end())
+            .and(with_db(db.clone()))
+            .and(with_allergens(allergens.clone()))
+            .and_then(handler::recipe_allergens_handler))
+        // GET "recipe/{id}/diets"
+        .or(recipe
+            .and(warp::get())
+            .and(warp::path::param())
+            .and(warp::path("diets"))
+            .and(warp::path::end())
+            .and(with_db(db.clone()))
+            .and_then(handler::recipe_diets_handler))
+        // GET "recipe/{id}/cost?selling_price={price}"
+        .or(recipe
+            .and(warp::get())
+            .and(warp::path::param())
+            .and(warp::path("cost"))
+            .and(warp::path::end())
+            .and(warp::query::<handler::CostQuery>())
+            .and(with_db(db.clone()))
+            .and_then(handler::recipe_cost_handler))
+        // POST "recipe/{id}/cook?servings={n}&unit={unit}&deduct={bool}"
+        .or(recipe
+            .and(warp::post())
+            .and(warp::path::param())
+            .and(warp::path("cook"))
+            .and(warp::path::end())
+            .and(warp::query::<handler::CookQuery>())
+            .and(with_db(db.clone()))
+            .and_then(handler::cook_recipe_handler))
+        // GET "recipe/{id}?servings={n}&unit={unit}&grams={bool}", as Markdown or plain text with "Accept: text/markdown" or "text/plain"
         .or(recipe
             .and(warp::get())
             .and(warp::path::param())
+            .and(warp::query::<handler::RecipeQuery>())
+            .and(warp::header::optional::<String>("accept"))
             .and(with_db(db.clone()))
+            .and(with_densities(densities.clone()))
             .and_then(handler::recipe_handler))
-        // GET "/recipe"
+        // GET "/recipe?diet={diet}&free_from={allergen},{allergen}"
         .or(recipe
             .and(warp::get())
+            .and(warp::query::<handler::RecipesQuery>())
             .and(with_db(db.clone()))
+            .and(with_allergens(allergens.clone()))
             .and_then(handler::recipes_list_handler));
 
-    let routes = recipe_routes
+    let price = warp::path("price");
+
+    // GET "price"
+    let price_routes = price
+        .and(warp::get())
+        .and(warp::path::end())
+        .and(with_db(db.clone()))
+        .and_then(handler::prices_handler)
+        // POST "price"
+        .or(price
+            .and(warp::post())
+            .and(warp::path::end())
+            .and(warp::body::json())
+            .and(with_db(db.clone()))
+            .and_then(handler::create_price_handler))
+        // PUT "price/{id}"
+        .or(price
+            .and(warp::put())
+            .and(warp::path::param())
+            .and(warp::path::end())
+            .and(warp::body::json())
+            .and(with_db(db.clone()))
+            .and_then(handler::edit_price_handler))
+        // DELETE "price/{id}"
+        .or(price
+            .and(warp::delete())
+            .and(warp::path::param())
+            .and(warp::path::end())
+            .and(with_db(db.clone()))
+            .and_then(handler::delete_price_handler));
+
+    let catalogue = warp::path("catalogue");
+
+    // GET "catalogue/autocomplete?q={text}&limit={n}"
+    let catalogue_routes = catalogue
+        .and(warp::path("autocomplete"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(warp::query::<handler::AutocompleteQuery>())
+        .and(with_db(db.clone()))
+        .and_then(handler::autocomplete_handler)
+        // GET "catalogue/links"
+        .or(catalogue
+            .and(warp::path("links"))
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(with_db(db.clone()))
+            .and_then(handler::catalogue_links_handler))
+        // POST "catalogue/links" with the proposals of GET "catalogue/links" to apply
+        .or(catalogue
+            .and(warp::path("links"))
+            .and(warp::path::end())
+            .and(warp::post())
+            .and(warp::body::json())
+            .and(with_db(db.clone()))
+            .and_then(handler::link_ingredients_handler))
+        // GET "catalogue"
+        .or(catalogue
+            .and(warp::get())
+            .and(warp::path::end())
+            .and(with_db(db.clone()))
+            .and_then(handler::catalogue_handler))
+        // POST "catalogue"
+        .or(catalogue
+            .and(warp::post())
+            .and(warp::path::end())
+            .and(warp::body::json())
+            .and(with_db(db.clone()))
+            .and_then(handler::create_catalogue_entry_handler))
+        // PUT "catalogue/{id}"
+        .or(catalogue
+            .and(warp::put())
+            .and(warp::path::param())
+            .and(warp::path::end())
+            .and(warp::body::json())
+            .and(with_db(db.clone()))
+            .and_then(handler::edit_catalogue_entry_handler))
+        // DELETE "catalogue/{id}"
+        .or(catalogue
+            .and(warp::delete())
+            .and(warp::path::param())
+            .and(warp::path::end())
+            .and(with_db(db.clone()))
+            .and_then(handler::delete_catalogue_entry_handler));
+
+    let pantry = warp::path("pantry");
+
+    // GET "pantry/recipes?max_missing={n}"
+    let pantry_routes = pantry
+        .and(warp::path("recipes"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(warp::query::<handler::CookableQuery>())
+        .and(with_db(db.clone()))
+        .and_then(handler::cookable_recipes_handler)
+        // GET "pantry"
+        .or(pantry
+            .and(warp::get())
+            .and(warp::path::end())
+            .and(with_db(db.clone()))
+            .and_then(handler::pantry_handler))
+        // POST "pantry"
+        .or(pantry
+            .and(warp::post())
+            .and(warp::path::end())
+            .and(warp::body::json())
+            .and(with_db(db.clone()))
+            .and_then(handler::create_pantry_item_handler))
+        // PUT "pantry/{id}"
+        .or(pantry
+            .and(warp::put())
+            .and(warp::path::param())
+            .and(warp::path::end())
+            .and(warp::body::json())
+            .and(with_db(db.clone()))
+            .and_then(handler::edit_pantry_item_handler))
+        // DELETE "pantry/{id}"
+        .or(pantry
+            .and(warp::delete())
+            .and(warp::path::param())
+            .and(warp::path::end())
+            .and(with_db(db.clone()))
+            .and_then(handler::delete_pantry_item_handler));
+
+    let shopping_list = warp::path("shopping-list");
+
+    // POST "shopping-list" with {"name": ..., "recipes": [{"recipe_uuid": ..., "target": {"amount": ..., "unit": ...}}]}
+    let shopping_list_routes = shopping_list
+        .and(warp::post())
+        .and(warp::path::end())
+        .and(warp::body::content_length_limit(MAX_TEXT_BODY_SIZE))
+        .and(warp::body::json())
+        .and(with_db(db.clone()))
+        .and(with_aisles(aisles.clone()))
+        .and_then(handler::create_shopping_list_handler)
+        // GET "shopping-list"
+        .or(shopping_list
+            .and(warp::get())
+            .and(warp::path::end())
+            .and(with_db(db.clone()))
+            .and_then(handler::shopping_lists_handler))
+        // GET "shopping-list/{id}"
+        .or(shopping_list
+            .and(warp::get())
+            .and(warp::path::param())
+            .and(warp::path::end())
+            .and(with_db(db.clone()))
+            .and_then(handler::shopping_list_handler))
+        // PUT "shopping-list/{id}/items/{idx}" with {"checked": bool}
+        .or(shopping_list
+            .and(warp::put())
+            .and(warp::path::param())
+            .and(warp::path("items"))
+            .and(warp::path::param())
+            .and(warp::path::end())
+            .and(warp::body::json())
+            .and(with_db(db.clone()))
+            .and_then(handler::check_shopping_item_handler))
+        // DELETE "shopping-list/{id}"
+        .or(shopping_list
+            .and(warp::delete())
+            .and(warp::path::param())
+            .and(warp::path::end())
+            .and(with_db(db.clone()))
+            .and_then(handler::delete_shopping_list_handler));
+
+    let meal_plan = warp::path("meal-plan");
+
+    // POST "meal-plan/shopping-list?from={date}&to={date}"
+    let meal_plan_routes = meal_plan
+        .and(warp::path("shopping-list"))
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(warp::query::<handler::MealPlanQuery>())
+        .and(with_db(db.clone()))
+        .and(with_aisles(aisles.clone()))
+        .and_then(handler::meal_plan_shopping_list_handler)
+        // GET "meal-plan/prep-schedule?from={date}&to={date}"
+        .or(meal_plan
+            .and(warp::path("prep-schedule"))
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(warp::query::<handler::MealPlanQuery>())
+            .and(with_db(db.clone()))
+            .and_then(handler::meal_plan_prep_handler))
+        // GET "meal-plan?from={date}&to={date}"
+        .or(meal_plan
+            .and(warp::get())
+            .and(warp::path::end())
+            .and(warp::query::<handler::MealPlanQuery>())
+            .and(with_db(db.clone()))
+            .and_then(handler::meal_plan_handler))
+        // POST "meal-plan"
+        .or(meal_plan
+            .and(warp::post())
+            .and(warp::path::end())
+            .and(warp::body::json())
+            .and(with_db(db.clone()))
+            .and_then(handler::create_meal_plan_entry_handler))
+        // PUT "meal-plan/{id}"
+        .or(meal_plan
+            .and(warp::put())
+            .and(warp::path::param())
+            .and(warp::path::end())
+            .and(warp::body::json())
+            .and(with_db(db.clone()))
+            .and_then(handler::edit_meal_plan_entry_handler))
+        // DELETE "meal-plan/{id}"
+        .or(meal_plan
+            .and(warp::delete())
+            .and(warp::path::param())
+            .and(warp::path::end())
+            .and(with_db(db.clone()))
+            .and_then(handler::delete_meal_plan_entry_handler));
+
+    let ingredient = warp::path("ingredient");
+
+    // POST "ingredient/parse"
+    let ingredient_routes = ingredient
+        .and(warp::path("parse"))
+        .and(warp::post())
+        .and(warp::body::content_length_limit(MAX_TEXT_BODY_SIZE))
+        .and(warp::body::bytes())
+        .and_then(handler::parse_ingredients_handler);
+
+    // POST "cookbook/pdf" with {"title": ..., "recipe_ids": [...]}
+    let cookbook_routes = warp::path("cookbook")
+        .and(warp::path("pdf"))
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(warp::body::content_length_limit(MAX_TEXT_BODY_SIZE))
+        .and(warp::body::json())
+        .and(with_db(db.clone()))
+        .and_then(handler::cookbook_pdf_handler);
+
+    let routes = recipe_import_routes
+        .or(recipe_routes)
+        .or(price_routes)
+        .or(pantry_routes)
+        .or(catalogue_routes)
+        .or(shopping_list_routes)
+        .or(meal_plan_routes)
+        .or(ingredient_routes)
+        .or(cookbook_routes)
         .with(
             warp::cors()
                 .allow_any_origin()
                 .allow_header(HeaderName::from_lowercase(b"content-type").unwrap())
-                .allow_method(Method::POST),
+                .allow_methods(vec![Method::POST, Method::PUT, Method::DELETE]),
         )
         .recover(error::handle_rejection);
 
@@ -73,3 +451,88 @@ async fn main() -> Result<()> {
 fn with_db(db: DB) -> impl Filter<Extract = (DB,), Error = Infallible> + Clone {
     warp::any().map(move || db.clone())
 }
+
+fn with_nutrients(
+    nutrients: Arc<NutrientTable>,
+) -> impl Filter<Extract = (Arc<NutrientTable>,), Error = Infallible> + Clone {
+    warp::any().map(move || nutrients.clone())
+}
+
+fn with_allergens(
+    allergens: Arc<AllergenRules>,
+) -> impl Filter<Extract = (Arc<AllergenRules>,), Error = Infallible> + Clone {
+    warp::any().map(move || allergens.clone())
+}
+
+fn with_aisles(
+    aisles: Arc<Aisles>,
+) -> impl Filter<Extract = (Arc<Aisles>,), Error = Infallible> + Clone {
+    warp::any().map(move || aisles.clone())
+}
+
+fn with_densities(
+    densities: Arc<Densities>,
+) -> impl Filter<Extract = (Arc<Densities>,), Error = Infallible> + Clone {
+    warp::any().map(move || densities.clone())
+}
+
+fn load_nutrient_table() -> Result<NutrientTable> {
+    match std::env::var(NUTRIENT_TABLE_VAR) {
+        Ok(path) => {
+            let csv = std::fs::read_to_string(&path)
+                .map_err(|e| error::Error::NutrientTableError(format!("{}: {}", path, e)))?;
+            NutrientTable::from_csv(&csv)
+                .map_err(|e| error::Error::NutrientTableError(format!("{}: {}", path, e)))
+        }
+        Err(_) => Ok(NutrientTable::bundled()),
+    }
+}
+
+fn load_allergen_rules() -> Result<AllergenRules> {
+    match std::env::var(ALLERGEN_RULES_VAR) {
+        Ok(path) => {
+            let yaml = std::fs::read_to_string(&path)
+                .map_err(|e| error::Error::AllergenRulesError(format!("{}: {}", path, e)))?;
+            AllergenRules::from_yaml(&yaml)
+                .map_err(|e| error::Error::AllergenRulesError(format!("{}: {}", path, e)))
+        }
+        Err(_) => Ok(AllergenRules::bundled()),
+    }
+}
+
+fn load_diet_taxonomy() -> Result<DietTaxonomy> {
+    match std::env::var(DIET_TAXONOMY_VAR) {
+        Ok(path) => {
+            let yaml = std::fs::read_to_string(&path)
+                .map_err(|e| error::Error::DietTaxonomyError(format!("{}: {}", path, e)))?;
+            DietTaxonomy::from_yaml(&yaml)
+                .map_err(|e| error::Error::DietTaxonomyError(format!("{}: {}", path, e)))
+        }
+        Err(_) => Ok(DietTaxonomy::bundled()),
+    }
+}
+
+fn load_aisles() -> Result<Aisles> {
+    match std::env::var(AISLES_VAR) {
+        Ok(path) => {
+            let yaml = std::fs::read_to_string(&path)
+                .map_err(|e| error::Error::AislesError(format!("{}: {}", path, e)))?;
+            Aisles::from_yaml(&yaml)
+                .map_err(|e| error::Error::AislesError(format!("{}: {}", path, e)))
+        }
+        Err(_) => Ok(Aisles::bundled()),
+    }
+}
+
+// Unlike the other files, a densities file only adds to the bundled table
+fn load_densities() -> Result<Densities> {
+    let mut densities = Densities::bundled();
+    if let Ok(path) = std::env::var(DENSITIES_VAR) {
+        let yaml = std::fs::read_to_string(&path)
+            .map_err(|e| error::Error::DensitiesError(format!("{}: {}", path, e)))?;
+        let own = Densities::from_yaml(&yaml)
+            .map_err(|e| error::Error::DensitiesError(format!("{}: {}", path, e)))?;
+        densities.extend(&own);
+    }
+    Ok(densities)
+}
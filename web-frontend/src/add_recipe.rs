@@ -11,6 +11,7 @@ use yew::services::fetch::{FetchService, FetchTask, Request, Response};
 use yew::services::ConsoleService;
 use yew_router::{route::Route, service::RouteService, Switch};
 
+use rcp_shared_rs_code::models::duration::Duration;
 use rcp_shared_rs_code::models::ingredient::Amount;
 use rcp_shared_rs_code::models::ingredient::Ingredient;
 use rcp_shared_rs_code::models::oven_fan_value::OvenFanValue;
@@ -19,6 +20,9 @@ use rcp_shared_rs_code::models::recipe_request::RecipeRequest;
 use rcp_shared_rs_code::models::step::Step;
 use rcp_shared_rs_code::models::temperature::Temperature;
 use rcp_shared_rs_code::models::temperature_unit::TemperatureUnit;
+use rcp_shared_rs_code::models::unit::Unit;
+use rcp_shared_rs_code::quantity::Quantity;
+use rcp_shared_rs_code::validation::ValidationError;
 
 use crate::app::RouteServiceType;
 use crate::app::RouteType;
@@ -40,7 +44,10 @@ pub struct State {
     recipe_data: RecipeRequest,
     post_recipes_task: Option<FetchTask>,
     post_response_display_msg: Option<String>, // TODO: Vector with multiple messages
-    selected_yield_idx: usize,
+    // Free text ingredient list, one ingredient per line
+    ingredient_lines: String,
+    // Shown next to the fields after a submit attempt
+    validation_errors: Vec<ValidationError>,
 }
 
 impl State {
@@ -51,9 +58,19 @@ impl State {
             post_recipes_task: None,
             post_response_display_msg: None,
             recipe_data,
-            selected_yield_idx: 0,
+            ingredient_lines: String::new(),
+            validation_errors: vec![],
         }
     }
+
+    fn yield_count(&self) -> usize {
+        self.recipe_data.yields.as_ref().map_or(0, |yields| yields.len())
+    }
+
+    // Every ingredient needs one amount per yield. A recipe without yields has one implicit yield, so there is always an amount.
+    fn amount_count(&self) -> usize {
+        self.yield_count().max(1)
+    }
 }
 
 pub enum Msg {
@@ -62,6 +79,10 @@ pub enum Msg {
     OnReceivePostResponse(Result<CreateRecipeResponse, anyhow::Error>),
     OnRecipeNameInputChanged(String),
     OnRecipeOvenTimeInputChanged(String),
+    OnRecipePrepTimeInputChanged(String),
+    OnRecipeCookTimeInputChanged(String),
+    OnRecipeRestTimeInputChanged(String),
+    OnRecipeTotalTimeInputChanged(String),
     OnRecipeNotesInputChanged(String),
     OnRecipeOvenFanSelectChanged(String),
     OnRecipeOvenTempAmountInputChanged(String), //TODO
@@ -71,6 +92,8 @@ pub enum Msg {
     OnRecipeSourceUrlInputChanged(String), //TODO
     // INGREDIENTS
     OnAddIngredient,
+    OnIngredientLinesInputChanged(String),
+    OnAddIngredientLines,
     OnIngredientNameInputChanged(usize, String),
     // (ingredient index, amount index, amount.amount value)
     OnIngredientAmountInputChanged(usize, usize, String),
@@ -128,6 +151,11 @@ impl Component for AddRecipeComp {
         match msg {
             Msg::Noop => false,
             Msg::OnAddRecipe => {
+                self.state.validation_errors = self.state.recipe_data.validate();
+                if !self.state.validation_errors.is_empty() {
+                    return true;
+                }
+
                 let task: FetchTask = self.build_fetch_recipe_task();
 
                 // 4. store the task so it isn't canceled immediately
@@ -162,9 +190,24 @@ impl Component for AddRecipeComp {
                 self.state.recipe_data.recipe_name = Some(recipe_name);
                 true
             }
-            Msg::OnRecipeOvenTimeInputChanged(oven_time) => {
-                let oven_time: f64 = oven_time.parse::<f64>().unwrap_or(0.0f64);
-                self.state.recipe_data.oven_time = Some(oven_time);
+            Msg::OnRecipeOvenTimeInputChanged(time_str) => {
+                update_duration(&mut self.state.recipe_data.oven_time, &time_str);
+                true
+            }
+            Msg::OnRecipePrepTimeInputChanged(time_str) => {
+                update_duration(&mut self.state.recipe_data.prep_time, &time_str);
+                true
+            }
+            Msg::OnRecipeCookTimeInputChanged(time_str) => {
+                update_duration(&mut self.state.recipe_data.cook_time, &time_str);
+                true
+            }
+            Msg::OnRecipeRestTimeInputChanged(time_str) => {
+                update_duration(&mut self.state.recipe_data.rest_time, &time_str);
+                true
+            }
+            Msg::OnRecipeTotalTimeInputChanged(time_str) => {
+                update_duration(&mut self.state.recipe_data.total_time, &time_str);
                 true
             }
             Msg::OnRecipeNotesInputChanged(notes) => {
@@ -176,33 +219,37 @@ impl Component for AddRecipeComp {
                 true
             }
             Msg::OnRecipeOvenTempAmountInputChanged(amount_str) => {
-                let amount: f64 = amount_str.parse().unwrap_or(0.0f64);
-                self.state.recipe_data.oven_temp = self
-                    .state
-                    .recipe_data
-                    .oven_temp
-                    .as_ref()
-                    .map_or(None, |temp| {
-                        Some(Temperature {
-                            amount,
-                            unit: temp.unit.clone(),
-                        })
-                    });
+                self.state
+                    .validation_errors
+                    .retain(|e| e.field != "oven_temp.amount");
+                if amount_str.trim().is_empty() {
+                    self.state.recipe_data.oven_temp = None;
+                } else {
+                    // Gas marks can be fractions like 1/2
+                    match Quantity::from_str(&amount_str) {
+                        Ok(quantity) => {
+                            self.state
+                                .recipe_data
+                                .oven_temp
+                                .get_or_insert_with(Temperature::default)
+                                .amount = quantity.value;
+                        }
+                        // A typo like "18O" keeps the last valid temperature, the re-render restores it
+                        Err(_) => self.state.validation_errors.push(ValidationError::new(
+                            "oven_temp.amount",
+                            &format!("\"{}\" is not a temperature", amount_str.trim()),
+                        )),
+                    }
+                }
                 true
             }
             Msg::OnRecipeOvenTempUnitInputChanged(unit_str) => {
-                let unit = TemperatureUnit::from_str(&unit_str).unwrap();
-                self.state.recipe_data.oven_temp = self
-                    .state
+                let unit = TemperatureUnit::from_str(&unit_str).unwrap_or_default();
+                self.state
                     .recipe_data
                     .oven_temp
-                    .as_ref()
-                    .map_or(None, |temp| {
-                        Some(Temperature {
-                            amount: temp.amount,
-                            unit,
-                        })
-                    });
+                    .get_or_insert_with(Temperature::default)
+                    .unit = unit;
                 true
             }
             Msg::OnRecipeSourceUrlInputChanged(source_url_str) => {
@@ -215,6 +262,18 @@ impl Component for AddRecipeComp {
                 } else {
                     self.state.recipe_data.yields = Some(vec![Yield::new()]);
                 }
+                // The first yield takes over the amounts of the implicit one
+                let amount_count = self.state.amount_count();
+                if let Some(ingredients) = self.state.recipe_data.ingredients.as_mut() {
+                    for ing in ingredients.iter_mut() {
+                        ing.ingredient
+                            .amounts
+                            .resize_with(amount_count, Amount::new);
+                        for sub in ing.substitutions.iter_mut() {
+                            sub.amounts.resize_with(amount_count, Amount::new);
+                        }
+                    }
+                }
                 true
             }
             Msg::OnAddStep => {
@@ -226,11 +285,52 @@ impl Component for AddRecipeComp {
                 true
             }
             Msg::OnAddIngredient => {
+                let mut ingredient = Ingredient::new();
+                ingredient.ingredient.amounts = (0..self.state.amount_count())
+                    .map(|_| Amount::new())
+                    .collect();
+
                 if let Some(ingredients) = self.state.recipe_data.ingredients.as_mut() {
-                    ingredients.push(Ingredient::new());
+                    ingredients.push(ingredient);
                 } else {
-                    self.state.recipe_data.ingredients = Some(vec![Ingredient::new()]);
+                    self.state.recipe_data.ingredients = Some(vec![ingredient]);
+                }
+                true
+            }
+            Msg::OnIngredientLinesInputChanged(lines) => {
+                self.state.ingredient_lines = lines;
+                false
+            }
+            Msg::OnAddIngredientLines => {
+                let amount_count = self.state.amount_count();
+                let mut unparsed_lines = vec![];
+
+                for line in self.state.ingredient_lines.lines() {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    match Ingredient::from_str(line) {
+                        Ok(mut ingredient) => {
+                            // The line only has one amount, start every yield with it
+                            let amount = ingredient.ingredient.amounts[0].clone();
+                            ingredient.ingredient.amounts = vec![amount; amount_count];
+                            for sub in ingredient.substitutions.iter_mut() {
+                                let amount = sub.amounts[0].clone();
+                                sub.amounts = vec![amount; amount_count];
+                            }
+
+                            self.state
+                                .recipe_data
+                                .ingredients
+                                .get_or_insert_with(Vec::new)
+                                .push(ingredient);
+                        }
+                        Err(_) => unparsed_lines.push(line.to_string()),
+                    }
                 }
+
+                // Keep what couldn't be parsed so it can be corrected
+                self.state.ingredient_lines = unparsed_lines.join("\n");
                 true
             }
             Msg::OnIngredientNameInputChanged(idx, ing_name_str) => {
@@ -250,8 +350,10 @@ impl Component for AddRecipeComp {
                     if ing_idx < ingredients.len() {
                         let ing = &mut ingredients[ing_idx].ingredient;
                         if amount_idx < ing.amounts.len() {
-                            let value: f64 = amount_str.parse().unwrap_or(0.0f64);
-                            ing.amounts[amount_idx].amount = value;
+                            // On invalid input the re-render restores the last valid amount
+                            if let Ok(quantity) = Quantity::from_str(&amount_str) {
+                                ing.amounts[amount_idx].set_quantity(quantity);
+                            }
                             true
                         } else {
                             false
@@ -268,7 +370,7 @@ impl Component for AddRecipeComp {
                     if ing_idx < ingredients.len() {
                         let ing = &mut ingredients[ing_idx].ingredient;
                         if amount_idx < ing.amounts.len() {
-                            ing.amounts[amount_idx].unit = unit_str;
+                            ing.amounts[amount_idx].unit = Unit::from_str(&unit_str).unwrap_or_default();
                             true
                         } else {
                             false
@@ -423,6 +525,22 @@ impl Component for AddRecipeComp {
                         if yields.is_empty() {
                             self.state.recipe_data.yields = None;
                         }
+                        // Drop the amount column belonging to the removed yield, unless it is the last one,
+                        // which stays for the implicit yield
+                        if let Some(ingredients) = self.state.recipe_data.ingredients.as_mut() {
+                            for ing in ingredients.iter_mut() {
+                                if yield_idx < ing.ingredient.amounts.len()
+                                    && ing.ingredient.amounts.len() > 1
+                                {
+                                    ing.ingredient.amounts.remove(yield_idx);
+                                }
+                                for sub in ing.substitutions.iter_mut() {
+                                    if yield_idx < sub.amounts.len() && sub.amounts.len() > 1 {
+                                        sub.amounts.remove(yield_idx);
+                                    }
+                                }
+                            }
+                        }
                         true
                     } else {
                         false
@@ -480,6 +598,7 @@ impl Component for AddRecipeComp {
 
                 <form class="ui form">
                     { self.view_recipe_name_input() }
+                    { self.view_times_input() }
                     { self.view_oven_time_input() }
                     { self.view_oven_temp_input() }
                     { self.view_oven_fan_input() }
@@ -520,6 +639,20 @@ impl AddRecipeComp {
         task
     }
 
+    // Errors of the field and everything nested in it, e.g. all errors of "ingredients[0]"
+    fn view_field_errors(&self, field: &str) -> Html {
+        let nested = format!("{}.", field);
+        let indexed = format!("{}[", field);
+        html! {
+            for self.state.validation_errors
+                .iter()
+                .filter(|e| e.field == field || e.field.starts_with(&nested) || e.field.starts_with(&indexed))
+                .map(|e| html! {
+                    <div class="ui pointing red basic label">{ &e.message }</div>
+                })
+        }
+    }
+
     fn view_submit_recipe_button(&self) -> Html {
         html! {
             <button class="ui button" type="submit" onclick=self.link.callback(|_| Msg::OnAddRecipe)>
@@ -529,27 +662,40 @@ impl AddRecipeComp {
     }
 
     fn view_oven_temp_input(&self) -> Html {
-        match &self.state.recipe_data.oven_temp {
-            None => html! {},
-            Some(temp) => {
-                let on_oven_temp_amount_input = self
-                    .link
-                    .callback(|e: InputData| Msg::OnRecipeOvenTempAmountInputChanged(e.value));
-
-                html! {
-
-                    <div class="field">
-                        <label for="oven_temp_amount_input">{"Oven Temp."}</label>
-                        <input
-                            type="number"
-                            id="oven_temp_amount_input"
-                            value=temp.amount,
-                            oninput=on_oven_temp_amount_input,
-                            />
+        let temp = self.state.recipe_data.oven_temp.as_ref();
+        let on_oven_temp_amount_input = self.link.callback(|e: ChangeData| match e {
+            ChangeData::Value(amount_str) => Msg::OnRecipeOvenTempAmountInputChanged(amount_str),
+            _ => Msg::Noop,
+        });
+        let unit = temp.map_or(TemperatureUnit::default(), |t| t.unit.clone());
 
-                    </div>
-                }
-            }
+        html! {
+            <div class="fields">
+                <div class="field">
+                    <label for="oven_temp_amount_input">{"Oven Temp."}</label>
+                    <input
+                        type="text"
+                        id="oven_temp_amount_input"
+                        value=temp.map_or("".to_string(), |t| Quantity::new(t.amount).to_string()),
+                        onchange=on_oven_temp_amount_input,
+                        />
+                </div>
+                <div class="field">
+                    <label for="oven_temp_unit_select">{"Unit"}</label>
+                    <select
+                        id="oven_temp_unit_select",
+                        onchange=self.link.callback(|e: ChangeData| Msg::OnRecipeOvenTempUnitInputChanged(match e {
+                            ChangeData::Select(sel_element) => sel_element.value(),
+                            _ => "".to_string(),
+                        }))
+                        >
+                        <option value="Celsius" selected={unit == TemperatureUnit::Celsius}>{"°C"}</option>
+                        <option value="Fahrenheit" selected={unit == TemperatureUnit::Fahrenheit}>{"°F"}</option>
+                        <option value="Gas Mark" selected={unit == TemperatureUnit::GasMark}>{"Gas Mark"}</option>
+                    </select>
+                </div>
+                { self.view_field_errors("oven_temp") }
+            </div>
         }
     }
 
@@ -594,17 +740,46 @@ impl AddRecipeComp {
     }
 
     fn view_oven_time_input(&self) -> Html {
+        self.view_duration_input(
+            "oven_time_input",
+            "Oven Time",
+            &self.state.recipe_data.oven_time,
+            Msg::OnRecipeOvenTimeInputChanged,
+        )
+    }
+
+    fn view_times_input(&self) -> Html {
+        let data = &self.state.recipe_data;
+        html! {
+            <div class="four fields">
+                { self.view_duration_input("prep_time_input", "Prep Time", &data.prep_time, Msg::OnRecipePrepTimeInputChanged) }
+                { self.view_duration_input("cook_time_input", "Cook Time", &data.cook_time, Msg::OnRecipeCookTimeInputChanged) }
+                { self.view_duration_input("rest_time_input", "Rest Time", &data.rest_time, Msg::OnRecipeRestTimeInputChanged) }
+                { self.view_duration_input("total_time_input", "Total Time", &data.total_time, Msg::OnRecipeTotalTimeInputChanged) }
+            </div>
+        }
+    }
+
+    // Accepts "45" (minutes), "1:30", "1 h 30 min" or ISO 8601
+    fn view_duration_input(
+        &self,
+        id: &str,
+        label: &str,
+        duration: &Option<Duration>,
+        msg: fn(String) -> Msg,
+    ) -> Html {
         html! {
             <div class="field">
-                <label for="oven_time_input">{"Oven Time"}</label>
+                <label for=id>{label}</label>
                 <input
-                    type="number"
-                    id="oven_time_input"
-                    value=match &self.state.recipe_data.oven_time {
-                        None => "".to_string(),
-                        Some(t) => t.to_string(),
-                    },
-                    oninput=self.link.callback(|e: InputData| Msg::OnRecipeOvenTimeInputChanged(e.value))
+                    type="text"
+                    id=id
+                    placeholder="e.g. 1 h 30 min"
+                    value=duration.map_or("".to_string(), |d| d.to_string()),
+                    onchange=self.link.callback(move |e: ChangeData| match e {
+                        ChangeData::Value(time_str) => msg(time_str),
+                        _ => Msg::Noop,
+                    })
                     />
             </div>
         }
@@ -623,6 +798,7 @@ impl AddRecipeComp {
                     },
                     oninput=self.link.callback(|e: InputData| Msg::OnRecipeNameInputChanged(e.value))
                     />
+                { self.view_field_errors("recipe_name") }
             </div>
         }
     }
@@ -642,6 +818,7 @@ impl AddRecipeComp {
                         />
                 </label>
             </div>
+            { self.view_field_errors(&format!("steps[{}]", idx)) }
 
             // Step notes
             {
@@ -694,18 +871,29 @@ impl AddRecipeComp {
     }
 
     fn view_ingredient_amount_input(&self, idx: usize, (pos, entry): (usize, &Amount)) -> Html {
+        // Label each amount column with the yield it belongs to
+        let yield_label = self
+            .state
+            .recipe_data
+            .yields
+            .as_ref()
+            .and_then(|yields| yields.get(pos))
+            .map_or("".to_string(), |y| format!(" ({} {})", y.amount, y.unit));
+
         html! {<>
             <div class="required two wide field">
                 // amount
                 <label>
-                    {"Amount"}
+                    { format!("Amount{}", yield_label) }
                 </label>
                 <input
-                    type="number",
-                    value=&entry.amount,
-                    oninput=self.link.callback(move |e: InputData|
-                        Msg::OnIngredientAmountInputChanged(idx, pos, e.value)
-                    )
+                    type="text",
+                    placeholder="e.g. 1 1/2, ¾, 2-3",
+                    value=entry.quantity().to_string(),
+                    onchange=self.link.callback(move |e: ChangeData| match e {
+                        ChangeData::Value(amount_str) => Msg::OnIngredientAmountInputChanged(idx, pos, amount_str),
+                        _ => Msg::Noop,
+                    })
                     />
             </div>
             // unit
@@ -713,12 +901,15 @@ impl AddRecipeComp {
                 <label>
                     {"Unit"}
                 </label>
+                // Parsing on every keystroke would rewrite "gram" to "g" while typing
                 <input
                     type="text"
-                    value=&entry.unit,
-                    oninput=self.link.callback(move |e: InputData|
-                        Msg::OnIngredientAmountUnitInputChanged(idx, pos, e.value)
-                    )
+                    placeholder="e.g. g, cups, tbsp",
+                    value=entry.unit.to_string(),
+                    onchange=self.link.callback(move |e: ChangeData| match e {
+                        ChangeData::Value(unit_str) => Msg::OnIngredientAmountUnitInputChanged(idx, pos, unit_str),
+                        _ => Msg::Noop,
+                    })
                     />
             </div>
         </>}
@@ -739,22 +930,12 @@ impl AddRecipeComp {
         let ing = &i.ingredient;
         let subs = &i.substitutions;
 
-        // let amounts_html =
-        //     ing.amounts
-        //         .iter()
-        //         .enumerate()
-        //         .map(|(pos, entry)| { self.view_ingredient_amount_input(idx, (pos, entry)) }).collect::<Html>();
-        let amount_html = if self.state.selected_yield_idx < ing.amounts.len() {
-            self.view_ingredient_amount_input(
-                idx,
-                (
-                    self.state.selected_yield_idx,
-                    &ing.amounts[self.state.selected_yield_idx],
-                ),
-            )
-        } else {
-            html! {}
-        };
+        let amounts_html = ing
+            .amounts
+            .iter()
+            .enumerate()
+            .map(|(pos, entry)| self.view_ingredient_amount_input(idx, (pos, entry)))
+            .collect::<Html>();
 
         let processing_html = ing
             .processing
@@ -779,8 +960,9 @@ impl AddRecipeComp {
                         />
                     </div>
 
-                    { amount_html }
+                    { amounts_html }
                 </div>
+                { self.view_field_errors(&format!("ingredients[{}]", idx)) }
 
                 // processing
                 { processing_html }
@@ -837,9 +1019,26 @@ impl AddRecipeComp {
             <h3>{"Ingredients"}</h3>
             { self.view_add_btn("Add Ingredient", |_| Msg::OnAddIngredient) }
             { ingredients_list }
+            { self.view_ingredient_lines_input() }
         </>}
     }
 
+    fn view_ingredient_lines_input(&self) -> Html {
+        html! {
+            <div class="field">
+                <label for="ingredient_lines_input">{"Paste ingredients, one per line"}</label>
+                <textarea
+                    rows=4,
+                    id="ingredient_lines_input",
+                    placeholder="e.g. 2 cups all-purpose flour, sifted (or cake flour)",
+                    value=&self.state.ingredient_lines,
+                    oninput=self.link.callback(|e: InputData| Msg::OnIngredientLinesInputChanged(e.value))
+                    />
+                { self.view_add_btn("Add Ingredients", |_| Msg::OnAddIngredientLines) }
+            </div>
+        }
+    }
+
     fn view_add_btn<P: 'static>(&self, text: &str, cb: P) -> Html
     where
         P: Fn(MouseEvent) -> Msg,
@@ -866,7 +1065,7 @@ impl AddRecipeComp {
 
     fn view_yield_input(&self, (idx, y): (usize, &Yield)) -> Html {
         html! {
-            <>
+            <div class="fields">
                 // Amount
                 <div class="field">
                     <input
@@ -884,7 +1083,10 @@ impl AddRecipeComp {
                         oninput=self.link.callback(move |e: InputData| Msg::OnYieldUnitInputChanged(idx, e.value))
                         />
                 </div>
-            </>
+
+                { self.view_remove_btn("Remove Yield", move |_| Msg::OnRemoveYield(idx)) }
+                { self.view_field_errors(&format!("yields[{}]", idx)) }
+            </div>
         }
     }
 
@@ -898,9 +1100,22 @@ impl AddRecipeComp {
                         .map(|(pos, entry)| self.view_yield_input((pos, entry)))
                 };
 
-                yields_html
+                html! {<>
+                    <h3>{"Yields"}</h3>
+                    { yields_html }
+                    { self.view_add_btn("Add Yield", |_| Msg::OnAddYield) }
+                </>}
             }
             None => self.view_add_btn("Add Yields", |_| Msg::OnAddYield),
         }
     }
 }
+
+// Clears the duration for empty input and keeps the old value if the input can't be parsed
+fn update_duration(duration: &mut Option<Duration>, time_str: &str) {
+    if time_str.trim().is_empty() {
+        *duration = None;
+    } else if let Ok(parsed) = Duration::from_str(time_str) {
+        *duration = Some(parsed);
+    }
+}
@@ -3,9 +3,12 @@ use yew_router::prelude::*;
 use yew_router::{route::Route, service::RouteService, Switch};
 
 use crate::add_recipe::AddRecipeComp;
+use crate::meal_plan::MealPlanComp;
 use crate::recipe::RecipeComp;
 use crate::recipe_list::RecipeList;
 use crate::reroute_agent::RerouteAgent;
+use crate::shopping_list::ShoppingListComp;
+use crate::shopping_lists::ShoppingListsComp;
 
 pub type RouterStateType = ();
 pub type RouteType = Route<RouterStateType>;
@@ -30,6 +33,15 @@ pub enum RecipesRoute {
     AllRecipes,
 }
 
+#[derive(Switch, Debug, Clone, PartialEq)]
+pub enum ShoppingListsRoute {
+    #[to = "/{id}"]
+    ViewShoppingList { id: String },
+
+    #[to = ""]
+    AllShoppingLists,
+}
+
 #[derive(Switch, Debug, Clone)]
 pub enum AppRoute {
     // #[to = "/profile/{id}"]
@@ -37,6 +49,12 @@ pub enum AppRoute {
     #[to = "/recipes{*:rest}"]
     Recipes(RecipesRoute),
 
+    #[to = "/shopping-lists{*:rest}"]
+    ShoppingLists(ShoppingListsRoute),
+
+    #[to = "/meal-plan"]
+    MealPlan,
+
     #[to = "/"]
     Home,
 }
@@ -129,6 +147,17 @@ impl App {
                     <RecipeComp recipe_uuid=id />
                 </>},
             },
+            AppRoute::ShoppingLists(shopping_lists_route) => match shopping_lists_route {
+                ShoppingListsRoute::AllShoppingLists => html! {<>
+                    <ShoppingListsComp />
+                </>},
+                ShoppingListsRoute::ViewShoppingList { id } => html! {<>
+                    <ShoppingListComp shopping_list_uuid=id />
+                </>},
+            },
+            AppRoute::MealPlan => html! {<>
+                <MealPlanComp />
+            </>},
             AppRoute::Home => html! {<>
                 { "Home" }
             </>},
@@ -160,6 +189,16 @@ impl App {
             _ => item,
         };
 
+        let shopping_lists_link_classes = match switch {
+            AppRoute::ShoppingLists(_) => active_item,
+            _ => item,
+        };
+
+        let meal_plan_link_classes = match switch {
+            AppRoute::MealPlan => active_item,
+            _ => item,
+        };
+
         html! {<>
             <a
                 class=home_link_classes,
@@ -179,6 +218,18 @@ impl App {
                 {"Add Recipe"}
             </a>
 
+            <a
+                class=shopping_lists_link_classes,
+                onclick=&self.change_route("/shopping-lists/".to_string())>
+                {"Shopping Lists"}
+            </a>
+
+            <a
+                class=meal_plan_link_classes,
+                onclick=&self.change_route("/meal-plan".to_string())>
+                {"Meal Plan"}
+            </a>
+
         </>}
     }
 
@@ -6,9 +6,12 @@
 
 mod add_recipe;
 mod app;
+mod meal_plan;
 mod recipe;
 mod recipe_list;
 mod reroute_agent;
+mod shopping_list;
+mod shopping_lists;
 
 use yew::html::{Component, ComponentLink};
 
@@ -0,0 +1,517 @@
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use yew::agent::{Dispatched, Dispatcher};
+use yew::events::ChangeData;
+use yew::format::{Json, Nothing, Text};
+use yew::prelude::*;
+use yew::services::fetch::{FetchService, FetchTask, Request, Response};
+
+use rcp_shared_rs_code::meal_planning::PrepTask;
+use rcp_shared_rs_code::models::meal_plan::{MealPlanEntry, MealSlot};
+use rcp_shared_rs_code::models::recipe::Recipe;
+
+use crate::reroute_agent::{RerouteAgent, RerouteRequestMsg};
+use crate::shopping_lists::CreateShoppingListResponse;
+
+pub enum Msg {
+    ReceiveFetchMealPlanResponse(Result<Vec<MealPlanEntry>, anyhow::Error>),
+    ReceiveFetchRecipesResponse(Result<Vec<Recipe>, anyhow::Error>),
+    OnPreviousWeek,
+    OnNextWeek,
+    OnDateInputChanged(String),
+    OnMealSlotSelectChanged(String),
+    OnRecipeSelectChanged(String),
+    OnServingsInputChanged(String),
+    OnAddEntry,
+    // (whether the entry was stored)
+    ReceivePostEntryResponse(bool),
+    // (meal plan uuid)
+    OnDeleteEntry(String),
+    // (meal plan uuid, whether the entry was removed)
+    ReceiveDeleteEntryResponse(String, bool),
+    OnCreateShoppingList,
+    ReceiveShoppingListResponse(Result<CreateShoppingListResponse, anyhow::Error>),
+    OnShowPrepSchedule,
+    ReceivePrepScheduleResponse(Result<Vec<PrepTask>, anyhow::Error>),
+}
+
+/* A week of planned meals with a form to plan more, and the shopping list and prep schedule of the week. */
+pub struct MealPlanComp {
+    link: ComponentLink<Self>,
+    reroute_agent: Dispatcher<RerouteAgent>,
+    // The monday of the week shown
+    week_start: NaiveDate,
+    entries: Option<Vec<MealPlanEntry>>,
+    recipes: Option<Vec<Recipe>>,
+    prep_schedule: Option<Vec<PrepTask>>,
+    fetch_meal_plan_task: Option<FetchTask>,
+    fetch_recipes_task: Option<FetchTask>,
+    post_entry_task: Option<FetchTask>,
+    delete_entry_tasks: BTreeMap<String, FetchTask>,
+    post_shopping_list_task: Option<FetchTask>,
+    fetch_prep_schedule_task: Option<FetchTask>,
+    new_entry: MealPlanEntry,
+    error_msg: Option<String>,
+}
+
+impl Component for MealPlanComp {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let today = Local::today().naive_local();
+        let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+        let fetch_meal_plan_task = MealPlanComp::build_fetch_meal_plan_task(week_start, &link);
+        let fetch_recipes_task = MealPlanComp::build_fetch_recipes_task(&link);
+
+        Self {
+            link,
+            reroute_agent: RerouteAgent::dispatcher(),
+            week_start,
+            entries: None,
+            recipes: None,
+            prep_schedule: None,
+            fetch_meal_plan_task: Some(fetch_meal_plan_task),
+            fetch_recipes_task: Some(fetch_recipes_task),
+            post_entry_task: None,
+            delete_entry_tasks: BTreeMap::new(),
+            post_shopping_list_task: None,
+            fetch_prep_schedule_task: None,
+            new_entry: MealPlanEntry {
+                date: today,
+                ..MealPlanEntry::default()
+            },
+            error_msg: None,
+        }
+    }
+
+    fn change(&mut self, _: Self::Properties) -> bool {
+        false
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::ReceiveFetchMealPlanResponse(response) => {
+                match response {
+                    Ok(entries) => self.entries = Some(entries),
+                    Err(error) => self.error_msg = Some(error.to_string()),
+                }
+                self.fetch_meal_plan_task = None;
+                true
+            }
+            Msg::ReceiveFetchRecipesResponse(response) => {
+                match response {
+                    Ok(recipes) => {
+                        if let Some(recipe) = recipes.first() {
+                            self.new_entry.recipe_uuid = recipe.recipe_uuid.clone();
+                        }
+                        self.recipes = Some(recipes);
+                    }
+                    Err(error) => self.error_msg = Some(error.to_string()),
+                }
+                self.fetch_recipes_task = None;
+                true
+            }
+            Msg::OnPreviousWeek => {
+                self.change_week(self.week_start - Duration::weeks(1));
+                true
+            }
+            Msg::OnNextWeek => {
+                self.change_week(self.week_start + Duration::weeks(1));
+                true
+            }
+            Msg::OnDateInputChanged(date_str) => {
+                if let Ok(date) = NaiveDate::from_str(date_str.trim()) {
+                    self.new_entry.date = date;
+                }
+                false
+            }
+            Msg::OnMealSlotSelectChanged(slot_str) => {
+                if let Ok(slot) = MealSlot::from_str(&slot_str) {
+                    self.new_entry.meal_slot = slot;
+                }
+                false
+            }
+            Msg::OnRecipeSelectChanged(recipe_uuid) => {
+                self.new_entry.recipe_uuid = recipe_uuid;
+                false
+            }
+            Msg::OnServingsInputChanged(servings_str) => {
+                if let Ok(servings) = servings_str.trim().parse::<f64>() {
+                    self.new_entry.servings = servings;
+                }
+                false
+            }
+            Msg::OnAddEntry => {
+                if self.new_entry.recipe_uuid.is_empty() {
+                    self.error_msg = Some("Pick a recipe".to_string());
+                    return true;
+                }
+                self.error_msg = None;
+                self.post_entry_task = Some(self.build_post_entry_task());
+                true
+            }
+            Msg::ReceivePostEntryResponse(stored) => {
+                self.post_entry_task = None;
+                if stored {
+                    self.change_week(self.week_start);
+                } else {
+                    self.error_msg = Some("Error planning meal".to_string());
+                }
+                true
+            }
+            Msg::OnDeleteEntry(meal_plan_uuid) => {
+                let task = self.build_delete_entry_task(&meal_plan_uuid);
+                self.delete_entry_tasks.insert(meal_plan_uuid, task);
+                false
+            }
+            Msg::ReceiveDeleteEntryResponse(meal_plan_uuid, removed) => {
+                self.delete_entry_tasks.remove(&meal_plan_uuid);
+                if removed {
+                    if let Some(entries) = &mut self.entries {
+                        entries.retain(|e| e.meal_plan_uuid != meal_plan_uuid);
+                    }
+                } else {
+                    self.error_msg = Some("Error removing meal".to_string());
+                }
+                true
+            }
+            Msg::OnCreateShoppingList => {
+                self.post_shopping_list_task = Some(self.build_post_shopping_list_task());
+                false
+            }
+            Msg::ReceiveShoppingListResponse(response) => {
+                self.post_shopping_list_task = None;
+                match response {
+                    Ok(response) => {
+                        let new_route = format!("/shopping-lists/{}", response.shopping_list_uuid);
+                        self.reroute_agent
+                            .send(RerouteRequestMsg::Reroute(new_route));
+                    }
+                    Err(_) => {
+                        self.error_msg =
+                            Some("Error creating shopping list, is a meal planned?".to_string());
+                    }
+                }
+                true
+            }
+            Msg::OnShowPrepSchedule => {
+                self.fetch_prep_schedule_task = Some(self.build_fetch_prep_schedule_task());
+                false
+            }
+            Msg::ReceivePrepScheduleResponse(response) => {
+                match response {
+                    Ok(tasks) => self.prep_schedule = Some(tasks),
+                    Err(error) => self.error_msg = Some(error.to_string()),
+                }
+                self.fetch_prep_schedule_task = None;
+                true
+            }
+        }
+    }
+
+    fn view(&self) -> Html {
+        html! {<>
+            <br/>
+            <h2>{ format!("Week of {}", self.week_start.format("%d.%m.%Y")) }</h2>
+            <div class="ui buttons">
+                <button class="ui button" onclick=self.link.callback(|_| Msg::OnPreviousWeek)>
+                    { "Previous week" }
+                </button>
+                <button class="ui button" onclick=self.link.callback(|_| Msg::OnNextWeek)>
+                    { "Next week" }
+                </button>
+            </div>
+            { self.view_week() }
+            <div class="ui buttons">
+                <button class="ui button" onclick=self.link.callback(|_| Msg::OnCreateShoppingList)>
+                    { "Shopping list for this week" }
+                </button>
+                <button class="ui button" onclick=self.link.callback(|_| Msg::OnShowPrepSchedule)>
+                    { "Prep schedule for this week" }
+                </button>
+            </div>
+            { self.view_prep_schedule() }
+            { self.view_new_entry() }
+        </>}
+    }
+}
+
+impl MealPlanComp {
+    fn week_query(week_start: NaiveDate) -> String {
+        format!("from={}&to={}", week_start, week_start + Duration::days(6))
+    }
+
+    fn change_week(&mut self, week_start: NaiveDate) {
+        self.week_start = week_start;
+        self.prep_schedule = None;
+        self.fetch_meal_plan_task = Some(MealPlanComp::build_fetch_meal_plan_task(
+            week_start, &self.link,
+        ));
+    }
+
+    fn build_fetch_meal_plan_task(week_start: NaiveDate, link: &ComponentLink<Self>) -> FetchTask {
+        let request = Request::get(format!(
+            "http://localhost:8080/meal-plan?{}",
+            MealPlanComp::week_query(week_start)
+        ))
+        .body(Nothing)
+        .expect("Could not build request.");
+
+        let callback = link.callback(
+            |response: Response<Json<Result<Vec<MealPlanEntry>, anyhow::Error>>>| {
+                let Json(data) = response.into_body();
+                Msg::ReceiveFetchMealPlanResponse(data)
+            },
+        );
+
+        FetchService::fetch(request, callback).expect("failed to start request")
+    }
+
+    fn build_fetch_recipes_task(link: &ComponentLink<Self>) -> FetchTask {
+        let request = Request::get("http://localhost:8080/recipe")
+            .body(Nothing)
+            .expect("Could not build request.");
+
+        let callback = link.callback(
+            |response: Response<Json<Result<Vec<Recipe>, anyhow::Error>>>| {
+                let Json(data) = response.into_body();
+                Msg::ReceiveFetchRecipesResponse(data)
+            },
+        );
+
+        FetchService::fetch(request, callback).expect("failed to start request")
+    }
+
+    fn build_post_entry_task(&self) -> FetchTask {
+        let post_request = Request::post("http://localhost:8080/meal-plan")
+            .header("content-type", "application/json")
+            .body(Json(&self.new_entry))
+            .expect("Could not build that request.");
+
+        let callback = self.link.callback(|response: Response<Text>| {
+            Msg::ReceivePostEntryResponse(response.status().is_success())
+        });
+
+        FetchService::fetch(post_request, callback).expect("failed to start post request")
+    }
+
+    fn build_delete_entry_task(&self, meal_plan_uuid: &str) -> FetchTask {
+        let request = Request::delete(format!(
+            "http://localhost:8080/meal-plan/{}",
+            meal_plan_uuid
+        ))
+        .body(Nothing)
+        .expect("Could not build that request.");
+
+        let meal_plan_uuid = meal_plan_uuid.to_string();
+        let callback = self.link.callback(move |response: Response<Text>| {
+            Msg::ReceiveDeleteEntryResponse(meal_plan_uuid.clone(), response.status().is_success())
+        });
+
+        FetchService::fetch(request, callback).expect("failed to start delete request")
+    }
+
+    fn build_post_shopping_list_task(&self) -> FetchTask {
+        let post_request = Request::post(format!(
+            "http://localhost:8080/meal-plan/shopping-list?{}",
+            MealPlanComp::week_query(self.week_start)
+        ))
+        .body(Nothing)
+        .expect("Could not build that request.");
+
+        let callback = self.link.callback(
+            |response: Response<Json<Result<CreateShoppingListResponse, anyhow::Error>>>| {
+                let Json(data) = response.into_body();
+                Msg::ReceiveShoppingListResponse(data)
+            },
+        );
+
+        FetchService::fetch(post_request, callback).expect("failed to start post request")
+    }
+
+    fn build_fetch_prep_schedule_task(&self) -> FetchTask {
+        let request = Request::get(format!(
+            "http://localhost:8080/meal-plan/prep-schedule?{}",
+            MealPlanComp::week_query(self.week_start)
+        ))
+        .body(Nothing)
+        .expect("Could not build request.");
+
+        let callback = self.link.callback(
+            |response: Response<Json<Result<Vec<PrepTask>, anyhow::Error>>>| {
+                let Json(data) = response.into_body();
+                Msg::ReceivePrepScheduleResponse(data)
+            },
+        );
+
+        FetchService::fetch(request, callback).expect("failed to start request")
+    }
+
+    fn recipe_name(&self, recipe_uuid: &str) -> String {
+        self.recipes
+            .iter()
+            .flatten()
+            .find(|r| r.recipe_uuid == recipe_uuid)
+            .and_then(|r| r.recipe_name.clone())
+            .unwrap_or_else(|| "Recipe".to_string())
+    }
+
+    fn view_week(&self) -> Html {
+        let entries = match &self.entries {
+            Some(entries) => entries,
+            None => return html! {},
+        };
+        let days: Vec<NaiveDate> = (0..7)
+            .map(|day| self.week_start + Duration::days(day))
+            .collect();
+
+        html! {
+            <table class="ui celled fixed table">
+                <thead>
+                    <tr>
+                        <th></th>
+                        { for days.iter().map(|day| html! {
+                            <th>{ day.format("%a %d.%m.").to_string() }</th>
+                        }) }
+                    </tr>
+                </thead>
+                <tbody>
+                    { for MealSlot::ALL.iter().map(|slot| html! {
+                        <tr>
+                            <td>{ slot.to_string() }</td>
+                            { for days.iter().map(|day| html! {
+                                <td>
+                                    { for entries
+                                        .iter()
+                                        .filter(|e| e.date == *day && e.meal_slot == *slot)
+                                        .map(|e| self.view_entry(e)) }
+                                </td>
+                            }) }
+                        </tr>
+                    }) }
+                </tbody>
+            </table>
+        }
+    }
+
+    fn view_entry(&self, entry: &MealPlanEntry) -> Html {
+        let meal_plan_uuid = entry.meal_plan_uuid.clone();
+
+        html! {
+            <div>
+                <a href={ format!("/recipes/{}", entry.recipe_uuid) }>
+                    { self.recipe_name(&entry.recipe_uuid) }
+                </a>
+                { format!(" ({})", entry.servings) }
+                <i
+                    class="close icon",
+                    onclick=self.link.callback(move |_| Msg::OnDeleteEntry(meal_plan_uuid.clone()))
+                    ></i>
+            </div>
+        }
+    }
+
+    fn view_prep_schedule(&self) -> Html {
+        let tasks = match &self.prep_schedule {
+            Some(tasks) => tasks,
+            None => return html! {},
+        };
+
+        html! {<>
+            <h3>{"Prep Schedule"}</h3>
+            <div class="ui list">
+                { for tasks.iter().map(|task| html! {
+                    <div class="item">
+                        <b>{ task.start.format("%a %d.%m. %H:%M").to_string() }</b>
+                        { format!(
+                            " start {} for {} on {}",
+                            task.recipe_name.as_deref().unwrap_or("Recipe"),
+                            task.meal_slot,
+                            task.meal_date.format("%a %d.%m.")
+                        ) }
+                        {
+                            match &task.total_time {
+                                Some(total_time) => format!(" (takes {})", total_time),
+                                None => "".to_string(),
+                            }
+                        }
+                    </div>
+                }) }
+            </div>
+        </>}
+    }
+
+    fn view_new_entry(&self) -> Html {
+        let recipes = match &self.recipes {
+            Some(recipes) => recipes,
+            None => return html! {},
+        };
+
+        html! {<>
+            <h2>{"Plan a Meal"}</h2>
+            <div class="ui form">
+                <div class="four fields">
+                    <div class="field">
+                        <label>{"Date"}</label>
+                        <input
+                            type="date",
+                            value=self.new_entry.date.to_string(),
+                            oninput=self.link.callback(|e: InputData| Msg::OnDateInputChanged(e.value))
+                            />
+                    </div>
+                    <div class="field">
+                        <label>{"Meal"}</label>
+                        <select
+                            onchange=self.link.callback(|e: ChangeData| Msg::OnMealSlotSelectChanged(match e {
+                                ChangeData::Select(sel_element) => sel_element.value(),
+                                _ => "".to_string(),
+                            }))
+                            >
+                            { for MealSlot::ALL.iter().map(|slot| html! {
+                                <option value=slot.to_string() selected={*slot == self.new_entry.meal_slot}>
+                                    { slot.to_string() }
+                                </option>
+                            }) }
+                        </select>
+                    </div>
+                    <div class="field">
+                        <label>{"Recipe"}</label>
+                        <select
+                            onchange=self.link.callback(|e: ChangeData| Msg::OnRecipeSelectChanged(match e {
+                                ChangeData::Select(sel_element) => sel_element.value(),
+                                _ => "".to_string(),
+                            }))
+                            >
+                            { for recipes.iter().map(|recipe| html! {
+                                <option
+                                    value=&recipe.recipe_uuid
+                                    selected={recipe.recipe_uuid == self.new_entry.recipe_uuid}>
+                                    { recipe.recipe_name.as_deref().unwrap_or("") }
+                                </option>
+                            }) }
+                        </select>
+                    </div>
+                    <div class="field">
+                        <label>{"Servings"}</label>
+                        <input
+                            type="number",
+                            value=&self.new_entry.servings,
+                            oninput=self.link.callback(|e: InputData| Msg::OnServingsInputChanged(e.value))
+                            />
+                    </div>
+                </div>
+                {
+                    match &self.error_msg {
+                        Some(error) => html! { <div class="ui negative message">{ error }</div> },
+                        None => html! {},
+                    }
+                }
+                <button class="ui button" type="submit" onclick=self.link.callback(|_| Msg::OnAddEntry)>
+                    { "Add" }
+                </button>
+            </div>
+        </>}
+    }
+}
@@ -1,50 +1,48 @@
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use yew::agent::{Dispatched, Dispatcher};
 use yew::callback::Callback;
+use yew::events::ChangeData;
 use yew::format::{Json, Nothing};
 use yew::prelude::*;
 use yew::services::fetch::{FetchService, FetchTask, Request, Response};
 
+use rcp_shared_rs_code::allergens::RecipeAllergens;
+use rcp_shared_rs_code::costing;
+use rcp_shared_rs_code::diets::RecipeDiets;
+use rcp_shared_rs_code::formats::schema_org;
 use rcp_shared_rs_code::models::book_source::BookSource;
+use rcp_shared_rs_code::models::duration::Duration;
 use rcp_shared_rs_code::models::haccp_value::HACCPValue;
 use rcp_shared_rs_code::models::ingredient::Ingredient;
 use rcp_shared_rs_code::models::oven_fan_value::OvenFanValue;
+use rcp_shared_rs_code::models::price_item::PriceItem;
 use rcp_shared_rs_code::models::r#yield::Yield;
 use rcp_shared_rs_code::models::step::Step;
 use rcp_shared_rs_code::models::temperature::Temperature;
 use rcp_shared_rs_code::models::temperature_unit::TemperatureUnit;
+use rcp_shared_rs_code::models::unit_system::UnitSystem;
+use rcp_shared_rs_code::nutrition::{Nutrients, RecipeNutrition};
 
 use rcp_shared_rs_code::models::recipe::Recipe;
 
 use crate::app::{RouteServiceType, RouteType};
 use crate::reroute_agent::{RerouteAgent, RerouteRequestMsg};
 
-// Struct for making add recipe requests
-#[derive(Serialize, Deserialize, Debug)]
-pub struct RecipeRequest {
-    pub recipe_name: Option<String>,
-    pub oven_time: Option<f64>,
-    pub notes: Option<String>,
-    pub oven_fan: Option<OvenFanValue>,
-}
-
-impl RecipeRequest {
-    pub fn valid(&self) -> bool {
-        self.recipe_name_valid()
-    }
-
-    fn recipe_name_valid(&self) -> bool {
-        match &self.recipe_name {
-            None => false,
-            Some(name) => name.chars().count() >= 4,
-        }
-    }
-}
-
 pub enum Msg {
     GetRecipe,
     ReceiveFetchRecipeResponse(Result<Recipe, anyhow::Error>),
     BackToAllRecipes,
+    OnUnitSystemSelectChanged(String),
+    OnInGramsToggled,
+    // (yield index as string)
+    OnYieldSelectChanged(String),
+    OnTemperatureUnitSelectChanged(String),
+    ReceiveFetchNutritionResponse(Result<RecipeNutrition, anyhow::Error>),
+    ReceiveFetchAllergensResponse(Result<RecipeAllergens, anyhow::Error>),
+    ReceiveFetchDietsResponse(Result<RecipeDiets, anyhow::Error>),
+    ReceiveFetchPricesResponse(Result<Vec<PriceItem>, anyhow::Error>),
+    OnSellingPriceInputChanged(String),
 }
 
 #[derive(PartialEq, Clone, Properties)]
@@ -58,6 +56,29 @@ pub struct RecipeComp {
     model: Recipe,
     fetch_recipe_task: Option<FetchTask>,
     fetch_error_msg: Option<String>,
+    // None shows amounts in the units the recipe was written in
+    unit_system: Option<UnitSystem>,
+    // Shows the recipe from "recipe/{id}?grams=true", with weights and the volumes of ingredients
+    // with a known density in grams, instead of the unit system
+    in_grams: bool,
+    // Which entry of the recipe's yields (and therefore of every ingredient's amounts) is shown
+    selected_yield_idx: usize,
+    // None shows the oven temperature as written
+    temperature_unit: Option<TemperatureUnit>,
+    // From "recipe/{id}/nutrition", for the selected yield
+    nutrition: Option<RecipeNutrition>,
+    fetch_nutrition_task: Option<FetchTask>,
+    // From "recipe/{id}/allergens"
+    allergens: Option<RecipeAllergens>,
+    fetch_allergens_task: Option<FetchTask>,
+    // From "recipe/{id}/diets"
+    diets: Option<RecipeDiets>,
+    fetch_diets_task: Option<FetchTask>,
+    // The price list from "price", the cost is calculated here
+    prices: Vec<PriceItem>,
+    fetch_prices_task: Option<FetchTask>,
+    // Per yield unit, for the food cost percentage
+    selling_price: Option<f64>,
 }
 
 impl Component for RecipeComp {
@@ -76,9 +97,26 @@ impl Component for RecipeComp {
             fetch_recipe_task: None,
             fetch_error_msg: None,
             reroute_agent: RerouteAgent::dispatcher(),
+            unit_system: None,
+            in_grams: false,
+            selected_yield_idx: 0,
+            temperature_unit: None,
+            nutrition: None,
+            fetch_nutrition_task: None,
+            allergens: None,
+            fetch_allergens_task: None,
+            diets: None,
+            fetch_diets_task: None,
+            prices: vec![],
+            fetch_prices_task: None,
+            selling_price: None,
         };
 
         recipe_comp.fetch_recipe();
+        recipe_comp.fetch_nutrition();
+        recipe_comp.fetch_allergens();
+        recipe_comp.fetch_diets();
+        recipe_comp.fetch_prices();
 
         recipe_comp
     }
@@ -118,6 +156,50 @@ impl Component for RecipeComp {
 
                 false
             }
+            Msg::OnUnitSystemSelectChanged(unit_system) => {
+                self.unit_system = UnitSystem::from_str(&unit_system).ok();
+                true
+            }
+            Msg::OnInGramsToggled => {
+                self.in_grams = !self.in_grams;
+                self.fetch_recipe();
+                true
+            }
+            Msg::OnYieldSelectChanged(idx_str) => {
+                self.selected_yield_idx = idx_str.parse().unwrap_or(0);
+                self.fetch_nutrition();
+                true
+            }
+            Msg::OnTemperatureUnitSelectChanged(unit_str) => {
+                self.temperature_unit = TemperatureUnit::from_str(&unit_str).ok();
+                true
+            }
+            Msg::ReceiveFetchNutritionResponse(response) => {
+                // Without nutrition it is left out, the recipe is still shown
+                self.nutrition = response.ok();
+                self.fetch_nutrition_task = None;
+                true
+            }
+            Msg::ReceiveFetchAllergensResponse(response) => {
+                self.allergens = response.ok();
+                self.fetch_allergens_task = None;
+                true
+            }
+            Msg::ReceiveFetchDietsResponse(response) => {
+                self.diets = response.ok();
+                self.fetch_diets_task = None;
+                true
+            }
+            Msg::ReceiveFetchPricesResponse(response) => {
+                // Without a price list the cost is left out, the recipe is still shown
+                self.prices = response.unwrap_or_default();
+                self.fetch_prices_task = None;
+                true
+            }
+            Msg::OnSellingPriceInputChanged(price_str) => {
+                self.selling_price = price_str.trim().parse().ok();
+                true
+            }
         }
     }
 
@@ -133,26 +215,43 @@ impl Component for RecipeComp {
 
 
             { self.view_notes() }
+            { self.view_diets() }
+
+            { self.view_times() }
 
             <h3 class="ui header">{"Oven"}</h3>
+            { self.view_oven_temp() }
             { self.view_oven_fan() }
             { self.view_oven_time() }
 
+            { self.view_yields() }
             { self.view_ingredients() }
+            { self.view_allergens() }
+            { self.view_equipment() }
 
-            { self.view_yields() }
             { self.view_steps() }
 
+            { self.view_nutrition() }
+            { self.view_cost() }
+
+            { self.view_json_ld() }
         </>}
     }
 }
 
 impl RecipeComp {
-    fn build_fetch_recipe_task(recipe_uuid: &str, link: &ComponentLink<Self>) -> FetchTask {
+    fn build_fetch_recipe_task(
+        recipe_uuid: &str,
+        in_grams: bool,
+        link: &ComponentLink<Self>,
+    ) -> FetchTask {
         // 1. build the request
-        let request = Request::get(format!("http://localhost:8080/recipe/{}", &recipe_uuid))
-            .body(Nothing)
-            .expect("Could not build request.");
+        let request = Request::get(format!(
+            "http://localhost:8080/recipe/{}?grams={}",
+            &recipe_uuid, in_grams
+        ))
+        .body(Nothing)
+        .expect("Could not build request.");
 
         // 2. construct a callback
         let callback = link.callback(|response: Response<Json<Result<Recipe, anyhow::Error>>>| {
@@ -166,10 +265,110 @@ impl RecipeComp {
         task
     }
 
+    fn build_fetch_nutrition_task(
+        recipe_uuid: &str,
+        yield_idx: usize,
+        link: &ComponentLink<Self>,
+    ) -> FetchTask {
+        let request = Request::get(format!(
+            "http://localhost:8080/recipe/{}/nutrition?yield_idx={}",
+            recipe_uuid, yield_idx
+        ))
+        .body(Nothing)
+        .expect("Could not build request.");
+
+        let callback = link.callback(
+            |response: Response<Json<Result<RecipeNutrition, anyhow::Error>>>| {
+                let Json(data) = response.into_body();
+                Msg::ReceiveFetchNutritionResponse(data)
+            },
+        );
+
+        FetchService::fetch(request, callback).expect("failed to start request")
+    }
+
+    fn fetch_nutrition(&mut self) {
+        self.fetch_nutrition_task = Some(RecipeComp::build_fetch_nutrition_task(
+            &self.model.recipe_uuid,
+            self.selected_yield_idx,
+            &self.link,
+        ));
+    }
+
+    fn build_fetch_allergens_task(recipe_uuid: &str, link: &ComponentLink<Self>) -> FetchTask {
+        let request = Request::get(format!(
+            "http://localhost:8080/recipe/{}/allergens",
+            recipe_uuid
+        ))
+        .body(Nothing)
+        .expect("Could not build request.");
+
+        let callback = link.callback(
+            |response: Response<Json<Result<RecipeAllergens, anyhow::Error>>>| {
+                let Json(data) = response.into_body();
+                Msg::ReceiveFetchAllergensResponse(data)
+            },
+        );
+
+        FetchService::fetch(request, callback).expect("failed to start request")
+    }
+
+    fn fetch_allergens(&mut self) {
+        self.fetch_allergens_task = Some(RecipeComp::build_fetch_allergens_task(
+            &self.model.recipe_uuid,
+            &self.link,
+        ));
+    }
+
+    fn build_fetch_diets_task(recipe_uuid: &str, link: &ComponentLink<Self>) -> FetchTask {
+        let request = Request::get(format!(
+            "http://localhost:8080/recipe/{}/diets",
+            recipe_uuid
+        ))
+        .body(Nothing)
+        .expect("Could not build request.");
+
+        let callback = link.callback(
+            |response: Response<Json<Result<RecipeDiets, anyhow::Error>>>| {
+                let Json(data) = response.into_body();
+                Msg::ReceiveFetchDietsResponse(data)
+            },
+        );
+
+        FetchService::fetch(request, callback).expect("failed to start request")
+    }
+
+    fn fetch_diets(&mut self) {
+        self.fetch_diets_task = Some(RecipeComp::build_fetch_diets_task(
+            &self.model.recipe_uuid,
+            &self.link,
+        ));
+    }
+
+    fn build_fetch_prices_task(link: &ComponentLink<Self>) -> FetchTask {
+        let request = Request::get("http://localhost:8080/price")
+            .body(Nothing)
+            .expect("Could not build request.");
+
+        let callback = link.callback(
+            |response: Response<Json<Result<Vec<PriceItem>, anyhow::Error>>>| {
+                let Json(data) = response.into_body();
+                Msg::ReceiveFetchPricesResponse(data)
+            },
+        );
+
+        FetchService::fetch(request, callback).expect("failed to start request")
+    }
+
+    fn fetch_prices(&mut self) {
+        self.fetch_prices_task = Some(RecipeComp::build_fetch_prices_task(&self.link));
+    }
+
     fn fetch_recipe(&mut self) {
         // 4. store the task so it isn't canceled immediately
         self.fetch_recipe_task = Some(RecipeComp::build_fetch_recipe_task(
             &self.model.recipe_uuid,
+            self.in_grams,
             &self.link,
         ));
     }
@@ -182,6 +381,19 @@ impl RecipeComp {
         }
     }
 
+    // schema.org Recipe for search engines and recipe managers reading the page
+    fn view_json_ld(&self) -> Html {
+        if self.fetch_recipe_task.is_some() || self.model.recipe_name.is_none() {
+            return html! {};
+        }
+
+        html! {
+            <script type="application/ld+json">
+                { schema_org::to_schema_org_json(&self.model) }
+            </script>
+        }
+    }
+
     fn view_error(&self) -> Html {
         if let Some(ref error) = self.fetch_error_msg {
             html! { <p>{ error.clone() }</p> }
@@ -213,6 +425,10 @@ impl RecipeComp {
 
     fn view_yields(&self) -> Html {
         self.model.yields.as_ref().map_or(html! {}, |yields| {
+            if yields.is_empty() {
+                return html! {};
+            }
+
             let yields_html = html! {
                 for yields
                     .iter()
@@ -221,21 +437,65 @@ impl RecipeComp {
             };
             html! {<>
                 <h3 class="ui header">{"Yields"}</h3>
-                <ul>
-                    { yields_html }
-                </ul>
+                <div class="ui form">
+                    <div class="inline field">
+                        <select
+                            id="yield_select",
+                            value=self.selected_yield_idx.to_string(),
+                            onchange=self.link.callback(|e: ChangeData| Msg::OnYieldSelectChanged(match e {
+                                ChangeData::Select(sel_element) => sel_element.value(),
+                                _ => "0".to_string(),
+                            }))
+                            >
+                            { yields_html }
+                        </select>
+                    </div>
+                </div>
             </>}
         })
     }
 
     fn view_yield(&self, (idx, r#yield): (usize, &Yield)) -> Html {
         html! {
-            <li>
+            <option value=idx.to_string() selected={idx == self.selected_yield_idx}>
                 { format!("{} {}", r#yield.amount, r#yield.unit) }
-            </li>
+            </option>
         }
     }
 
+    fn view_oven_temp(&self) -> Html {
+        self.model.oven_temp.as_ref().map_or(html! {}, |temp| {
+            let shown = match &self.temperature_unit {
+                Some(unit) => temp.to_unit(unit),
+                None => temp.clone(),
+            };
+
+            html! {
+                <div class="ui form">
+                    <div class="inline fields">
+                        <div class="field">
+                            <p>{ format!("Temperature: {}", shown) }</p>
+                        </div>
+                        <div class="field">
+                            <select
+                                id="temperature_unit_select",
+                                onchange=self.link.callback(|e: ChangeData| Msg::OnTemperatureUnitSelectChanged(match e {
+                                    ChangeData::Select(sel_element) => sel_element.value(),
+                                    _ => "".to_string(),
+                                }))
+                                >
+                                <option value="" selected={self.temperature_unit.is_none()}>{"As written"}</option>
+                                <option value="Celsius" selected={self.temperature_unit == Some(TemperatureUnit::Celsius)}>{"°C"}</option>
+                                <option value="Fahrenheit" selected={self.temperature_unit == Some(TemperatureUnit::Fahrenheit)}>{"°F"}</option>
+                                <option value="Gas Mark" selected={self.temperature_unit == Some(TemperatureUnit::GasMark)}>{"Gas Mark"}</option>
+                            </select>
+                        </div>
+                    </div>
+                </div>
+            }
+        })
+    }
+
     fn view_oven_fan(&self) -> Html {
         self.model.oven_fan.as_ref().map_or(html! {}, |of| {
             html! {
@@ -247,11 +507,36 @@ impl RecipeComp {
     fn view_oven_time(&self) -> Html {
         self.model.oven_time.as_ref().map_or(html! {}, |ot| {
             html! {
-                <p>{ format!("Total time: {}", ot) }</p>
+                <p>{ format!("Time: {}", ot) }</p>
             }
         })
     }
 
+    fn view_times(&self) -> Html {
+        let times = [
+            ("Prep", self.model.prep_time),
+            ("Cook", self.model.cook_time),
+            ("Rest", self.model.rest_time),
+            ("Total", self.model.effective_total_time()),
+        ];
+        if times.iter().all(|(_, time)| time.is_none()) {
+            return html! {};
+        }
+
+        html! {
+            <div class="ui horizontal list">
+                { for times.iter().filter_map(|(label, time)| time.map(|time| html! {
+                    <div class="item">
+                        <div class="content">
+                            <div class="header">{ label }</div>
+                            { time.to_string() }
+                        </div>
+                    </div>
+                })) }
+            </div>
+        }
+    }
+
     fn view_steps(&self) -> Html {
         self.model.steps.as_ref().map_or(html! {}, |steps| {
             if steps.is_empty() {
@@ -274,8 +559,211 @@ impl RecipeComp {
         })
     }
 
+    fn view_equipment(&self) -> Html {
+        self.model.equipment.as_ref().map_or(html! {}, |equipment| {
+            if equipment.is_empty() {
+                html! {}
+            } else {
+                html! {<>
+                    <h3 class="ui header">{"Equipment"}</h3>
+                    <div class="ui bulleted list">
+                        { for equipment.iter().map(|name| html! { <div class="item">{ name }</div> }) }
+                    </div>
+                </>}
+            }
+        })
+    }
+
+    fn view_diets(&self) -> Html {
+        let result = match &self.diets {
+            Some(result) => result,
+            None => return html! {},
+        };
+        html! {
+            <div>
+                { for result.diets.iter().map(|diet| html! {
+                    <div class="ui green label">{ diet.to_string() }</div>
+                }) }
+            </div>
+        }
+    }
+
+    fn view_allergens(&self) -> Html {
+        let result = match &self.allergens {
+            Some(result) if !result.contains.is_empty() || !result.may_contain.is_empty() => result,
+            _ => return html! {},
+        };
+
+        html! {<>
+            <h3 class="ui header">{"Allergens"}</h3>
+            <div>
+                { for result.contains.iter().map(|a| html! {
+                    <div class="ui red label">{ a.to_string() }</div>
+                }) }
+                { for result.may_contain.iter().map(|a| html! {
+                    <div class="ui basic label">{ format!("{} (substitution)", a) }</div>
+                }) }
+            </div>
+        </>}
+    }
+
+    fn view_nutrition(&self) -> Html {
+        let result = match &self.nutrition {
+            Some(result) if !result.ingredients.is_empty() => result,
+            _ => return html! {},
+        };
+
+        let rows: [(&str, fn(&Nutrients) -> f64, &str); 7] = [
+            ("Energy", |n| n.energy_kcal, "kcal"),
+            ("Protein", |n| n.protein_g, "g"),
+            ("Fat", |n| n.fat_g, "g"),
+            ("Carbohydrates", |n| n.carbohydrates_g, "g"),
+            ("Fiber", |n| n.fiber_g, "g"),
+            ("Sugar", |n| n.sugar_g, "g"),
+            ("Sodium", |n| n.sodium_mg, "mg"),
+        ];
+        let cell = |nutrients: Option<&Nutrients>, value: fn(&Nutrients) -> f64, unit: &str| {
+            nutrients.map_or("".to_string(), |n| format!("{:.0} {}", value(n), unit))
+        };
+
+        html! {<>
+            <h3 class="ui header">{"Nutrition"}</h3>
+            <table class="ui very basic collapsing celled table">
+                <thead>
+                    <tr>
+                        <th></th>
+                        <th>{"Total"}</th>
+                        {
+                            if result.per_serving.is_some() {
+                                html! { <th>{"Per serving"}</th> }
+                            } else { html! {} }
+                        }
+                    </tr>
+                </thead>
+                <tbody>
+                    { for rows.iter().map(|(label, value, unit)| html! {
+                        <tr>
+                            <td>{ label }</td>
+                            <td>{ cell(Some(&result.total), *value, unit) }</td>
+                            {
+                                if result.per_serving.is_some() {
+                                    html! { <td>{ cell(result.per_serving.as_ref(), *value, unit) }</td> }
+                                } else { html! {} }
+                            }
+                        </tr>
+                    }) }
+                </tbody>
+            </table>
+            {
+                if result.missing.is_empty() {
+                    html! {}
+                } else {
+                    html! {
+                        <div class="ui small warning message">
+                            <p>{"Not counted:"}</p>
+                            <div class="ui bulleted list">
+                                { for result.missing.iter().map(|m| html! {
+                                    <div class="item">{ format!("{}: {}", m.ingredient_name, m.reason) }</div>
+                                }) }
+                            </div>
+                        </div>
+                    }
+                }
+            }
+        </>}
+    }
+
+    fn view_cost(&self) -> Html {
+        let result = costing::recipe_cost(
+            &self.model,
+            self.selected_yield_idx,
+            &self.prices,
+            self.selling_price,
+        );
+        if result.ingredients.is_empty() {
+            return html! {};
+        }
+
+        let yield_unit = result.yield_unit.clone().unwrap_or_default();
+        html! {<>
+            <h3 class="ui header">{"Cost"}</h3>
+            <table class="ui very basic collapsing celled table">
+                <tbody>
+                    { for result.ingredients.iter().map(|i| html! {
+                        <tr>
+                            <td>{ &i.ingredient_name }</td>
+                            <td>{ format!("{:.2}", i.cost) }</td>
+                        </tr>
+                    }) }
+                    <tr>
+                        <td><b>{"Total"}</b></td>
+                        <td><b>{ format!("{:.2}", result.total) }</b></td>
+                    </tr>
+                    {
+                        match result.per_yield_unit {
+                            Some(cost) => html! {
+                                <tr>
+                                    <td>{ format!("Per {}", yield_unit) }</td>
+                                    <td>{ format!("{:.2}", cost) }</td>
+                                </tr>
+                            },
+                            None => html! {},
+                        }
+                    }
+                </tbody>
+            </table>
+            {
+                if result.per_yield_unit.is_some() {
+                    html! {
+                        <div class="ui form">
+                            <div class="inline field">
+                                <label>{ format!("Selling price per {}", yield_unit) }</label>
+                                <input
+                                    type="number",
+                                    oninput=self.link.callback(|e: InputData| Msg::OnSellingPriceInputChanged(e.value))
+                                    />
+                                {
+                                    match result.food_cost_percent {
+                                        Some(percent) => html! {
+                                            <div class="ui label">{ format!("Food cost {:.1} %", percent) }</div>
+                                        },
+                                        None => html! {},
+                                    }
+                                }
+                            </div>
+                        </div>
+                    }
+                } else { html! {} }
+            }
+            {
+                if result.missing.is_empty() {
+                    html! {}
+                } else {
+                    html! {
+                        <div class="ui small warning message">
+                            <p>{"Not counted:"}</p>
+                            <div class="ui bulleted list">
+                                { for result.missing.iter().map(|m| html! {
+                                    <div class="item">{ format!("{}: {}", m.ingredient_name, m.reason) }</div>
+                                }) }
+                            </div>
+                        </div>
+                    }
+                }
+            }
+        </>}
+    }
+
     fn view_step(&self, (idx, step): (usize, &Step)) -> Html {
         let notes_html = step.notes.as_ref().map_or(html! {}, |s| html! {{s}});
+        let timers_html = html! {
+            for step.timers.iter().map(|timer| html! {
+                <div class="ui label">
+                    <i class="clock outline icon"></i>
+                    { timer.name.as_ref().map_or(timer.to_string(), |name| format!("{}: {}", name, timer)) }
+                </div>
+            })
+        };
 
         html! {
             <div class="ui segment">
@@ -284,6 +772,7 @@ impl RecipeComp {
                 </div>
                 <div class="content">
                     <p>{ &step.step }</p>
+                    { timers_html }
                     <p><em>{ notes_html }</em></p>
                 </div>
             </div>
@@ -307,6 +796,7 @@ impl RecipeComp {
 
                     html! {<>
                         <h3 class="ui header">{"Ingredients"}</h3>
+                        { self.view_unit_system_select() }
                         <div class="ui celled list">
                             { ingredients_html }
                         </div>
@@ -315,9 +805,48 @@ impl RecipeComp {
             })
     }
 
+    fn view_unit_system_select(&self) -> Html {
+        html! {
+            <div class="ui form">
+                <div class="inline field">
+                    <label for="unit_system_select">{"Units"}</label>
+                    <select
+                        id="unit_system_select",
+                        value=self.unit_system.map_or("".to_string(), |us| us.to_string()),
+                        onchange=self.link.callback(|e: ChangeData| Msg::OnUnitSystemSelectChanged(match e {
+                            ChangeData::Select(sel_element) => sel_element.value(),
+                            _ => "".to_string(),
+                        }))
+                        >
+                        <option value="">{"As written"}</option>
+                        <option value="Metric">{"Metric"}</option>
+                        <option value="Imperial">{"Imperial"}</option>
+                    </select>
+                </div>
+                <div class="inline field">
+                    <div class="ui checkbox">
+                        <input
+                            type="checkbox",
+                            id="in_grams_checkbox",
+                            checked=self.in_grams,
+                            onclick=self.link.callback(|_| Msg::OnInGramsToggled)
+                            />
+                        <label for="in_grams_checkbox">{"Show everything in grams"}</label>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
     fn view_ingredient(&self, (idx, ing): (usize, &Ingredient)) -> Html {
         let cb_id = format!("ingredient-checkbox-{}", idx);
         let i = &ing.ingredient;
+        // The recipe was fetched in grams already
+        let amount_str = match self.unit_system {
+            _ if self.in_grams => i.get_amount_str(self.selected_yield_idx),
+            Some(system) => i.get_amount_str_in(self.selected_yield_idx, system),
+            None => i.get_amount_str(self.selected_yield_idx),
+        };
 
         html! {
             <div class="item">
@@ -326,7 +855,7 @@ impl RecipeComp {
                 //     <label for=&cb_id>{&i.ingredient_name}</label>
                 // </div>
                 <div class="content">
-                    <div class="meta right floated"><span>{ i.get_amount_str(0/*TODO index*/) }</span></div>
+                    <div class="meta right floated"><span>{ amount_str }</span></div>
                     <div class="header left floated">{ &i.ingredient_name }</div>
                     <div class="left floated">
                         {
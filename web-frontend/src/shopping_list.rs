@@ -0,0 +1,239 @@
+use serde_json::json;
+use std::collections::BTreeMap;
+use yew::format::{Json, Nothing, Text};
+use yew::prelude::*;
+use yew::services::fetch::{FetchService, FetchTask, Request, Response};
+
+use rcp_shared_rs_code::models::shopping_list::{ShoppingItem, ShoppingList};
+
+pub enum Msg {
+    ReceiveFetchShoppingListResponse(Result<ShoppingList, anyhow::Error>),
+    // (item index)
+    OnItemToggled(usize),
+    // (item index, whether the list was stored)
+    ReceiveCheckItemResponse(usize, bool),
+}
+
+#[derive(PartialEq, Clone, Properties)]
+pub struct Props {
+    pub shopping_list_uuid: String,
+}
+
+pub struct ShoppingListComp {
+    link: ComponentLink<Self>,
+    shopping_list_uuid: String,
+    model: Option<ShoppingList>,
+    fetch_shopping_list_task: Option<FetchTask>,
+    // One request per item being checked off, so clicking the next item doesn't cancel the last one
+    check_item_tasks: BTreeMap<usize, FetchTask>,
+    error_msg: Option<String>,
+}
+
+impl Component for ShoppingListComp {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let task =
+            ShoppingListComp::build_fetch_shopping_list_task(&props.shopping_list_uuid, &link);
+
+        Self {
+            link,
+            shopping_list_uuid: props.shopping_list_uuid,
+            model: None,
+            fetch_shopping_list_task: Some(task),
+            check_item_tasks: BTreeMap::new(),
+            error_msg: None,
+        }
+    }
+
+    fn change(&mut self, _: Self::Properties) -> bool {
+        false
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::ReceiveFetchShoppingListResponse(response) => {
+                match response {
+                    Ok(list) => {
+                        self.model = Some(list);
+                        self.error_msg = None;
+                    }
+                    Err(error) => {
+                        self.error_msg = Some(error.to_string());
+                    }
+                }
+                self.fetch_shopping_list_task = None;
+                true
+            }
+            Msg::OnItemToggled(idx) => {
+                let checked = match self.item_mut(idx) {
+                    Some(item) => {
+                        item.checked = !item.checked;
+                        item.checked
+                    }
+                    None => return false,
+                };
+                let task = self.build_check_item_task(idx, checked);
+                self.check_item_tasks.insert(idx, task);
+                true
+            }
+            Msg::ReceiveCheckItemResponse(idx, stored) => {
+                self.check_item_tasks.remove(&idx);
+                if !stored {
+                    // Show the item as it is stored
+                    if let Some(item) = self.item_mut(idx) {
+                        item.checked = !item.checked;
+                    }
+                    self.error_msg = Some("Could not save the shopping list".to_string());
+                }
+                true
+            }
+        }
+    }
+
+    fn view(&self) -> Html {
+        html! {<>
+            <br/>
+            { self.view_fetching() }
+            { self.view_error() }
+            { self.view_shopping_list() }
+        </>}
+    }
+}
+
+impl ShoppingListComp {
+    fn build_fetch_shopping_list_task(
+        shopping_list_uuid: &str,
+        link: &ComponentLink<Self>,
+    ) -> FetchTask {
+        let request = Request::get(format!(
+            "http://localhost:8080/shopping-list/{}",
+            shopping_list_uuid
+        ))
+        .body(Nothing)
+        .expect("Could not build request.");
+
+        let callback = link.callback(
+            |response: Response<Json<Result<ShoppingList, anyhow::Error>>>| {
+                let Json(data) = response.into_body();
+                Msg::ReceiveFetchShoppingListResponse(data)
+            },
+        );
+
+        FetchService::fetch(request, callback).expect("failed to start request")
+    }
+
+    fn build_check_item_task(&self, idx: usize, checked: bool) -> FetchTask {
+        let request = Request::put(format!(
+            "http://localhost:8080/shopping-list/{}/items/{}",
+            self.shopping_list_uuid, idx
+        ))
+        .header("content-type", "application/json")
+        .body(Json(&json!({ "checked": checked })))
+        .expect("Could not build that request.");
+
+        let callback = self.link.callback(move |response: Response<Text>| {
+            Msg::ReceiveCheckItemResponse(idx, response.status().is_success())
+        });
+
+        FetchService::fetch(request, callback).expect("failed to start put request")
+    }
+
+    fn item_mut(&mut self, idx: usize) -> Option<&mut ShoppingItem> {
+        self.model.as_mut().and_then(|list| list.items.get_mut(idx))
+    }
+
+    fn view_fetching(&self) -> Html {
+        if self.fetch_shopping_list_task.is_some() {
+            html! {
+                <div class="ui medium text loader active">{ "Loading data..."}</div>
+            }
+        } else {
+            html! {}
+        }
+    }
+
+    fn view_error(&self) -> Html {
+        match &self.error_msg {
+            Some(error) => html! { <div class="ui negative message">{ error }</div> },
+            None => html! {},
+        }
+    }
+
+    fn view_shopping_list(&self) -> Html {
+        let list = match &self.model {
+            Some(list) => list,
+            None => return html! {},
+        };
+
+        // The items are sorted by aisle, see shopping::shopping_items
+        let mut aisles: Vec<(&str, Vec<(usize, &ShoppingItem)>)> = vec![];
+        for (idx, item) in list.items.iter().enumerate() {
+            match aisles.last_mut() {
+                Some((aisle, items)) if *aisle == item.aisle => items.push((idx, item)),
+                _ => aisles.push((&item.aisle, vec![(idx, item)])),
+            }
+        }
+
+        html! {<>
+            <h2 class="ui header">{ list.name.as_deref().unwrap_or("Shopping List") }</h2>
+            <div class="ui horizontal list">
+                { for list.recipes.iter().map(|r| html! {
+                    <a class="item" href={ format!("/recipes/{}", r.recipe_uuid) }>
+                        { format!(
+                            "{} ({} {})",
+                            r.recipe_name.as_deref().unwrap_or("Recipe"),
+                            r.target.amount,
+                            r.target.unit
+                        ) }
+                    </a>
+                }) }
+            </div>
+            { for aisles.iter().map(|(aisle, items)| html! {<>
+                <h3 class="ui header">{ aisle }</h3>
+                <div class="ui list">
+                    { for items.iter().map(|entry| self.view_item(*entry)) }
+                </div>
+            </>}) }
+        </>}
+    }
+
+    fn view_item(&self, (idx, item): (usize, &ShoppingItem)) -> Html {
+        let cb_id = format!("shopping-item-checkbox-{}", idx);
+        let amounts = item
+            .amounts
+            .iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<String>>()
+            .join(" + ");
+        let label = if amounts.is_empty() {
+            item.ingredient_name.clone()
+        } else {
+            format!("{} {}", amounts, item.ingredient_name)
+        };
+
+        html! {
+            <div class="item">
+                <div class="ui checkbox">
+                    <input
+                        type="checkbox",
+                        id=&cb_id,
+                        checked=item.checked,
+                        onclick=self.link.callback(move |_| Msg::OnItemToggled(idx))
+                        />
+                    <label for=&cb_id>
+                        {
+                            if item.checked {
+                                html! { <s>{ label }</s> }
+                            } else {
+                                html! { { label } }
+                            }
+                        }
+                    </label>
+                </div>
+                <div class="description">{ item.recipes.join(", ") }</div>
+            </div>
+        }
+    }
+}
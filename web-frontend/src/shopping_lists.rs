@@ -0,0 +1,316 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use yew::agent::{Dispatched, Dispatcher};
+use yew::format::{Json, Nothing};
+use yew::prelude::*;
+use yew::services::fetch::{FetchService, FetchTask, Request, Response};
+
+use rcp_shared_rs_code::models::r#yield::Yield;
+use rcp_shared_rs_code::models::recipe::Recipe;
+use rcp_shared_rs_code::models::shopping_list::{ShoppingList, ShoppingListRecipe};
+
+use crate::reroute_agent::{RerouteAgent, RerouteRequestMsg};
+
+#[derive(Serialize, Debug)]
+pub struct ShoppingListRequest {
+    pub name: Option<String>,
+    pub recipes: Vec<ShoppingListRecipe>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateShoppingListResponse {
+    pub status: u16,
+    pub shopping_list_uuid: String,
+}
+
+pub enum Msg {
+    ReceiveFetchShoppingListsResponse(Result<Vec<ShoppingList>, anyhow::Error>),
+    ReceiveFetchRecipesResponse(Result<Vec<Recipe>, anyhow::Error>),
+    OnNameInputChanged(String),
+    // (recipe uuid)
+    OnRecipeToggled(String),
+    // (recipe uuid, target amount as string)
+    OnTargetAmountInputChanged(String, String),
+    OnCreateShoppingList,
+    OnReceivePostResponse(Result<CreateShoppingListResponse, anyhow::Error>),
+}
+
+/* The stored shopping lists and a form to make a new one from recipes. */
+pub struct ShoppingListsComp {
+    link: ComponentLink<Self>,
+    reroute_agent: Dispatcher<RerouteAgent>,
+    shopping_lists: Option<Vec<ShoppingList>>,
+    recipes: Option<Vec<Recipe>>,
+    fetch_shopping_lists_task: Option<FetchTask>,
+    fetch_recipes_task: Option<FetchTask>,
+    post_shopping_list_task: Option<FetchTask>,
+    name: String,
+    // The recipes picked for the new list, by uuid, with the yield to buy for
+    targets: BTreeMap<String, Yield>,
+    error_msg: Option<String>,
+}
+
+impl Component for ShoppingListsComp {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let fetch_shopping_lists_task = ShoppingListsComp::build_fetch_shopping_lists_task(&link);
+        let fetch_recipes_task = ShoppingListsComp::build_fetch_recipes_task(&link);
+
+        Self {
+            link,
+            reroute_agent: RerouteAgent::dispatcher(),
+            shopping_lists: None,
+            recipes: None,
+            fetch_shopping_lists_task: Some(fetch_shopping_lists_task),
+            fetch_recipes_task: Some(fetch_recipes_task),
+            post_shopping_list_task: None,
+            name: String::new(),
+            targets: BTreeMap::new(),
+            error_msg: None,
+        }
+    }
+
+    fn change(&mut self, _: Self::Properties) -> bool {
+        false
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::ReceiveFetchShoppingListsResponse(response) => {
+                match response {
+                    Ok(lists) => self.shopping_lists = Some(lists),
+                    Err(error) => self.error_msg = Some(error.to_string()),
+                }
+                self.fetch_shopping_lists_task = None;
+                true
+            }
+            Msg::ReceiveFetchRecipesResponse(response) => {
+                match response {
+                    Ok(recipes) => self.recipes = Some(recipes),
+                    Err(error) => self.error_msg = Some(error.to_string()),
+                }
+                self.fetch_recipes_task = None;
+                true
+            }
+            Msg::OnNameInputChanged(name) => {
+                self.name = name;
+                false
+            }
+            Msg::OnRecipeToggled(recipe_uuid) => {
+                if self.targets.remove(&recipe_uuid).is_none() {
+                    // Start with the yield the recipe is written for
+                    let target = self
+                        .recipes
+                        .iter()
+                        .flatten()
+                        .find(|r| r.recipe_uuid == recipe_uuid)
+                        .and_then(|r| r.yields.as_ref())
+                        .and_then(|yields| yields.first())
+                        .cloned()
+                        .unwrap_or_else(|| Yield {
+                            amount: 4.0,
+                            ..Yield::new()
+                        });
+                    self.targets.insert(recipe_uuid, target);
+                }
+                true
+            }
+            Msg::OnTargetAmountInputChanged(recipe_uuid, amount_str) => {
+                if let (Some(target), Ok(amount)) = (
+                    self.targets.get_mut(&recipe_uuid),
+                    amount_str.trim().parse::<f64>(),
+                ) {
+                    target.amount = amount;
+                }
+                false
+            }
+            Msg::OnCreateShoppingList => {
+                if self.targets.is_empty() {
+                    self.error_msg = Some("Pick at least one recipe".to_string());
+                    return true;
+                }
+                self.error_msg = None;
+                self.post_shopping_list_task = Some(self.build_post_shopping_list_task());
+                true
+            }
+            Msg::OnReceivePostResponse(response) => {
+                self.post_shopping_list_task = None;
+                match response {
+                    Ok(response) => {
+                        let new_route = format!("/shopping-lists/{}", response.shopping_list_uuid);
+                        self.reroute_agent
+                            .send(RerouteRequestMsg::Reroute(new_route));
+                    }
+                    Err(_) => {
+                        self.error_msg = Some("Error creating shopping list".to_string());
+                    }
+                }
+                true
+            }
+        }
+    }
+
+    fn view(&self) -> Html {
+        html! {<>
+            <br/>
+            { self.view_shopping_lists() }
+            { self.view_new_shopping_list() }
+        </>}
+    }
+}
+
+impl ShoppingListsComp {
+    fn build_fetch_shopping_lists_task(link: &ComponentLink<Self>) -> FetchTask {
+        let request = Request::get("http://localhost:8080/shopping-list")
+            .body(Nothing)
+            .expect("Could not build request.");
+
+        let callback = link.callback(
+            |response: Response<Json<Result<Vec<ShoppingList>, anyhow::Error>>>| {
+                let Json(data) = response.into_body();
+                Msg::ReceiveFetchShoppingListsResponse(data)
+            },
+        );
+
+        FetchService::fetch(request, callback).expect("failed to start request")
+    }
+
+    fn build_fetch_recipes_task(link: &ComponentLink<Self>) -> FetchTask {
+        let request = Request::get("http://localhost:8080/recipe")
+            .body(Nothing)
+            .expect("Could not build request.");
+
+        let callback = link.callback(
+            |response: Response<Json<Result<Vec<Recipe>, anyhow::Error>>>| {
+                let Json(data) = response.into_body();
+                Msg::ReceiveFetchRecipesResponse(data)
+            },
+        );
+
+        FetchService::fetch(request, callback).expect("failed to start request")
+    }
+
+    fn build_post_shopping_list_task(&self) -> FetchTask {
+        let body = ShoppingListRequest {
+            name: Some(self.name.clone()).filter(|name| !name.trim().is_empty()),
+            recipes: self
+                .targets
+                .iter()
+                .map(|(recipe_uuid, target)| ShoppingListRecipe {
+                    recipe_uuid: recipe_uuid.clone(),
+                    recipe_name: None,
+                    target: target.clone(),
+                })
+                .collect(),
+        };
+
+        let post_request = Request::post("http://localhost:8080/shopping-list")
+            .header("content-type", "application/json")
+            .body(Json(&body))
+            .expect("Could not build that request.");
+
+        let callback = self.link.callback(
+            |response: Response<Json<Result<CreateShoppingListResponse, anyhow::Error>>>| {
+                let Json(data) = response.into_body();
+                Msg::OnReceivePostResponse(data)
+            },
+        );
+
+        FetchService::fetch(post_request, callback).expect("failed to start post request")
+    }
+
+    fn view_shopping_lists(&self) -> Html {
+        let lists = match &self.shopping_lists {
+            Some(lists) if !lists.is_empty() => lists,
+            _ => return html! {},
+        };
+
+        html! {<>
+            <h2>{"Shopping Lists"}</h2>
+            <div class="ui list">
+                { for lists.iter().map(|list| html! {
+                    <a class="item" href={ format!("/shopping-lists/{}", list.shopping_list_uuid) }>
+                        { list.name.as_deref().unwrap_or("Shopping List") }
+                        { format!(
+                            " ({} of {} items checked)",
+                            list.items.iter().filter(|item| item.checked).count(),
+                            list.items.len()
+                        ) }
+                    </a>
+                }) }
+            </div>
+        </>}
+    }
+
+    fn view_new_shopping_list(&self) -> Html {
+        let recipes = match &self.recipes {
+            Some(recipes) => recipes,
+            None => return html! {},
+        };
+
+        html! {<>
+            <h2>{"New Shopping List"}</h2>
+            <div class="ui form">
+                <div class="field">
+                    <label>{"Name"}</label>
+                    <input
+                        type="text",
+                        oninput=self.link.callback(|e: InputData| Msg::OnNameInputChanged(e.value))
+                        />
+                </div>
+                { for recipes.iter().map(|recipe| self.view_recipe_target(recipe)) }
+                {
+                    match &self.error_msg {
+                        Some(error) => html! { <div class="ui negative message">{ error }</div> },
+                        None => html! {},
+                    }
+                }
+                <button class="ui button" type="submit" onclick=self.link.callback(|_| Msg::OnCreateShoppingList)>
+                    { "Create" }
+                </button>
+            </div>
+        </>}
+    }
+
+    fn view_recipe_target(&self, recipe: &Recipe) -> Html {
+        let cb_id = format!("recipe-checkbox-{}", recipe.recipe_uuid);
+        let toggled = recipe.recipe_uuid.clone();
+        let target = self.targets.get(&recipe.recipe_uuid);
+
+        html! {
+            <div class="inline fields">
+                <div class="field">
+                    <div class="ui checkbox">
+                        <input
+                            type="checkbox",
+                            id=&cb_id,
+                            checked=target.is_some(),
+                            onclick=self.link.callback(move |_| Msg::OnRecipeToggled(toggled.clone()))
+                            />
+                        <label for=&cb_id>{ recipe.recipe_name.as_deref().unwrap_or("") }</label>
+                    </div>
+                </div>
+                {
+                    match target {
+                        Some(target) => {
+                            let recipe_uuid = recipe.recipe_uuid.clone();
+                            html! {
+                                <div class="field">
+                                    <input
+                                        type="number",
+                                        value=&target.amount,
+                                        oninput=self.link.callback(move |e: InputData| Msg::OnTargetAmountInputChanged(recipe_uuid.clone(), e.value))
+                                        />
+                                    <label>{ &target.unit }</label>
+                                </div>
+                            }
+                        }
+                        None => html! {},
+                    }
+                }
+            </div>
+        }
+    }
+}